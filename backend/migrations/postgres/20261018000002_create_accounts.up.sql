@@ -0,0 +1,13 @@
+CREATE TABLE accounts (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(128) NOT NULL,
+    account_type VARCHAR(32) NOT NULL,
+    currency CHAR(3) NOT NULL,
+    balance NUMERIC(28, 8) NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_accounts_user_id ON accounts(user_id);
@@ -0,0 +1,12 @@
+-- user_id 为空表示系统分类
+CREATE TABLE categories (
+    id UUID PRIMARY KEY,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(64) NOT NULL,
+    icon VARCHAR(64) NOT NULL,
+    color VARCHAR(16) NOT NULL,
+    transaction_type VARCHAR(32) NOT NULL,
+    is_system BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX idx_categories_user_id ON categories(user_id);
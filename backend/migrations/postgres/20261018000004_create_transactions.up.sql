@@ -0,0 +1,17 @@
+CREATE TABLE transactions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    category_id UUID REFERENCES categories(id) ON DELETE SET NULL,
+    transaction_type VARCHAR(32) NOT NULL,
+    amount NUMERIC(28, 8) NOT NULL,
+    currency CHAR(3) NOT NULL,
+    description TEXT NOT NULL,
+    notes TEXT,
+    transaction_date TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_transactions_user_date ON transactions(user_id, transaction_date DESC, id DESC);
+CREATE INDEX idx_transactions_account_id ON transactions(account_id);
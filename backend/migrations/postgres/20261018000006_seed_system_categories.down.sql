@@ -0,0 +1,5 @@
+DELETE FROM categories WHERE id IN (
+    '00000000-0000-0000-0000-000000000101',
+    '00000000-0000-0000-0000-000000000102',
+    '00000000-0000-0000-0000-000000000201'
+);
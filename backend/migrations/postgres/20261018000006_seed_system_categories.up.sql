@@ -0,0 +1,4 @@
+INSERT INTO categories (id, user_id, name, icon, color, transaction_type, is_system) VALUES
+    ('00000000-0000-0000-0000-000000000101', NULL, '餐饮', 'restaurant', '#FF9800', 'Expense', TRUE),
+    ('00000000-0000-0000-0000-000000000102', NULL, '交通', 'commute', '#2196F3', 'Expense', TRUE),
+    ('00000000-0000-0000-0000-000000000201', NULL, '工资', 'work', '#4CAF50', 'Income', TRUE);
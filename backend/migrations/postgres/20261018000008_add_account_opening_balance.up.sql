@@ -0,0 +1,12 @@
+-- 期初余额，当前余额 = 期初余额 + 交易流水
+ALTER TABLE accounts ADD COLUMN opening_balance NUMERIC(28, 8) NOT NULL DEFAULT 0;
+
+-- 此前余额不随交易变化，即为创建账户时的期初余额
+UPDATE accounts SET opening_balance = balance;
+
+-- 按已有交易重建当前余额：收入增加，其余类型减少
+UPDATE accounts a SET balance = a.opening_balance + COALESCE((
+    SELECT SUM(CASE WHEN t.transaction_type = 'Income' THEN t.amount ELSE -t.amount END)
+    FROM transactions t
+    WHERE t.account_id = a.id
+), 0);
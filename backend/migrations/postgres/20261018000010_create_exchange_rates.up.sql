@@ -0,0 +1,10 @@
+-- 汇率表：1 单位 base_currency 兑换 rate 单位 quote_currency，按日期保存历史汇率
+CREATE TABLE exchange_rates (
+    base_currency VARCHAR(3) NOT NULL,
+    quote_currency VARCHAR(3) NOT NULL,
+    rate_date DATE NOT NULL,
+    rate NUMERIC(28, 12) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (base_currency, quote_currency, rate_date)
+);
@@ -0,0 +1,6 @@
+-- 子分类通过 parent_id 关联上级分类，最多两级
+-- 归档的分类不能用于新交易，已有交易保留原分类
+ALTER TABLE categories ADD COLUMN parent_id UUID REFERENCES categories(id) ON DELETE SET NULL;
+ALTER TABLE categories ADD COLUMN is_archived BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_categories_parent_id ON categories(parent_id);
@@ -0,0 +1,25 @@
+DELETE FROM categories WHERE id IN (
+    '00000000-0000-0000-0000-000000000103',
+    '00000000-0000-0000-0000-000000000104',
+    '00000000-0000-0000-0000-000000000105',
+    '00000000-0000-0000-0000-000000000106',
+    '00000000-0000-0000-0000-000000000107',
+    '00000000-0000-0000-0000-000000000108',
+    '00000000-0000-0000-0000-000000000109',
+    '00000000-0000-0000-0000-00000000010a',
+    '00000000-0000-0000-0000-0000000001ff',
+    '00000000-0000-0000-0000-000000000202',
+    '00000000-0000-0000-0000-000000000203',
+    '00000000-0000-0000-0000-000000000204',
+    '00000000-0000-0000-0000-000000000205',
+    '00000000-0000-0000-0000-0000000002ff',
+    '00000000-0000-0000-0000-000000000301',
+    '00000000-0000-0000-0000-000000000302',
+    '00000000-0000-0000-0000-000000000303',
+    '00000000-0000-0000-0000-000000000304',
+    '00000000-0000-0000-0000-0000000003ff'
+);
+
+ALTER TABLE categories DROP COLUMN catalog_version;
+
+DROP TABLE IF EXISTS category_translations;
@@ -0,0 +1,80 @@
+-- 系统分类的多语言名称，categories.name 为 zh-CN 名称
+CREATE TABLE category_translations (
+    category_id UUID NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    locale VARCHAR(16) NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    PRIMARY KEY (category_id, locale)
+);
+
+-- 系统分类所属的分类目录版本，用户分类为空
+-- 目录有增改时新增迁移并递增版本，已发布分类的 id 保持不变
+ALTER TABLE categories ADD COLUMN catalog_version INTEGER;
+
+UPDATE categories SET catalog_version = 1 WHERE is_system;
+
+INSERT INTO categories (id, user_id, name, icon, color, transaction_type, is_system, catalog_version) VALUES
+    ('00000000-0000-0000-0000-000000000103', NULL, '购物', 'shopping_bag', '#E91E63', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000104', NULL, '居住', 'home', '#795548', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000105', NULL, '娱乐', 'movie', '#9C27B0', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000106', NULL, '医疗', 'local_hospital', '#F44336', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000107', NULL, '教育', 'school', '#3F51B5', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000108', NULL, '通讯', 'phone_android', '#00BCD4', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000109', NULL, '旅行', 'flight', '#009688', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-00000000010a', NULL, '人情', 'card_giftcard', '#FF5722', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-0000000001ff', NULL, '其他支出', 'more_horiz', '#9E9E9E', 'Expense', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000202', NULL, '奖金', 'emoji_events', '#8BC34A', 'Income', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000203', NULL, '兼职', 'work_outline', '#CDDC39', 'Income', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000204', NULL, '理财收益', 'trending_up', '#FFC107', 'Income', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000205', NULL, '礼金', 'redeem', '#FFEB3B', 'Income', TRUE, 1),
+    ('00000000-0000-0000-0000-0000000002ff', NULL, '其他收入', 'more_horiz', '#9E9E9E', 'Income', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000301', NULL, '股票', 'show_chart', '#3F51B5', 'Investment', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000302', NULL, '基金', 'pie_chart', '#673AB7', 'Investment', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000303', NULL, '债券', 'account_balance', '#607D8B', 'Investment', TRUE, 1),
+    ('00000000-0000-0000-0000-000000000304', NULL, '加密货币', 'currency_bitcoin', '#FFC107', 'Investment', TRUE, 1),
+    ('00000000-0000-0000-0000-0000000003ff', NULL, '其他投资', 'more_horiz', '#9E9E9E', 'Investment', TRUE, 1);
+
+INSERT INTO category_translations (category_id, locale, name) VALUES
+    ('00000000-0000-0000-0000-000000000101', 'zh-CN', '餐饮'),
+    ('00000000-0000-0000-0000-000000000101', 'en', 'Food & Dining'),
+    ('00000000-0000-0000-0000-000000000102', 'zh-CN', '交通'),
+    ('00000000-0000-0000-0000-000000000102', 'en', 'Transportation'),
+    ('00000000-0000-0000-0000-000000000103', 'zh-CN', '购物'),
+    ('00000000-0000-0000-0000-000000000103', 'en', 'Shopping'),
+    ('00000000-0000-0000-0000-000000000104', 'zh-CN', '居住'),
+    ('00000000-0000-0000-0000-000000000104', 'en', 'Housing'),
+    ('00000000-0000-0000-0000-000000000105', 'zh-CN', '娱乐'),
+    ('00000000-0000-0000-0000-000000000105', 'en', 'Entertainment'),
+    ('00000000-0000-0000-0000-000000000106', 'zh-CN', '医疗'),
+    ('00000000-0000-0000-0000-000000000106', 'en', 'Healthcare'),
+    ('00000000-0000-0000-0000-000000000107', 'zh-CN', '教育'),
+    ('00000000-0000-0000-0000-000000000107', 'en', 'Education'),
+    ('00000000-0000-0000-0000-000000000108', 'zh-CN', '通讯'),
+    ('00000000-0000-0000-0000-000000000108', 'en', 'Phone & Internet'),
+    ('00000000-0000-0000-0000-000000000109', 'zh-CN', '旅行'),
+    ('00000000-0000-0000-0000-000000000109', 'en', 'Travel'),
+    ('00000000-0000-0000-0000-00000000010a', 'zh-CN', '人情'),
+    ('00000000-0000-0000-0000-00000000010a', 'en', 'Gifts & Donations'),
+    ('00000000-0000-0000-0000-0000000001ff', 'zh-CN', '其他支出'),
+    ('00000000-0000-0000-0000-0000000001ff', 'en', 'Other Expenses'),
+    ('00000000-0000-0000-0000-000000000201', 'zh-CN', '工资'),
+    ('00000000-0000-0000-0000-000000000201', 'en', 'Salary'),
+    ('00000000-0000-0000-0000-000000000202', 'zh-CN', '奖金'),
+    ('00000000-0000-0000-0000-000000000202', 'en', 'Bonus'),
+    ('00000000-0000-0000-0000-000000000203', 'zh-CN', '兼职'),
+    ('00000000-0000-0000-0000-000000000203', 'en', 'Side Income'),
+    ('00000000-0000-0000-0000-000000000204', 'zh-CN', '理财收益'),
+    ('00000000-0000-0000-0000-000000000204', 'en', 'Investment Income'),
+    ('00000000-0000-0000-0000-000000000205', 'zh-CN', '礼金'),
+    ('00000000-0000-0000-0000-000000000205', 'en', 'Gifts Received'),
+    ('00000000-0000-0000-0000-0000000002ff', 'zh-CN', '其他收入'),
+    ('00000000-0000-0000-0000-0000000002ff', 'en', 'Other Income'),
+    ('00000000-0000-0000-0000-000000000301', 'zh-CN', '股票'),
+    ('00000000-0000-0000-0000-000000000301', 'en', 'Stocks'),
+    ('00000000-0000-0000-0000-000000000302', 'zh-CN', '基金'),
+    ('00000000-0000-0000-0000-000000000302', 'en', 'Funds'),
+    ('00000000-0000-0000-0000-000000000303', 'zh-CN', '债券'),
+    ('00000000-0000-0000-0000-000000000303', 'en', 'Bonds'),
+    ('00000000-0000-0000-0000-000000000304', 'zh-CN', '加密货币'),
+    ('00000000-0000-0000-0000-000000000304', 'en', 'Crypto'),
+    ('00000000-0000-0000-0000-0000000003ff', 'zh-CN', '其他投资'),
+    ('00000000-0000-0000-0000-0000000003ff', 'en', 'Other Investments');
@@ -0,0 +1,19 @@
+-- 预算表：category_id 和 tag 二选一作为统计范围，tag 保存标签名称
+CREATE TABLE budgets (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(128) NOT NULL,
+    category_id UUID REFERENCES categories(id) ON DELETE CASCADE,
+    tag VARCHAR(64),
+    period VARCHAR(16) NOT NULL,
+    amount NUMERIC(28, 8) NOT NULL,
+    currency CHAR(3) NOT NULL,
+    rollover BOOLEAN NOT NULL DEFAULT FALSE,
+    start_date DATE NOT NULL,
+    end_date DATE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    CHECK ((category_id IS NULL) <> (tag IS NULL))
+);
+
+CREATE INDEX idx_budgets_user_id ON budgets(user_id);
@@ -0,0 +1,33 @@
+-- 重复交易：交易模板加重复规则，tags 为 JSON 数组
+-- next_date 为下一次发生的日期，全部发生完毕后为 NULL；occurrence_count 同时用作乐观锁的版本号
+CREATE TABLE recurring_transactions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    category_id UUID REFERENCES categories(id) ON DELETE SET NULL,
+    transaction_type VARCHAR(32) NOT NULL,
+    amount NUMERIC(28, 8) NOT NULL,
+    description TEXT NOT NULL,
+    notes TEXT,
+    tags TEXT NOT NULL DEFAULT '[]',
+    frequency VARCHAR(16) NOT NULL,
+    interval_count INTEGER NOT NULL DEFAULT 1,
+    day_of_month INTEGER,
+    last_business_day BOOLEAN NOT NULL DEFAULT FALSE,
+    start_date DATE NOT NULL,
+    end_date DATE,
+    occurrence_limit INTEGER,
+    next_date DATE,
+    occurrence_count INTEGER NOT NULL DEFAULT 0,
+    is_paused BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_recurring_transactions_user_id ON recurring_transactions(user_id);
+CREATE INDEX idx_recurring_transactions_next_date ON recurring_transactions(next_date);
+
+-- 由重复交易生成的交易，删除重复交易时置空
+ALTER TABLE transactions ADD COLUMN recurring_id UUID;
+
+CREATE INDEX idx_transactions_recurring_id ON transactions(recurring_id);
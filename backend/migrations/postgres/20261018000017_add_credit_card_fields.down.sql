@@ -0,0 +1,2 @@
+ALTER TABLE accounts DROP COLUMN statement_closing_day;
+ALTER TABLE accounts DROP COLUMN credit_limit;
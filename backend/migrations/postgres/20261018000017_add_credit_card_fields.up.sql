@@ -0,0 +1,3 @@
+-- 信用卡的信用额度和每月的账单日
+ALTER TABLE accounts ADD COLUMN credit_limit NUMERIC(28, 8) CHECK (credit_limit > 0);
+ALTER TABLE accounts ADD COLUMN statement_closing_day INTEGER CHECK (statement_closing_day BETWEEN 1 AND 31);
@@ -0,0 +1,19 @@
+-- 信用卡分期，每笔消费最多一个分期
+-- recurring_id 为记入手续费的重复交易，不设外键，提前还清后置空
+CREATE TABLE installment_plans (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    transaction_id UUID NOT NULL UNIQUE REFERENCES transactions(id) ON DELETE CASCADE,
+    principal NUMERIC(28, 8) NOT NULL,
+    periods INTEGER NOT NULL,
+    fee_per_period NUMERIC(28, 8) NOT NULL DEFAULT 0,
+    first_period_date DATE NOT NULL,
+    recurring_id UUID,
+    paid_off_date DATE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_installment_plans_user_id ON installment_plans(user_id);
+CREATE INDEX idx_installment_plans_account_id ON installment_plans(account_id);
@@ -0,0 +1,5 @@
+ALTER TABLE accounts DROP COLUMN loan_repayment_method;
+ALTER TABLE accounts DROP COLUMN loan_first_payment_date;
+ALTER TABLE accounts DROP COLUMN loan_term_months;
+ALTER TABLE accounts DROP COLUMN loan_interest_rate;
+ALTER TABLE accounts DROP COLUMN loan_principal;
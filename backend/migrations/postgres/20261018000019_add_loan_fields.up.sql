@@ -0,0 +1,6 @@
+-- 贷款和借出账户的借款条件，五个字段同时为空或同时有值
+ALTER TABLE accounts ADD COLUMN loan_principal NUMERIC(28, 8) CHECK (loan_principal > 0);
+ALTER TABLE accounts ADD COLUMN loan_interest_rate NUMERIC(28, 8);
+ALTER TABLE accounts ADD COLUMN loan_term_months INTEGER;
+ALTER TABLE accounts ADD COLUMN loan_first_payment_date DATE;
+ALTER TABLE accounts ADD COLUMN loan_repayment_method VARCHAR(32);
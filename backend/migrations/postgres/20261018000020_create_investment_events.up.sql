@@ -0,0 +1,21 @@
+-- 投资事件：买入、卖出、分红和拆股，持仓和成本由事件按时间先后重放得出
+-- 买入和卖出有 quantity、price，分红有 amount，拆股的 quantity 为每一股变为的股数
+CREATE TABLE investment_events (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    symbol VARCHAR(20) NOT NULL,
+    asset_class VARCHAR(16) NOT NULL,
+    event_type VARCHAR(16) NOT NULL,
+    event_date TIMESTAMPTZ NOT NULL,
+    quantity NUMERIC(28, 8),
+    price NUMERIC(28, 8),
+    amount NUMERIC(28, 8),
+    fee NUMERIC(28, 8) NOT NULL DEFAULT 0,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_investment_events_account_date ON investment_events(account_id, event_date);
+CREATE INDEX idx_investment_events_user_id ON investment_events(user_id);
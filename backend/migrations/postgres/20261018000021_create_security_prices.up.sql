@@ -0,0 +1,10 @@
+-- 品种价格：用户手动记录的收盘价，用于投资组合估值，同一品种同一天只保留一个价格
+CREATE TABLE security_prices (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    symbol VARCHAR(20) NOT NULL,
+    price_date DATE NOT NULL,
+    price NUMERIC(28, 8) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (user_id, symbol, price_date)
+);
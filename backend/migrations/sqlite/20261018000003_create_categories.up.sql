@@ -0,0 +1,12 @@
+-- user_id 为空表示系统分类
+CREATE TABLE categories (
+    id BLOB PRIMARY KEY,
+    user_id BLOB REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    icon TEXT NOT NULL,
+    color TEXT NOT NULL,
+    transaction_type TEXT NOT NULL,
+    is_system INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_categories_user_id ON categories(user_id);
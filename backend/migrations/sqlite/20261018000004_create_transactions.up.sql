@@ -0,0 +1,17 @@
+CREATE TABLE transactions (
+    id BLOB PRIMARY KEY,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id BLOB NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    category_id BLOB REFERENCES categories(id) ON DELETE SET NULL,
+    transaction_type TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    description TEXT NOT NULL,
+    notes TEXT,
+    transaction_date TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_transactions_user_date ON transactions(user_id, transaction_date DESC, id DESC);
+CREATE INDEX idx_transactions_account_id ON transactions(account_id);
@@ -0,0 +1,5 @@
+DELETE FROM categories WHERE id IN (
+    X'00000000000000000000000000000101',
+    X'00000000000000000000000000000102',
+    X'00000000000000000000000000000201'
+);
@@ -0,0 +1,4 @@
+INSERT INTO categories (id, user_id, name, icon, color, transaction_type, is_system) VALUES
+    (X'00000000000000000000000000000101', NULL, '餐饮', 'restaurant', '#FF9800', 'Expense', 1),
+    (X'00000000000000000000000000000102', NULL, '交通', 'commute', '#2196F3', 'Expense', 1),
+    (X'00000000000000000000000000000201', NULL, '工资', 'work', '#4CAF50', 'Income', 1);
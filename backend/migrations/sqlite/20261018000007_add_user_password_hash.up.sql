@@ -0,0 +1,2 @@
+-- bcrypt 密码哈希，迁移前创建的用户为空字符串，无法通过密码登录
+ALTER TABLE users ADD COLUMN password_hash TEXT NOT NULL DEFAULT '';
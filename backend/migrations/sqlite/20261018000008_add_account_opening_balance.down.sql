@@ -0,0 +1,3 @@
+UPDATE accounts SET balance = opening_balance;
+
+ALTER TABLE accounts DROP COLUMN opening_balance;
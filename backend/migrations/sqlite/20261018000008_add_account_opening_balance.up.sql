@@ -0,0 +1,6 @@
+-- 期初余额，当前余额 = 期初余额 + 交易流水
+ALTER TABLE accounts ADD COLUMN opening_balance TEXT NOT NULL DEFAULT '0';
+
+-- 此前余额不随交易变化，即为创建账户时的期初余额
+-- TEXT 金额无法在 SQL 中精确求和，已有交易的账户需调用 POST /api/accounts/:id/recalculate 重建余额
+UPDATE accounts SET opening_balance = balance;
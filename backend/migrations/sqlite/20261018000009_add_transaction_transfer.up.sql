@@ -0,0 +1,5 @@
+-- 转账由共享 transfer_id 的转出（Out）和转入（In）两条交易组成
+ALTER TABLE transactions ADD COLUMN transfer_id BLOB;
+ALTER TABLE transactions ADD COLUMN transfer_direction TEXT;
+
+CREATE INDEX idx_transactions_transfer_id ON transactions(transfer_id);
@@ -0,0 +1,10 @@
+-- 汇率表：1 单位 base_currency 兑换 rate 单位 quote_currency，按日期保存历史汇率
+CREATE TABLE exchange_rates (
+    base_currency TEXT NOT NULL,
+    quote_currency TEXT NOT NULL,
+    rate_date TEXT NOT NULL,
+    rate TEXT NOT NULL,
+    source TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (base_currency, quote_currency, rate_date)
+);
@@ -0,0 +1,2 @@
+-- 用户本位币，统计时所有金额折算为该货币
+ALTER TABLE users ADD COLUMN base_currency TEXT NOT NULL DEFAULT 'CNY';
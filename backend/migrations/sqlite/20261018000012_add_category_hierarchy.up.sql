@@ -0,0 +1,7 @@
+-- 子分类通过 parent_id 关联上级分类，最多两级
+-- 归档的分类不能用于新交易，已有交易保留原分类
+-- 不加外键约束，SQLite 无法删除带外键的列；分类只会归档，不会单独删除
+ALTER TABLE categories ADD COLUMN parent_id BLOB;
+ALTER TABLE categories ADD COLUMN is_archived INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_categories_parent_id ON categories(parent_id);
@@ -0,0 +1,25 @@
+DELETE FROM categories WHERE id IN (
+    X'00000000000000000000000000000103',
+    X'00000000000000000000000000000104',
+    X'00000000000000000000000000000105',
+    X'00000000000000000000000000000106',
+    X'00000000000000000000000000000107',
+    X'00000000000000000000000000000108',
+    X'00000000000000000000000000000109',
+    X'0000000000000000000000000000010a',
+    X'000000000000000000000000000001ff',
+    X'00000000000000000000000000000202',
+    X'00000000000000000000000000000203',
+    X'00000000000000000000000000000204',
+    X'00000000000000000000000000000205',
+    X'000000000000000000000000000002ff',
+    X'00000000000000000000000000000301',
+    X'00000000000000000000000000000302',
+    X'00000000000000000000000000000303',
+    X'00000000000000000000000000000304',
+    X'000000000000000000000000000003ff'
+);
+
+ALTER TABLE categories DROP COLUMN catalog_version;
+
+DROP TABLE IF EXISTS category_translations;
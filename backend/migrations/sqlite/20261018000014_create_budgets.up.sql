@@ -0,0 +1,19 @@
+-- 预算表：category_id 和 tag 二选一作为统计范围，tag 保存标签名称
+CREATE TABLE budgets (
+    id BLOB PRIMARY KEY,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    category_id BLOB REFERENCES categories(id) ON DELETE CASCADE,
+    tag TEXT,
+    period TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    rollover INTEGER NOT NULL DEFAULT 0,
+    start_date TEXT NOT NULL,
+    end_date TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    CHECK ((category_id IS NULL) <> (tag IS NULL))
+);
+
+CREATE INDEX idx_budgets_user_id ON budgets(user_id);
@@ -0,0 +1,18 @@
+-- 信用卡每月的还款日
+ALTER TABLE accounts ADD COLUMN payment_due_day INTEGER CHECK (payment_due_day BETWEEN 1 AND 31);
+
+-- 到期提醒：source_id 为重复交易或信用卡账户的 id，不设外键，来源删除后提醒保留
+-- 唯一约束保证同一款项的同一到期日只提醒一次
+CREATE TABLE notifications (
+    id BLOB PRIMARY KEY,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    source_id BLOB NOT NULL,
+    due_date TEXT NOT NULL,
+    title TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    read_at TEXT,
+    UNIQUE (user_id, kind, source_id, due_date)
+);
@@ -0,0 +1,3 @@
+-- 信用卡的信用额度和每月的账单日，额度以 TEXT 保存十进制字符串
+ALTER TABLE accounts ADD COLUMN credit_limit TEXT;
+ALTER TABLE accounts ADD COLUMN statement_closing_day INTEGER CHECK (statement_closing_day BETWEEN 1 AND 31);
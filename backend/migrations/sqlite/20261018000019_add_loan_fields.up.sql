@@ -0,0 +1,6 @@
+-- 贷款和借出账户的借款条件，五个字段同时为空或同时有值，本金和利率以 TEXT 保存十进制字符串
+ALTER TABLE accounts ADD COLUMN loan_principal TEXT;
+ALTER TABLE accounts ADD COLUMN loan_interest_rate TEXT;
+ALTER TABLE accounts ADD COLUMN loan_term_months INTEGER;
+ALTER TABLE accounts ADD COLUMN loan_first_payment_date TEXT;
+ALTER TABLE accounts ADD COLUMN loan_repayment_method TEXT;
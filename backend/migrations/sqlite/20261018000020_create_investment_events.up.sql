@@ -0,0 +1,21 @@
+-- 投资事件：买入、卖出、分红和拆股，持仓和成本由事件按时间先后重放得出
+-- 买入和卖出有 quantity、price，分红有 amount，拆股的 quantity 为每一股变为的股数
+CREATE TABLE investment_events (
+    id BLOB PRIMARY KEY,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    account_id BLOB NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    symbol TEXT NOT NULL,
+    asset_class TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    event_date TEXT NOT NULL,
+    quantity TEXT,
+    price TEXT,
+    amount TEXT,
+    fee TEXT NOT NULL DEFAULT '0',
+    notes TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_investment_events_account_date ON investment_events(account_id, event_date);
+CREATE INDEX idx_investment_events_user_id ON investment_events(user_id);
@@ -0,0 +1,10 @@
+-- 品种价格：用户手动记录的收盘价，用于投资组合估值，同一品种同一天只保留一个价格
+CREATE TABLE security_prices (
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    symbol TEXT NOT NULL,
+    price_date TEXT NOT NULL,
+    price TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, symbol, price_date)
+);
@@ -1,393 +1,990 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    async_trait,
+    extract::{
+        rejection::JsonRejection, FromRequest, FromRequestParts, Path, Query, Request, State,
+    },
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Json, Response},
     routing::{get, post, put, delete},
     Router,
 };
 use uuid::Uuid;
 use std::sync::Arc;
-use chrono::Utc;
 
+use crate::auth::CurrentUser;
 use crate::models::*;
-use crate::services::AppState;
+use crate::validation::{Validate, Validator};
+use crate::services::{
+    AccountService, AppState, AuthService, BudgetService, CategoryService, ExchangeRateService, InstallmentService,
+    InvestmentService, LoanService, NotificationService, PortfolioService, RecurringTransactionService, ServiceError, StatisticsService, TagService, TransactionService, TransferService,
+    UpcomingService, UserService,
+};
 
 pub fn create_api_router() -> Router<Arc<AppState>> {
     Router::new()
+        // 认证相关路由
+        .route("/auth/register", post(register))
+        .route("/auth/login", post(login))
+        .route("/auth/refresh", post(refresh_token))
+        .route("/auth/logout", post(logout))
+        .route("/auth/logout-all", post(logout_all))
+
         // 用户相关路由
-        .route("/users", post(create_user))
+        .route("/users/me", get(get_current_user))
+        .route("/users/me", put(update_current_user))
         .route("/users/:id", get(get_user))
-        
+
         // 账户相关路由
         .route("/accounts", get(get_accounts))
         .route("/accounts", post(create_account))
         .route("/accounts/:id", get(get_account))
         .route("/accounts/:id", put(update_account))
         .route("/accounts/:id", delete(delete_account))
-        
+        .route("/accounts/:id/recalculate", post(recalculate_account_balance))
+        .route("/accounts/:id/statement", get(get_account_statement))
+        .route("/accounts/:id/amortization", get(get_account_amortization))
+        .route("/accounts/:id/loan-payments", post(create_loan_payment))
+        .route("/accounts/:id/investment-events", get(get_investment_events))
+        .route("/accounts/:id/investment-events", post(create_investment_event))
+        .route("/accounts/:id/holdings", get(get_account_holdings))
+
         // 交易相关路由
         .route("/transactions", get(get_transactions))
         .route("/transactions", post(create_transaction))
         .route("/transactions/:id", get(get_transaction))
         .route("/transactions/:id", put(update_transaction))
         .route("/transactions/:id", delete(delete_transaction))
-        
+        .route("/transactions/:id/installments", post(create_installment_plan))
+
+        // 转账相关路由
+        .route("/transfers", post(create_transfer))
+        .route("/transfers/:id", get(get_transfer))
+        .route("/transfers/:id", put(update_transfer))
+        .route("/transfers/:id", delete(delete_transfer))
+
         // 分类相关路由
         .route("/categories", get(get_categories))
-        
+        .route("/categories", post(create_category))
+        .route("/categories/catalog", get(get_category_catalog))
+        .route("/categories/:id", get(get_category))
+        .route("/categories/:id", put(update_category))
+        .route("/categories/:id", delete(archive_category))
+        .route("/categories/:id/merge", post(merge_category))
+
+        // 标签相关路由，标签名在路径中需要 URL 编码
+        .route("/tags", get(get_tags))
+        .route("/tags/:name", put(rename_tag))
+        .route("/tags/:name/merge", post(merge_tag))
+
+        // 预算相关路由
+        .route("/budgets", get(get_budgets))
+        .route("/budgets", post(create_budget))
+        .route("/budgets/status", get(get_budget_statuses))
+        .route("/budgets/:id", get(get_budget))
+        .route("/budgets/:id", put(update_budget))
+        .route("/budgets/:id", delete(delete_budget))
+        .route("/budgets/:id/status", get(get_budget_status))
+
+        // 重复交易路由
+        .route("/recurring-transactions", get(get_recurring_transactions))
+        .route("/recurring-transactions", post(create_recurring_transaction))
+        .route("/recurring-transactions/:id", get(get_recurring_transaction))
+        .route("/recurring-transactions/:id", put(update_recurring_transaction))
+        .route("/recurring-transactions/:id", delete(delete_recurring_transaction))
+        .route("/recurring-transactions/:id/skip", post(skip_recurring_transaction))
+        .route("/recurring-transactions/:id/pause", post(pause_recurring_transaction))
+        .route("/recurring-transactions/:id/resume", post(resume_recurring_transaction))
+
+        // 信用卡分期路由
+        .route("/installments", get(get_installment_plans))
+        .route("/installments/:id", get(get_installment_plan))
+        .route("/installments/:id/payoff", post(payoff_installment_plan))
+
+        // 投资事件路由
+        .route("/investment-events/:id", get(get_investment_event))
+        .route("/investment-events/:id", put(update_investment_event))
+        .route("/investment-events/:id", delete(delete_investment_event))
+
+        // 投资组合路由
+        .route("/security-prices", get(get_security_prices))
+        .route("/security-prices", post(record_security_price))
+        .route("/portfolio/performance", get(get_portfolio_performance))
+
+        // 到期款项和提醒路由
+        .route("/upcoming", get(get_upcoming))
+        .route("/notifications", get(get_notifications))
+        .route("/notifications/:id/read", post(mark_notification_read))
+
         // 统计相关路由
         .route("/summary", get(get_financial_summary))
+        .route("/summary/tags", get(get_tag_spending))
+
+        // 汇率相关路由
+        .route("/exchange-rates", get(get_exchange_rate))
+}
+
+// 服务错误映射为HTTP响应，响应体为带错误码的 ApiResponse
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ServiceError::NotFound(_) => StatusCode::NOT_FOUND,
+            ServiceError::InvalidInput(_) | ServiceError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ServiceError::Conflict(_) => StatusCode::CONFLICT,
+            ServiceError::AuthenticationFailed => StatusCode::UNAUTHORIZED,
+            ServiceError::AuthorizationFailed => StatusCode::FORBIDDEN,
+            ServiceError::ExchangeRateUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ServiceError::Database(_) | ServiceError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        // 内部错误只记录日志，不向客户端暴露细节
+        let message = match &self {
+            ServiceError::Database(_) | ServiceError::Internal(_) => {
+                tracing::error!("{}", self);
+                "Internal server error".to_string()
+            }
+            _ => self.to_string(),
+        };
+        let code = self.code().to_string();
+        let details = match self {
+            ServiceError::Validation(details) => details,
+            _ => Vec::new(),
+        };
+
+        let body = ApiResponse::<()>::error(ApiError { code, details }, message);
+        (status, Json(body)).into_response()
+    }
+}
+
+// JSON 请求体，解析后执行格式校验，失败时同样返回带错误码的响应
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: Validate,
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ServiceError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| ServiceError::InvalidInput(rejection.body_text()))?;
+
+        let mut validator = Validator::new();
+        value.validate(&mut validator);
+        validator.finish()?;
+        Ok(ApiJson(value))
+    }
+}
+
+// 查询参数，解析失败时返回带错误码的响应而不是纯文本
+pub struct ApiQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    T: serde::de::DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ServiceError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| ServiceError::InvalidInput(rejection.body_text()))?;
+        Ok(ApiQuery(value))
+    }
+}
+
+// Accept-Language 请求头选择的语言，未提供时使用默认语言
+#[async_trait]
+impl<S> FromRequestParts<S> for Locale
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(Locale::from_accept_language)
+            .unwrap_or_default())
+    }
+}
+
+// 认证API处理器
+async fn register(
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<CreateUserRequest>,
+) -> Result<Json<ApiResponse<AuthResponse>>, ServiceError> {
+    let response = AuthService::new(state).register(payload).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+async fn login(
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<LoginRequest>,
+) -> Result<Json<ApiResponse<AuthResponse>>, ServiceError> {
+    let response = AuthService::new(state).login(payload).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+async fn refresh_token(
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<RefreshTokenRequest>,
+) -> Result<Json<ApiResponse<AuthResponse>>, ServiceError> {
+    let response = AuthService::new(state).refresh(payload).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+async fn logout(
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<RefreshTokenRequest>,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    AuthService::new(state).logout(payload).await?;
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn logout_all(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    AuthService::new(state).logout_all(user_id).await?;
+    Ok(Json(ApiResponse::success(())))
 }
 
 // 用户API处理器
-async fn create_user(
-    State(_state): State<Arc<AppState>>,
-    Json(payload): Json<CreateUserRequest>,
-) -> Result<Json<ApiResponse<User>>, StatusCode> {
-    // TODO: 实现用户创建逻辑
-    let user = User {
-        id: Uuid::new_v4(),
-        username: payload.username,
-        email: payload.email,
-        display_name: payload.display_name,
-        avatar_url: None,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
+async fn get_current_user(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<ApiResponse<User>>, ServiceError> {
+    let user = UserService::new(state).get_user(user_id).await?;
+    Ok(Json(ApiResponse::success(user)))
+}
+
+async fn update_current_user(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiJson(payload): ApiJson<UpdateUserRequest>,
+) -> Result<Json<ApiResponse<User>>, ServiceError> {
+    let user = UserService::new(state).update_user(user_id, payload).await?;
     Ok(Json(ApiResponse::success(user)))
 }
 
 async fn get_user(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
+    CurrentUser(current_user_id): CurrentUser,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<User>>, StatusCode> {
-    // TODO: 从数据库查询用户
-    // 暂时返回模拟数据
-    let user = User {
-        id: user_id,
-        username: "demo_user".to_string(),
-        email: "demo@example.com".to_string(),
-        display_name: "演示用户".to_string(),
-        avatar_url: None,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
+) -> Result<Json<ApiResponse<User>>, ServiceError> {
+    // 只能查看自己的用户信息
+    if user_id != current_user_id {
+        return Err(ServiceError::AuthorizationFailed);
+    }
+    let user = UserService::new(state).get_user(user_id).await?;
     Ok(Json(ApiResponse::success(user)))
 }
 
 // 账户API处理器
 async fn get_accounts(
-    State(_state): State<Arc<AppState>>,
-    Query(pagination): Query<PaginationQuery>,
-) -> Result<Json<ApiResponse<PaginatedResponse<Account>>>, StatusCode> {
-    // TODO: 从数据库查询账户列表
-    // 暂时返回模拟数据
-    let accounts = vec![
-        Account {
-            id: Uuid::new_v4(),
-            user_id: Uuid::new_v4(),
-            name: "现金账户".to_string(),
-            account_type: AccountType::Cash,
-            currency: "CNY".to_string(),
-            balance: rust_decimal::Decimal::from(5000),
-            is_active: true,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        },
-        Account {
-            id: Uuid::new_v4(),
-            user_id: Uuid::new_v4(),
-            name: "工商银行卡".to_string(),
-            account_type: AccountType::BankCard,
-            currency: "CNY".to_string(),
-            balance: rust_decimal::Decimal::from(15000),
-            is_active: true,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        },
-    ];
-    
-    let page = pagination.page.unwrap_or(1);
-    let limit = pagination.limit.unwrap_or(20);
-    
-    let response = PaginatedResponse {
-        data: accounts,
-        page,
-        limit,
-        total: 2,
-        has_next: false,
-    };
-    
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(pagination): ApiQuery<PaginationQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<Account>>>, ServiceError> {
+    let response = AccountService::new(state)
+        .get_accounts(user_id, pagination)
+        .await?;
     Ok(Json(ApiResponse::success(response)))
 }
 
 async fn create_account(
-    State(_state): State<Arc<AppState>>,
-    Json(payload): Json<CreateAccountRequest>,
-) -> Result<Json<ApiResponse<Account>>, StatusCode> {
-    // TODO: 实现账户创建逻辑
-    let account = Account {
-        id: Uuid::new_v4(),
-        user_id: Uuid::new_v4(), // TODO: 从认证中获取用户ID
-        name: payload.name,
-        account_type: payload.account_type,
-        currency: payload.currency,
-        balance: payload.initial_balance.unwrap_or_default(),
-        is_active: true,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiJson(payload): ApiJson<CreateAccountRequest>,
+) -> Result<Json<ApiResponse<Account>>, ServiceError> {
+    let account = AccountService::new(state)
+        .create_account(user_id, payload)
+        .await?;
     Ok(Json(ApiResponse::success(account)))
 }
 
 async fn get_account(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
     Path(account_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Account>>, StatusCode> {
-    // TODO: 从数据库查询特定账户
-    let account = Account {
-        id: account_id,
-        user_id: Uuid::new_v4(),
-        name: "演示账户".to_string(),
-        account_type: AccountType::Cash,
-        currency: "CNY".to_string(),
-        balance: rust_decimal::Decimal::from(10000),
-        is_active: true,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
+) -> Result<Json<ApiResponse<Account>>, ServiceError> {
+    let account = AccountService::new(state)
+        .get_account(user_id, account_id)
+        .await?;
     Ok(Json(ApiResponse::success(account)))
 }
 
 async fn update_account(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
     Path(account_id): Path<Uuid>,
-    Json(_payload): Json<CreateAccountRequest>,
-) -> Result<Json<ApiResponse<Account>>, StatusCode> {
-    // TODO: 实现账户更新逻辑
-    let account = Account {
-        id: account_id,
-        user_id: Uuid::new_v4(),
-        name: "更新后的账户".to_string(),
-        account_type: AccountType::Cash,
-        currency: "CNY".to_string(),
-        balance: rust_decimal::Decimal::from(10000),
-        is_active: true,
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
+    ApiJson(payload): ApiJson<UpdateAccountRequest>,
+) -> Result<Json<ApiResponse<Account>>, ServiceError> {
+    let account = AccountService::new(state)
+        .update_account(user_id, account_id, payload)
+        .await?;
     Ok(Json(ApiResponse::success(account)))
 }
 
 async fn delete_account(
-    State(_state): State<Arc<AppState>>,
-    Path(_account_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<()>>, StatusCode> {
-    // TODO: 实现账户删除逻辑
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    AccountService::new(state)
+        .delete_account(user_id, account_id)
+        .await?;
     Ok(Json(ApiResponse::success(())))
 }
 
+async fn recalculate_account_balance(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Account>>, ServiceError> {
+    let account = AccountService::new(state)
+        .recalculate_balance(user_id, account_id)
+        .await?;
+    Ok(Json(ApiResponse::success(account)))
+}
+
+async fn get_account_statement(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(account_id): Path<Uuid>,
+    ApiQuery(query): ApiQuery<StatementQuery>,
+) -> Result<Json<ApiResponse<CreditCardStatement>>, ServiceError> {
+    let statement = AccountService::new(state)
+        .get_statement(user_id, account_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(statement)))
+}
+
+async fn get_account_amortization(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<AmortizationSchedule>>, ServiceError> {
+    let schedule = LoanService::new(state)
+        .get_amortization(user_id, account_id)
+        .await?;
+    Ok(Json(ApiResponse::success(schedule)))
+}
+
+async fn create_loan_payment(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(account_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<LoanPaymentRequest>,
+) -> Result<Json<ApiResponse<LoanPayment>>, ServiceError> {
+    let payment = LoanService::new(state)
+        .create_payment(user_id, account_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(payment)))
+}
+
 // 交易API处理器
 async fn get_transactions(
-    State(_state): State<Arc<AppState>>,
-    Query(pagination): Query<PaginationQuery>,
-) -> Result<Json<ApiResponse<PaginatedResponse<Transaction>>>, StatusCode> {
-    // TODO: 从数据库查询交易列表
-    // 暂时返回模拟数据
-    let transactions = vec![
-        Transaction {
-            id: Uuid::new_v4(),
-            user_id: Uuid::new_v4(),
-            account_id: Uuid::new_v4(),
-            category_id: None,
-            transaction_type: TransactionType::Income,
-            amount: rust_decimal::Decimal::from(3000),
-            currency: "CNY".to_string(),
-            description: "工资收入".to_string(),
-            notes: None,
-            tags: vec!["工资".to_string()],
-            transaction_date: Utc::now(),
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        },
-        Transaction {
-            id: Uuid::new_v4(),
-            user_id: Uuid::new_v4(),
-            account_id: Uuid::new_v4(),
-            category_id: None,
-            transaction_type: TransactionType::Expense,
-            amount: rust_decimal::Decimal::from(500),
-            currency: "CNY".to_string(),
-            description: "超市购物".to_string(),
-            notes: Some("买了生活用品".to_string()),
-            tags: vec!["购物".to_string(), "生活".to_string()],
-            transaction_date: Utc::now(),
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        },
-    ];
-    
-    let page = pagination.page.unwrap_or(1);
-    let limit = pagination.limit.unwrap_or(20);
-    
-    let response = PaginatedResponse {
-        data: transactions,
-        page,
-        limit,
-        total: 2,
-        has_next: false,
-    };
-    
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(query): ApiQuery<TransactionQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<Transaction>>>, ServiceError> {
+    let response = TransactionService::new(state)
+        .get_transactions(user_id, query)
+        .await?;
     Ok(Json(ApiResponse::success(response)))
 }
 
 async fn create_transaction(
-    State(_state): State<Arc<AppState>>,
-    Json(payload): Json<CreateTransactionRequest>,
-) -> Result<Json<ApiResponse<Transaction>>, StatusCode> {
-    // TODO: 实现交易创建逻辑
-    let transaction = Transaction {
-        id: Uuid::new_v4(),
-        user_id: Uuid::new_v4(), // TODO: 从认证中获取用户ID
-        account_id: payload.account_id,
-        category_id: payload.category_id,
-        transaction_type: payload.transaction_type,
-        amount: payload.amount,
-        currency: "CNY".to_string(), // TODO: 从账户获取货币类型
-        description: payload.description,
-        notes: payload.notes,
-        tags: payload.tags.unwrap_or_default(),
-        transaction_date: payload.transaction_date.unwrap_or(Utc::now()),
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiJson(payload): ApiJson<CreateTransactionRequest>,
+) -> Result<Json<ApiResponse<Transaction>>, ServiceError> {
+    let transaction = TransactionService::new(state)
+        .create_transaction(user_id, payload)
+        .await?;
     Ok(Json(ApiResponse::success(transaction)))
 }
 
 async fn get_transaction(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
     Path(transaction_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Transaction>>, StatusCode> {
-    // TODO: 从数据库查询特定交易
-    let transaction = Transaction {
-        id: transaction_id,
-        user_id: Uuid::new_v4(),
-        account_id: Uuid::new_v4(),
-        category_id: None,
-        transaction_type: TransactionType::Expense,
-        amount: rust_decimal::Decimal::from(100),
-        currency: "CNY".to_string(),
-        description: "演示交易".to_string(),
-        notes: None,
-        tags: vec![],
-        transaction_date: Utc::now(),
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
+) -> Result<Json<ApiResponse<Transaction>>, ServiceError> {
+    let transaction = TransactionService::new(state)
+        .get_transaction(user_id, transaction_id)
+        .await?;
     Ok(Json(ApiResponse::success(transaction)))
 }
 
 async fn update_transaction(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
     Path(transaction_id): Path<Uuid>,
-    Json(_payload): Json<UpdateTransactionRequest>,
-) -> Result<Json<ApiResponse<Transaction>>, StatusCode> {
-    // TODO: 实现交易更新逻辑
-    let transaction = Transaction {
-        id: transaction_id,
-        user_id: Uuid::new_v4(),
-        account_id: Uuid::new_v4(),
-        category_id: None,
-        transaction_type: TransactionType::Expense,
-        amount: rust_decimal::Decimal::from(200),
-        currency: "CNY".to_string(),
-        description: "更新后的交易".to_string(),
-        notes: None,
-        tags: vec![],
-        transaction_date: Utc::now(),
-        created_at: Utc::now(),
-        updated_at: Utc::now(),
-    };
-    
+    ApiJson(payload): ApiJson<UpdateTransactionRequest>,
+) -> Result<Json<ApiResponse<Transaction>>, ServiceError> {
+    let transaction = TransactionService::new(state)
+        .update_transaction(user_id, transaction_id, payload)
+        .await?;
     Ok(Json(ApiResponse::success(transaction)))
 }
 
 async fn delete_transaction(
-    State(_state): State<Arc<AppState>>,
-    Path(_transaction_id): Path<Uuid>,
-) -> Result<Json<ApiResponse<()>>, StatusCode> {
-    // TODO: 实现交易删除逻辑
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(transaction_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    TransactionService::new(state)
+        .delete_transaction(user_id, transaction_id)
+        .await?;
+    Ok(Json(ApiResponse::success(())))
+}
+
+// 转账API处理器
+async fn create_transfer(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiJson(payload): ApiJson<CreateTransferRequest>,
+) -> Result<Json<ApiResponse<Transfer>>, ServiceError> {
+    let transfer = TransferService::new(state)
+        .create_transfer(user_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(transfer)))
+}
+
+async fn get_transfer(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(transfer_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Transfer>>, ServiceError> {
+    let transfer = TransferService::new(state)
+        .get_transfer(user_id, transfer_id)
+        .await?;
+    Ok(Json(ApiResponse::success(transfer)))
+}
+
+async fn update_transfer(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(transfer_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<UpdateTransferRequest>,
+) -> Result<Json<ApiResponse<Transfer>>, ServiceError> {
+    let transfer = TransferService::new(state)
+        .update_transfer(user_id, transfer_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(transfer)))
+}
+
+async fn delete_transfer(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(transfer_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    TransferService::new(state)
+        .delete_transfer(user_id, transfer_id)
+        .await?;
     Ok(Json(ApiResponse::success(())))
 }
 
 // 分类API处理器
 async fn get_categories(
-    State(_state): State<Arc<AppState>>,
-) -> Result<Json<ApiResponse<Vec<Category>>>, StatusCode> {
-    // TODO: 从数据库查询分类列表
-    // 暂时返回模拟数据
-    let categories = vec![
-        Category {
-            id: Uuid::new_v4(),
-            name: "餐饮".to_string(),
-            icon: "restaurant".to_string(),
-            color: "#FF9800".to_string(),
-            transaction_type: TransactionType::Expense,
-            is_system: true,
-        },
-        Category {
-            id: Uuid::new_v4(),
-            name: "交通".to_string(),
-            icon: "commute".to_string(),
-            color: "#2196F3".to_string(),
-            transaction_type: TransactionType::Expense,
-            is_system: true,
-        },
-        Category {
-            id: Uuid::new_v4(),
-            name: "工资".to_string(),
-            icon: "work".to_string(),
-            color: "#4CAF50".to_string(),
-            transaction_type: TransactionType::Income,
-            is_system: true,
-        },
-    ];
-    
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    locale: Locale,
+    ApiQuery(query): ApiQuery<CategoryQuery>,
+) -> Result<Json<ApiResponse<Vec<Category>>>, ServiceError> {
+    let categories = CategoryService::new(state)
+        .get_categories(user_id, query, locale)
+        .await?;
     Ok(Json(ApiResponse::success(categories)))
 }
 
+async fn get_category_catalog(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    locale: Locale,
+) -> Result<Json<ApiResponse<CategoryCatalog>>, ServiceError> {
+    let catalog = CategoryService::new(state).get_catalog(user_id, locale).await?;
+    Ok(Json(ApiResponse::success(catalog)))
+}
+
+async fn create_category(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiJson(payload): ApiJson<CreateCategoryRequest>,
+) -> Result<Json<ApiResponse<Category>>, ServiceError> {
+    let category = CategoryService::new(state)
+        .create_category(user_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(category)))
+}
+
+async fn get_category(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(category_id): Path<Uuid>,
+    locale: Locale,
+) -> Result<Json<ApiResponse<Category>>, ServiceError> {
+    let category = CategoryService::new(state)
+        .get_category(user_id, category_id, locale)
+        .await?;
+    Ok(Json(ApiResponse::success(category)))
+}
+
+async fn update_category(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(category_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<UpdateCategoryRequest>,
+) -> Result<Json<ApiResponse<Category>>, ServiceError> {
+    let category = CategoryService::new(state)
+        .update_category(user_id, category_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(category)))
+}
+
+async fn archive_category(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(category_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    CategoryService::new(state)
+        .archive_category(user_id, category_id)
+        .await?;
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn merge_category(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(category_id): Path<Uuid>,
+    locale: Locale,
+    ApiJson(payload): ApiJson<MergeCategoryRequest>,
+) -> Result<Json<ApiResponse<MergeCategoryResponse>>, ServiceError> {
+    let response = CategoryService::new(state)
+        .merge_category(user_id, category_id, payload, locale)
+        .await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// 标签API处理器
+async fn get_tags(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(query): ApiQuery<TagQuery>,
+) -> Result<Json<ApiResponse<Vec<Tag>>>, ServiceError> {
+    let tags = TagService::new(state).get_tags(user_id, query).await?;
+    Ok(Json(ApiResponse::success(tags)))
+}
+
+async fn rename_tag(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(name): Path<String>,
+    ApiJson(payload): ApiJson<RenameTagRequest>,
+) -> Result<Json<ApiResponse<TagChangeResponse>>, ServiceError> {
+    let response = TagService::new(state)
+        .rename_tag(user_id, &name, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+async fn merge_tag(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(name): Path<String>,
+    ApiJson(payload): ApiJson<MergeTagRequest>,
+) -> Result<Json<ApiResponse<TagChangeResponse>>, ServiceError> {
+    let response = TagService::new(state)
+        .merge_tag(user_id, &name, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// 预算API处理器
+async fn get_budgets(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<ApiResponse<Vec<Budget>>>, ServiceError> {
+    let budgets = BudgetService::new(state).get_budgets(user_id).await?;
+    Ok(Json(ApiResponse::success(budgets)))
+}
+
+async fn create_budget(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiJson(payload): ApiJson<CreateBudgetRequest>,
+) -> Result<Json<ApiResponse<Budget>>, ServiceError> {
+    let budget = BudgetService::new(state)
+        .create_budget(user_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(budget)))
+}
+
+async fn get_budget_statuses(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(query): ApiQuery<BudgetStatusQuery>,
+) -> Result<Json<ApiResponse<Vec<BudgetStatus>>>, ServiceError> {
+    let statuses = BudgetService::new(state)
+        .get_budget_statuses(user_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(statuses)))
+}
+
+async fn get_budget(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(budget_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Budget>>, ServiceError> {
+    let budget = BudgetService::new(state)
+        .get_budget(user_id, budget_id)
+        .await?;
+    Ok(Json(ApiResponse::success(budget)))
+}
+
+async fn update_budget(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(budget_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<UpdateBudgetRequest>,
+) -> Result<Json<ApiResponse<Budget>>, ServiceError> {
+    let budget = BudgetService::new(state)
+        .update_budget(user_id, budget_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(budget)))
+}
+
+async fn delete_budget(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(budget_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    BudgetService::new(state)
+        .delete_budget(user_id, budget_id)
+        .await?;
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn get_budget_status(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(budget_id): Path<Uuid>,
+    ApiQuery(query): ApiQuery<BudgetStatusQuery>,
+) -> Result<Json<ApiResponse<BudgetStatus>>, ServiceError> {
+    let status = BudgetService::new(state)
+        .get_budget_status(user_id, budget_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(status)))
+}
+
+// 重复交易API处理器
+async fn get_recurring_transactions(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<ApiResponse<Vec<RecurringTransaction>>>, ServiceError> {
+    let recurring = RecurringTransactionService::new(state)
+        .get_recurring_list(user_id)
+        .await?;
+    Ok(Json(ApiResponse::success(recurring)))
+}
+
+async fn create_recurring_transaction(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiJson(payload): ApiJson<CreateRecurringTransactionRequest>,
+) -> Result<Json<ApiResponse<RecurringTransaction>>, ServiceError> {
+    let recurring = RecurringTransactionService::new(state)
+        .create_recurring(user_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(recurring)))
+}
+
+async fn get_recurring_transaction(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(recurring_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RecurringTransaction>>, ServiceError> {
+    let recurring = RecurringTransactionService::new(state)
+        .get_recurring(user_id, recurring_id)
+        .await?;
+    Ok(Json(ApiResponse::success(recurring)))
+}
+
+async fn update_recurring_transaction(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(recurring_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<UpdateRecurringTransactionRequest>,
+) -> Result<Json<ApiResponse<RecurringTransaction>>, ServiceError> {
+    let recurring = RecurringTransactionService::new(state)
+        .update_recurring(user_id, recurring_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(recurring)))
+}
+
+async fn delete_recurring_transaction(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(recurring_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    RecurringTransactionService::new(state)
+        .delete_recurring(user_id, recurring_id)
+        .await?;
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn skip_recurring_transaction(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(recurring_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RecurringTransaction>>, ServiceError> {
+    let recurring = RecurringTransactionService::new(state)
+        .skip_next(user_id, recurring_id)
+        .await?;
+    Ok(Json(ApiResponse::success(recurring)))
+}
+
+async fn pause_recurring_transaction(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(recurring_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RecurringTransaction>>, ServiceError> {
+    let recurring = RecurringTransactionService::new(state)
+        .set_paused(user_id, recurring_id, true)
+        .await?;
+    Ok(Json(ApiResponse::success(recurring)))
+}
+
+async fn resume_recurring_transaction(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(recurring_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RecurringTransaction>>, ServiceError> {
+    let recurring = RecurringTransactionService::new(state)
+        .set_paused(user_id, recurring_id, false)
+        .await?;
+    Ok(Json(ApiResponse::success(recurring)))
+}
+
+// 信用卡分期API处理器
+async fn create_installment_plan(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(transaction_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<CreateInstallmentPlanRequest>,
+) -> Result<Json<ApiResponse<InstallmentPlanDetails>>, ServiceError> {
+    let plan = InstallmentService::new(state)
+        .create_installment(user_id, transaction_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(plan)))
+}
+
+async fn get_installment_plans(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<ApiResponse<Vec<InstallmentPlanDetails>>>, ServiceError> {
+    let plans = InstallmentService::new(state).get_installments(user_id).await?;
+    Ok(Json(ApiResponse::success(plans)))
+}
+
+async fn get_installment_plan(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(plan_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<InstallmentPlanDetails>>, ServiceError> {
+    let plan = InstallmentService::new(state)
+        .get_installment(user_id, plan_id)
+        .await?;
+    Ok(Json(ApiResponse::success(plan)))
+}
+
+async fn payoff_installment_plan(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(plan_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<PayoffInstallmentPlanRequest>,
+) -> Result<Json<ApiResponse<InstallmentPlanDetails>>, ServiceError> {
+    let plan = InstallmentService::new(state)
+        .payoff_installment(user_id, plan_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(plan)))
+}
+
+// 投资事件和持仓API处理器
+async fn get_investment_events(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Vec<InvestmentEvent>>>, ServiceError> {
+    let events = InvestmentService::new(state)
+        .get_events(user_id, account_id)
+        .await?;
+    Ok(Json(ApiResponse::success(events)))
+}
+
+async fn create_investment_event(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(account_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<CreateInvestmentEventRequest>,
+) -> Result<Json<ApiResponse<InvestmentEvent>>, ServiceError> {
+    let event = InvestmentService::new(state)
+        .create_event(user_id, account_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(event)))
+}
+
+async fn get_investment_event(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<InvestmentEvent>>, ServiceError> {
+    let event = InvestmentService::new(state)
+        .get_event(user_id, event_id)
+        .await?;
+    Ok(Json(ApiResponse::success(event)))
+}
+
+async fn update_investment_event(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(event_id): Path<Uuid>,
+    ApiJson(payload): ApiJson<UpdateInvestmentEventRequest>,
+) -> Result<Json<ApiResponse<InvestmentEvent>>, ServiceError> {
+    let event = InvestmentService::new(state)
+        .update_event(user_id, event_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(event)))
+}
+
+async fn delete_investment_event(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, ServiceError> {
+    InvestmentService::new(state)
+        .delete_event(user_id, event_id)
+        .await?;
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn get_account_holdings(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(account_id): Path<Uuid>,
+    ApiQuery(query): ApiQuery<HoldingsQuery>,
+) -> Result<Json<ApiResponse<AccountHoldings>>, ServiceError> {
+    let holdings = InvestmentService::new(state)
+        .get_holdings(user_id, account_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(holdings)))
+}
+
+// 投资组合API处理器
+async fn get_security_prices(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(query): ApiQuery<SecurityPriceQuery>,
+) -> Result<Json<ApiResponse<Vec<SecurityPrice>>>, ServiceError> {
+    let prices = PortfolioService::new(state)
+        .get_prices(user_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(prices)))
+}
+
+async fn record_security_price(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiJson(payload): ApiJson<SecurityPriceRequest>,
+) -> Result<Json<ApiResponse<SecurityPrice>>, ServiceError> {
+    let price = PortfolioService::new(state)
+        .record_price(user_id, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(price)))
+}
+
+async fn get_portfolio_performance(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(query): ApiQuery<PerformanceQuery>,
+) -> Result<Json<ApiResponse<PortfolioPerformance>>, ServiceError> {
+    let performance = PortfolioService::new(state)
+        .get_performance(user_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(performance)))
+}
+
+// 到期款项和提醒API处理器
+async fn get_upcoming(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(query): ApiQuery<UpcomingQuery>,
+) -> Result<Json<ApiResponse<Vec<UpcomingPayment>>>, ServiceError> {
+    let payments = UpcomingService::new(state)
+        .get_upcoming(user_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(payments)))
+}
+
+async fn get_notifications(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(query): ApiQuery<NotificationQuery>,
+) -> Result<Json<ApiResponse<Vec<Notification>>>, ServiceError> {
+    let notifications = NotificationService::new(state)
+        .get_notifications(user_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(notifications)))
+}
+
+async fn mark_notification_read(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    Path(notification_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<Notification>>, ServiceError> {
+    let notification = NotificationService::new(state)
+        .mark_read(user_id, notification_id)
+        .await?;
+    Ok(Json(ApiResponse::success(notification)))
+}
+
 // 统计API处理器
 async fn get_financial_summary(
-    State(_state): State<Arc<AppState>>,
-) -> Result<Json<ApiResponse<FinancialSummary>>, StatusCode> {
-    // TODO: 从数据库计算统计数据
-    // 暂时返回模拟数据
-    let summary = FinancialSummary {
-        total_income: rust_decimal::Decimal::from(10000),
-        total_expense: rust_decimal::Decimal::from(6000),
-        net_income: rust_decimal::Decimal::from(4000),
-        account_balances: vec![
-            AccountBalance {
-                account_id: Uuid::new_v4(),
-                account_name: "现金".to_string(),
-                balance: rust_decimal::Decimal::from(5000),
-                currency: "CNY".to_string(),
-            },
-            AccountBalance {
-                account_id: Uuid::new_v4(),
-                account_name: "银行卡".to_string(),
-                balance: rust_decimal::Decimal::from(15000),
-                currency: "CNY".to_string(),
-            },
-        ],
-    };
-    
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+) -> Result<Json<ApiResponse<FinancialSummary>>, ServiceError> {
+    let summary = StatisticsService::new(state)
+        .get_financial_summary(user_id)
+        .await?;
     Ok(Json(ApiResponse::success(summary)))
-}
\ No newline at end of file
+}
+
+async fn get_tag_spending(
+    State(state): State<Arc<AppState>>,
+    CurrentUser(user_id): CurrentUser,
+    ApiQuery(query): ApiQuery<TagSpendingQuery>,
+) -> Result<Json<ApiResponse<TagSpendingReport>>, ServiceError> {
+    let report = TagService::new(state)
+        .get_tag_spending(user_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+// 汇率API处理器
+async fn get_exchange_rate(
+    State(state): State<Arc<AppState>>,
+    _: CurrentUser,
+    ApiQuery(query): ApiQuery<ExchangeRateQuery>,
+) -> Result<Json<ApiResponse<ExchangeRate>>, ServiceError> {
+    let rate = ExchangeRateService::new(state).lookup(query).await?;
+    Ok(Json(ApiResponse::success(rate)))
+}
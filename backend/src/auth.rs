@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use chrono::Utc;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::services::{AppState, ServiceError};
+
+// 令牌类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenType {
+    Access,  // 访问令牌，短期有效
+    Refresh, // 刷新令牌，用于换取新的访问令牌
+}
+
+// JWT 载荷
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Claims {
+    pub sub: Uuid,
+    pub typ: TokenType,
+    // 会话ID，同一次登录后轮换出的令牌共享同一会话（令牌族）
+    pub sid: Uuid,
+    pub jti: Uuid,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+// 签发的令牌对
+#[derive(Debug)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    // 刷新令牌的 jti，服务端保存用于轮换校验
+    pub refresh_jti: Uuid,
+    pub expires_in: i64,
+}
+
+// 签发单个令牌
+pub fn issue_token(
+    user_id: Uuid,
+    session_id: Uuid,
+    token_type: TokenType,
+    ttl_secs: i64,
+    secret: &str,
+) -> Result<(String, Claims), jsonwebtoken::errors::Error> {
+    let now = Utc::now().timestamp();
+    let claims = Claims {
+        sub: user_id,
+        typ: token_type,
+        sid: session_id,
+        jti: Uuid::new_v4(),
+        iat: now,
+        exp: now + ttl_secs,
+    };
+    let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes()))?;
+    Ok((token, claims))
+}
+
+// 签发访问令牌和刷新令牌
+pub fn issue_token_pair(
+    user_id: Uuid,
+    session_id: Uuid,
+    access_ttl_secs: i64,
+    refresh_ttl_secs: i64,
+    secret: &str,
+) -> Result<TokenPair, jsonwebtoken::errors::Error> {
+    let (access_token, _) = issue_token(user_id, session_id, TokenType::Access, access_ttl_secs, secret)?;
+    let (refresh_token, refresh_claims) =
+        issue_token(user_id, session_id, TokenType::Refresh, refresh_ttl_secs, secret)?;
+    Ok(TokenPair {
+        access_token,
+        refresh_token,
+        refresh_jti: refresh_claims.jti,
+        expires_in: access_ttl_secs,
+    })
+}
+
+// 校验令牌签名、有效期和类型
+pub fn decode_token(token: &str, expected: TokenType, secret: &str) -> Option<Claims> {
+    let data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .ok()?;
+    (data.claims.typ == expected).then_some(data.claims)
+}
+
+// 计算密码的 bcrypt 哈希，耗时较长，调用方应放在阻塞线程池中执行
+pub fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
+    bcrypt::hash(password, bcrypt::DEFAULT_COST)
+}
+
+// 用户不存在时用于校验的固定哈希，与真实哈希的耗时相同，避免通过响应时间判断用户名是否存在
+pub const DUMMY_PASSWORD_HASH: &str = "$2b$12$93wFMyfFx0OzYy3mS7GnVOpktmg4K5mybDVkgZ99iMQH9Atn5v4fO";
+
+// 校验密码，哈希格式错误视为不匹配
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    bcrypt::verify(password, password_hash).unwrap_or(false)
+}
+
+// 当前登录用户，从 Authorization: Bearer <访问令牌> 中解析，所属会话被注销后立即失效
+#[derive(Debug, Clone, Copy)]
+pub struct CurrentUser(pub Uuid);
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for CurrentUser {
+    type Rejection = ServiceError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(ServiceError::AuthenticationFailed)?;
+
+        let claims = decode_token(token.trim(), TokenType::Access, &state.config.jwt_secret)
+            .ok_or(ServiceError::AuthenticationFailed)?;
+
+        if !state.sessions.is_session_active(claims.sid).await? {
+            return Err(ServiceError::AuthenticationFailed);
+        }
+        Ok(CurrentUser(claims.sub))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 格式错误的哈希会立即返回，起不到固定耗时的作用
+    #[test]
+    fn dummy_hash_is_a_default_cost_bcrypt_hash() {
+        assert!(bcrypt::verify("password", DUMMY_PASSWORD_HASH).is_ok());
+        assert!(DUMMY_PASSWORD_HASH.starts_with(&format!("$2b${}$", bcrypt::DEFAULT_COST)));
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::migrate::MigrateError;
+use uuid::Uuid;
+
+use crate::models::{
+    Account, Budget, Category, ExchangeRate, InstallmentPlan, InvestmentEvent, Notification, RecurringTransaction, SecurityPrice, SortOrder, Tag, Transaction,
+    TransactionSortField, TransactionType, User,
+};
+
+mod memory;
+mod postgres;
+mod sql;
+mod sqlite;
+
+pub use memory::MemoryStorage;
+pub use postgres::PostgresStorage;
+pub use sqlite::SqliteStorage;
+
+// 存储后端类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    Postgres, // 多用户部署
+    Sqlite,   // 单用户自托管
+    Memory,   // 单元测试，重启后数据丢失
+}
+
+impl std::str::FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "postgres" | "postgresql" => Ok(StorageBackend::Postgres),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            "memory" => Ok(StorageBackend::Memory),
+            other => Err(format!("unknown storage backend: {}", other)),
+        }
+    }
+}
+
+// 根据配置创建存储
+pub async fn connect(
+    backend: StorageBackend,
+    database_url: &str,
+) -> Result<Arc<dyn Storage>, StorageError> {
+    let storage: Arc<dyn Storage> = match backend {
+        StorageBackend::Postgres => Arc::new(PostgresStorage::connect(database_url).await?),
+        StorageBackend::Sqlite => Arc::new(SqliteStorage::connect(database_url).await?),
+        StorageBackend::Memory => Arc::new(MemoryStorage::new()),
+    };
+    Ok(storage)
+}
+
+// 存储层错误
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("{0} already exists")]
+    Conflict(String),
+
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Migrate(#[from] MigrateError),
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+// 迁移状态
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+// 某类交易某种货币在某一天（UTC）的金额合计
+#[derive(Debug, Clone)]
+pub struct DailyTotal {
+    pub transaction_type: TransactionType,
+    pub currency: String,
+    pub date: NaiveDate,
+    pub amount: Decimal,
+}
+
+// 带某个标签的交易某种货币在某一天（UTC）的金额合计和笔数
+#[derive(Debug, Clone)]
+pub struct TagDailyTotal {
+    pub tag: String,
+    pub currency: String,
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub transaction_count: u64,
+}
+
+// 交易列表筛选条件，各条件之间为“且”的关系，空列表表示不限
+#[derive(Debug, Clone, Default)]
+pub struct TransactionFilter {
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub account_ids: Vec<Uuid>,
+    pub category_ids: Vec<Uuid>,
+    pub transaction_type: Option<TransactionType>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub tags: Vec<String>,
+    // true 时需包含全部标签，否则包含任意一个即可
+    pub match_all_tags: bool,
+    // 小写的关键词，每个都需出现在描述或备注中
+    pub search_terms: Vec<String>,
+}
+
+impl TransactionFilter {
+    // 内存存储使用，语义与 SQL 实现保持一致
+    pub fn matches(&self, t: &Transaction) -> bool {
+        let has_tag = |tag: &String| t.tags.contains(tag);
+        self.start_date.is_none_or(|start| t.transaction_date >= start)
+            && self.end_date.is_none_or(|end| t.transaction_date <= end)
+            && (self.account_ids.is_empty() || self.account_ids.contains(&t.account_id))
+            && (self.category_ids.is_empty()
+                || t.category_id.is_some_and(|id| self.category_ids.contains(&id)))
+            && self.transaction_type.is_none_or(|ty| t.transaction_type == ty)
+            && self.min_amount.is_none_or(|min| t.amount >= min)
+            && self.max_amount.is_none_or(|max| t.amount <= max)
+            && (self.tags.is_empty()
+                || if self.match_all_tags {
+                    self.tags.iter().all(has_tag)
+                } else {
+                    self.tags.iter().any(has_tag)
+                })
+            && self.search_terms.iter().all(|term| {
+                t.description.to_lowercase().contains(term)
+                    || t.notes.as_deref().is_some_and(|notes| notes.to_lowercase().contains(term))
+            })
+    }
+}
+
+// 交易列表排序，排序字段相同时按 id 同向排序，保证分页稳定
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransactionSort {
+    pub field: TransactionSortField,
+    pub order: SortOrder,
+}
+
+impl TransactionSort {
+    pub fn value_of(&self, t: &Transaction) -> SortValue {
+        match self.field {
+            TransactionSortField::TransactionDate => SortValue::Time(t.transaction_date),
+            TransactionSortField::Amount => SortValue::Amount(t.amount),
+            TransactionSortField::CreatedAt => SortValue::Time(t.created_at),
+        }
+    }
+
+    // 按当前排序 t 是否排在游标之后
+    pub fn is_after(&self, t: &Transaction, cursor: &Cursor) -> bool {
+        let ordering = (self.value_of(t), t.id).partial_cmp(&(cursor.value, cursor.id));
+        match self.order {
+            SortOrder::Asc => ordering == Some(std::cmp::Ordering::Greater),
+            SortOrder::Desc => ordering == Some(std::cmp::Ordering::Less),
+        }
+    }
+}
+
+// 排序字段的值
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum SortValue {
+    Time(DateTime<Utc>),
+    Amount(Decimal),
+}
+
+// 游标指向上一页的最后一条记录
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cursor {
+    pub value: SortValue,
+    pub id: Uuid,
+}
+
+// 分页起点：跳过 offset 条，或从游标之后开始
+#[derive(Debug, Clone, Copy)]
+pub enum PageStart {
+    Offset(i64),
+    After(Cursor),
+}
+
+// 在应用层按日汇总交易金额，SQLite 的 TEXT 金额列无法精确 SUM
+fn daily_totals(
+    rows: impl IntoIterator<Item = (TransactionType, String, DateTime<Utc>, Decimal)>,
+) -> Vec<DailyTotal> {
+    let mut totals: HashMap<(TransactionType, String, NaiveDate), Decimal> = HashMap::new();
+    for (transaction_type, currency, transaction_date, amount) in rows {
+        *totals
+            .entry((transaction_type, currency, transaction_date.date_naive()))
+            .or_default() += amount;
+    }
+    let mut totals: Vec<DailyTotal> = totals
+        .into_iter()
+        .map(|((transaction_type, currency, date), amount)| DailyTotal {
+            transaction_type,
+            currency,
+            date,
+            amount,
+        })
+        .collect();
+    totals.sort_by_key(|total| total.date);
+    totals
+}
+
+// 与 daily_totals 相同，按标签分别汇总
+fn tag_daily_totals(
+    rows: impl IntoIterator<Item = (String, String, DateTime<Utc>, Decimal)>,
+) -> Vec<TagDailyTotal> {
+    let mut totals: HashMap<(String, String, NaiveDate), (Decimal, u64)> = HashMap::new();
+    for (tag, currency, transaction_date, amount) in rows {
+        let total = totals.entry((tag, currency, transaction_date.date_naive())).or_default();
+        total.0 += amount;
+        total.1 += 1;
+    }
+    let mut totals: Vec<TagDailyTotal> = totals
+        .into_iter()
+        .map(|((tag, currency, date), (amount, transaction_count))| TagDailyTotal {
+            tag,
+            currency,
+            date,
+            amount,
+            transaction_count,
+        })
+        .collect();
+    totals.sort_by_key(|total| total.date);
+    totals
+}
+
+// ==================== 仓储接口 ====================
+
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn insert_user(&self, user: &User) -> StorageResult<()>;
+    async fn find_user(&self, user_id: Uuid) -> StorageResult<Option<User>>;
+    async fn find_user_by_username(&self, username: &str) -> StorageResult<Option<User>>;
+    async fn update_user(&self, user: &User) -> StorageResult<()>;
+}
+
+#[async_trait]
+pub trait AccountRepository: Send + Sync {
+    async fn insert_account(&self, account: &Account) -> StorageResult<()>;
+    // 按创建时间和 id 升序，游标的排序值为 created_at
+    async fn list_accounts(&self, user_id: Uuid, limit: i64, start: PageStart) -> StorageResult<Vec<Account>>;
+    async fn count_accounts(&self, user_id: Uuid) -> StorageResult<i64>;
+    async fn find_account(&self, account_id: Uuid) -> StorageResult<Option<Account>>;
+    // 不修改余额，余额只随交易变化
+    async fn update_account(&self, account: &Account) -> StorageResult<()>;
+    // 涉及该账户的转账整笔删除，对方账户的余额随之冲销
+    async fn delete_account(&self, account_id: Uuid) -> StorageResult<bool>;
+    // 所有用户中设置了还款日的启用账户
+    async fn list_payment_due_accounts(&self) -> StorageResult<Vec<Account>>;
+    // 根据期初余额和全部交易重建余额，账户不存在时返回 None
+    async fn recalculate_balance(&self, account_id: Uuid) -> StorageResult<Option<Decimal>>;
+}
+
+#[async_trait]
+// 交易的写入、修改和删除与账户余额的调整在同一个数据库事务中完成
+pub trait TransactionRepository: Send + Sync {
+    async fn insert_transaction(&self, transaction: &Transaction) -> StorageResult<()>;
+    async fn list_transactions(
+        &self,
+        user_id: Uuid,
+        filter: &TransactionFilter,
+        sort: TransactionSort,
+        limit: i64,
+        start: PageStart,
+    ) -> StorageResult<Vec<Transaction>>;
+    async fn count_transactions(&self, user_id: Uuid, filter: &TransactionFilter) -> StorageResult<i64>;
+    async fn find_transaction(&self, transaction_id: Uuid) -> StorageResult<Option<Transaction>>;
+    async fn update_transaction(&self, transaction: &Transaction) -> StorageResult<()>;
+    async fn delete_transaction(&self, transaction_id: Uuid) -> StorageResult<bool>;
+    // 转账的各条交易作为整体写入、修改和删除
+    async fn insert_transfer(&self, legs: &[Transaction]) -> StorageResult<()>;
+    // 以 legs 替换转账现有的交易：已有的更新，新增的写入，不在 legs 中的删除
+    async fn update_transfer(&self, transfer_id: Uuid, legs: &[Transaction]) -> StorageResult<()>;
+    async fn delete_transfer(&self, transfer_id: Uuid) -> StorageResult<bool>;
+    async fn list_transfer_legs(&self, transfer_id: Uuid) -> StorageResult<Vec<Transaction>>;
+    // 按交易类型、货币和日期汇总符合条件的交易金额
+    async fn sum_transactions_by_day(
+        &self,
+        user_id: Uuid,
+        filter: &TransactionFilter,
+    ) -> StorageResult<Vec<DailyTotal>>;
+}
+
+#[async_trait]
+pub trait CategoryRepository: Send + Sync {
+    // 系统分类和该用户的分类，包括已归档的
+    async fn list_categories(&self, user_id: Uuid) -> StorageResult<Vec<Category>>;
+    // 系统分类在指定语言下的名称
+    async fn list_category_translations(&self, locale: &str) -> StorageResult<HashMap<Uuid, String>>;
+    async fn find_category(&self, category_id: Uuid) -> StorageResult<Option<Category>>;
+    async fn insert_category(&self, category: &Category) -> StorageResult<()>;
+    async fn update_category(&self, category: &Category) -> StorageResult<()>;
+    // 归档分类及其子分类
+    async fn archive_category(&self, category_id: Uuid) -> StorageResult<bool>;
+    // 来源分类的交易和预算改为目标分类，子分类移到目标分类下，然后归档来源分类，返回改动的交易数
+    async fn merge_category(&self, source_id: Uuid, target_id: Uuid) -> StorageResult<u64>;
+}
+
+// 标签按名称在用户内唯一，随交易自动创建；不再被任何交易引用的标签不列出
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    async fn list_tags(&self, user_id: Uuid) -> StorageResult<Vec<Tag>>;
+    // 交易和预算中的 source 标签替换为 target（不存在时创建），返回标签有变化的交易数
+    async fn merge_tag(&self, user_id: Uuid, source: &str, target: &str) -> StorageResult<u64>;
+    // 按标签、货币和日期汇总符合条件的交易金额
+    async fn sum_transactions_by_tag(
+        &self,
+        user_id: Uuid,
+        filter: &TransactionFilter,
+    ) -> StorageResult<Vec<TagDailyTotal>>;
+}
+
+#[async_trait]
+pub trait BudgetRepository: Send + Sync {
+    async fn insert_budget(&self, budget: &Budget) -> StorageResult<()>;
+    // 按创建时间升序
+    async fn list_budgets(&self, user_id: Uuid) -> StorageResult<Vec<Budget>>;
+    async fn find_budget(&self, budget_id: Uuid) -> StorageResult<Option<Budget>>;
+    async fn update_budget(&self, budget: &Budget) -> StorageResult<()>;
+    async fn delete_budget(&self, budget_id: Uuid) -> StorageResult<bool>;
+}
+
+// 修改重复交易时以 occurrence_count 作为版本号做乐观锁：
+// 存储中的次数与 expected_count 不一致说明已被其他请求或后台任务修改，此时不做任何修改并返回 false，
+// 保证同一次发生只生成一笔交易
+#[async_trait]
+pub trait RecurringTransactionRepository: Send + Sync {
+    async fn insert_recurring(&self, recurring: &RecurringTransaction) -> StorageResult<()>;
+    // 按创建时间升序
+    async fn list_recurring(&self, user_id: Uuid) -> StorageResult<Vec<RecurringTransaction>>;
+    // 所有用户中未暂停且下一次发生不晚于 date 的重复交易
+    async fn list_due_recurring(&self, date: NaiveDate) -> StorageResult<Vec<RecurringTransaction>>;
+    async fn find_recurring(&self, recurring_id: Uuid) -> StorageResult<Option<RecurringTransaction>>;
+    // successor 不为空时在同一事务中写入，用于从某次发生起拆分为新的重复交易
+    async fn update_recurring(
+        &self,
+        recurring: &RecurringTransaction,
+        expected_count: u32,
+        successor: Option<&RecurringTransaction>,
+    ) -> StorageResult<bool>;
+    // 更新重复交易并写入本次生成的交易
+    async fn post_recurring(
+        &self,
+        recurring: &RecurringTransaction,
+        expected_count: u32,
+        transaction: &Transaction,
+    ) -> StorageResult<bool>;
+    // 已生成的交易保留，不再关联重复交易
+    async fn delete_recurring(&self, recurring_id: Uuid) -> StorageResult<bool>;
+}
+
+#[async_trait]
+pub trait InstallmentRepository: Send + Sync {
+    // 记入手续费的重复交易在同一事务中写入
+    async fn insert_installment(
+        &self,
+        plan: &InstallmentPlan,
+        fee_charges: Option<&RecurringTransaction>,
+    ) -> StorageResult<()>;
+    // 按创建时间升序
+    async fn list_installments(&self, user_id: Uuid) -> StorageResult<Vec<InstallmentPlan>>;
+    async fn list_account_installments(&self, account_id: Uuid) -> StorageResult<Vec<InstallmentPlan>>;
+    async fn find_installment(&self, plan_id: Uuid) -> StorageResult<Option<InstallmentPlan>>;
+    async fn find_transaction_installment(&self, transaction_id: Uuid) -> StorageResult<Option<InstallmentPlan>>;
+    // 只修改 recurring_id、paid_off_date 和 updated_at
+    async fn update_installment(&self, plan: &InstallmentPlan) -> StorageResult<()>;
+}
+
+#[async_trait]
+pub trait InvestmentRepository: Send + Sync {
+    async fn insert_investment_event(&self, event: &InvestmentEvent) -> StorageResult<()>;
+    // 按事件时间、创建时间升序，即持仓重放的顺序
+    async fn list_investment_events(&self, account_id: Uuid) -> StorageResult<Vec<InvestmentEvent>>;
+    async fn find_investment_event(&self, event_id: Uuid) -> StorageResult<Option<InvestmentEvent>>;
+    async fn update_investment_event(&self, event: &InvestmentEvent) -> StorageResult<()>;
+    async fn delete_investment_event(&self, event_id: Uuid) -> StorageResult<bool>;
+}
+
+#[async_trait]
+pub trait SecurityPriceRepository: Send + Sync {
+    // 同一用户同一品种同一天的价格已存在时覆盖
+    async fn upsert_security_price(&self, price: &SecurityPrice) -> StorageResult<()>;
+    // 按品种、日期升序，symbol 为空时返回全部品种
+    async fn list_security_prices(&self, user_id: Uuid, symbol: Option<&str>) -> StorageResult<Vec<SecurityPrice>>;
+}
+
+#[async_trait]
+pub trait NotificationRepository: Send + Sync {
+    // 同一用户同一款项的同一到期日已有提醒时不写入并返回 false
+    async fn insert_notification(&self, notification: &Notification) -> StorageResult<bool>;
+    // 按到期日和创建时间降序
+    async fn list_notifications(&self, user_id: Uuid, unread_only: bool) -> StorageResult<Vec<Notification>>;
+    async fn find_notification(&self, notification_id: Uuid) -> StorageResult<Option<Notification>>;
+    // 已读的提醒保持原来的已读时间
+    async fn mark_notification_read(&self, notification_id: Uuid, read_at: DateTime<Utc>) -> StorageResult<()>;
+}
+
+#[async_trait]
+pub trait ExchangeRateRepository: Send + Sync {
+    // 同一货币对同一天的汇率已存在时覆盖
+    async fn upsert_exchange_rates(&self, rates: &[ExchangeRate]) -> StorageResult<()>;
+    // date 当天或之前最近一天的汇率
+    async fn find_exchange_rate(&self, base: &str, quote: &str, date: NaiveDate) -> StorageResult<Option<ExchangeRate>>;
+}
+
+// 完整的存储后端
+#[async_trait]
+pub trait Storage:
+    UserRepository
+    + AccountRepository
+    + TransactionRepository
+    + CategoryRepository
+    + TagRepository
+    + BudgetRepository
+    + RecurringTransactionRepository
+    + InstallmentRepository
+    + InvestmentRepository
+    + SecurityPriceRepository
+    + NotificationRepository
+    + ExchangeRateRepository
+    + std::fmt::Debug
+{
+    // 执行所有未应用的迁移
+    async fn run_migrations(&self) -> StorageResult<()>;
+    // 回滚最近一次迁移，返回被回滚的版本号
+    async fn revert_last_migration(&self) -> StorageResult<Option<i64>>;
+    // 列出所有迁移及其应用状态
+    async fn migration_status(&self) -> StorageResult<Vec<MigrationStatus>>;
+}
@@ -0,0 +1,895 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use super::{
+    daily_totals, tag_daily_totals, AccountRepository, BudgetRepository, CategoryRepository, DailyTotal,
+    ExchangeRateRepository, InstallmentRepository, InvestmentRepository, MigrationStatus, NotificationRepository, PageStart, RecurringTransactionRepository,
+    SecurityPriceRepository, SortValue, Storage,
+    StorageError, StorageResult, TagDailyTotal, TagRepository, TransactionFilter, TransactionRepository,
+    TransactionSort, UserRepository,
+};
+use crate::models::{
+    Account, Budget, Category, ExchangeRate, InstallmentPlan, InvestmentEvent, Notification, RecurringTransaction, SecurityPrice, SortOrder, Tag,
+    Transaction, TransactionSortField, TransactionType, User,
+};
+
+#[derive(Debug, Default)]
+struct MemoryData {
+    users: HashMap<Uuid, User>,
+    accounts: HashMap<Uuid, Account>,
+    transactions: HashMap<Uuid, Transaction>,
+    categories: HashMap<Uuid, Category>,
+    budgets: HashMap<Uuid, Budget>,
+    recurring: HashMap<Uuid, RecurringTransaction>,
+    installments: HashMap<Uuid, InstallmentPlan>,
+    investment_events: HashMap<Uuid, InvestmentEvent>,
+    security_prices: HashMap<(Uuid, String, NaiveDate), SecurityPrice>,
+    notifications: HashMap<Uuid, Notification>,
+    exchange_rates: HashMap<(String, String, NaiveDate), ExchangeRate>,
+}
+
+// 内存存储，用于单元测试和本地演示，进程退出后数据丢失
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    data: RwLock<MemoryData>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        let storage = Self::default();
+        storage.data.write().unwrap().categories =
+            system_categories().into_iter().map(|c| (c.id, c)).collect();
+        storage
+    }
+}
+
+// 与迁移脚本中的系统分类目录保持一致：(id, 交易类型, zh-CN 名称, en 名称, 图标, 颜色)
+const SYSTEM_CATEGORIES: &[(u128, TransactionType, &str, &str, &str, &str)] = &[
+    (0x101, TransactionType::Expense, "餐饮", "Food & Dining", "restaurant", "#FF9800"),
+    (0x102, TransactionType::Expense, "交通", "Transportation", "commute", "#2196F3"),
+    (0x103, TransactionType::Expense, "购物", "Shopping", "shopping_bag", "#E91E63"),
+    (0x104, TransactionType::Expense, "居住", "Housing", "home", "#795548"),
+    (0x105, TransactionType::Expense, "娱乐", "Entertainment", "movie", "#9C27B0"),
+    (0x106, TransactionType::Expense, "医疗", "Healthcare", "local_hospital", "#F44336"),
+    (0x107, TransactionType::Expense, "教育", "Education", "school", "#3F51B5"),
+    (0x108, TransactionType::Expense, "通讯", "Phone & Internet", "phone_android", "#00BCD4"),
+    (0x109, TransactionType::Expense, "旅行", "Travel", "flight", "#009688"),
+    (0x10a, TransactionType::Expense, "人情", "Gifts & Donations", "card_giftcard", "#FF5722"),
+    (0x1ff, TransactionType::Expense, "其他支出", "Other Expenses", "more_horiz", "#9E9E9E"),
+    (0x201, TransactionType::Income, "工资", "Salary", "work", "#4CAF50"),
+    (0x202, TransactionType::Income, "奖金", "Bonus", "emoji_events", "#8BC34A"),
+    (0x203, TransactionType::Income, "兼职", "Side Income", "work_outline", "#CDDC39"),
+    (0x204, TransactionType::Income, "理财收益", "Investment Income", "trending_up", "#FFC107"),
+    (0x205, TransactionType::Income, "礼金", "Gifts Received", "redeem", "#FFEB3B"),
+    (0x2ff, TransactionType::Income, "其他收入", "Other Income", "more_horiz", "#9E9E9E"),
+    (0x301, TransactionType::Investment, "股票", "Stocks", "show_chart", "#3F51B5"),
+    (0x302, TransactionType::Investment, "基金", "Funds", "pie_chart", "#673AB7"),
+    (0x303, TransactionType::Investment, "债券", "Bonds", "account_balance", "#607D8B"),
+    (0x304, TransactionType::Investment, "加密货币", "Crypto", "currency_bitcoin", "#FFC107"),
+    (0x3ff, TransactionType::Investment, "其他投资", "Other Investments", "more_horiz", "#9E9E9E"),
+];
+const CATALOG_VERSION: i32 = 1;
+
+fn system_categories() -> Vec<Category> {
+    SYSTEM_CATEGORIES
+        .iter()
+        .map(|&(id, transaction_type, name, _, icon, color)| Category {
+            id: Uuid::from_u128(id),
+            user_id: None,
+            parent_id: None,
+            name: name.to_string(),
+            icon: icon.to_string(),
+            color: color.to_string(),
+            transaction_type,
+            is_system: true,
+            is_archived: false,
+            catalog_version: Some(CATALOG_VERSION),
+        })
+        .collect()
+}
+
+// 调整账户余额，调用方需持有写锁
+fn adjust_balance(data: &mut MemoryData, account_id: Uuid, delta: Decimal, now: DateTime<Utc>) {
+    if delta.is_zero() {
+        return;
+    }
+    if let Some(account) = data.accounts.get_mut(&account_id) {
+        account.balance += delta;
+        account.updated_at = now;
+    }
+}
+
+fn insert_ledger_entry(data: &mut MemoryData, transaction: &Transaction) {
+    data.transactions.insert(transaction.id, transaction.clone());
+    adjust_balance(data, transaction.account_id, transaction.balance_effect(), transaction.updated_at);
+}
+
+fn update_ledger_entry(data: &mut MemoryData, transaction: &Transaction) {
+    let Some(old) = data.transactions.get(&transaction.id).cloned() else {
+        return;
+    };
+    data.transactions.insert(transaction.id, transaction.clone());
+    adjust_balance(data, old.account_id, -old.balance_effect(), transaction.updated_at);
+    adjust_balance(data, transaction.account_id, transaction.balance_effect(), transaction.updated_at);
+}
+
+fn delete_ledger_entry(data: &mut MemoryData, transaction_id: Uuid) -> bool {
+    let Some(old) = data.transactions.remove(&transaction_id) else {
+        return false;
+    };
+    adjust_balance(data, old.account_id, -old.balance_effect(), Utc::now());
+    data.installments.retain(|_, p| p.transaction_id != transaction_id);
+    true
+}
+
+// 游标分页时 items 已按游标筛选，从头开始取
+fn page<T: Clone>(items: Vec<&T>, limit: i64, start: PageStart) -> Vec<T> {
+    let offset = match start {
+        PageStart::Offset(offset) => offset,
+        PageStart::After(_) => 0,
+    };
+    items
+        .into_iter()
+        .skip(offset.max(0) as usize)
+        .take(limit.max(0) as usize)
+        .cloned()
+        .collect()
+}
+
+#[async_trait]
+impl UserRepository for MemoryStorage {
+    async fn insert_user(&self, user: &User) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if data.users.values().any(|u| u.username == user.username) {
+            return Err(StorageError::Conflict(format!("username {}", user.username)));
+        }
+        if data.users.values().any(|u| u.email == user.email) {
+            return Err(StorageError::Conflict(format!("email {}", user.email)));
+        }
+        data.users.insert(user.id, user.clone());
+        Ok(())
+    }
+
+    async fn find_user(&self, user_id: Uuid) -> StorageResult<Option<User>> {
+        Ok(self.data.read().unwrap().users.get(&user_id).cloned())
+    }
+
+    async fn find_user_by_username(&self, username: &str) -> StorageResult<Option<User>> {
+        let data = self.data.read().unwrap();
+        Ok(data.users.values().find(|u| u.username == username).cloned())
+    }
+
+    async fn update_user(&self, user: &User) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if let Some(existing) = data.users.get_mut(&user.id) {
+            *existing = user.clone();
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AccountRepository for MemoryStorage {
+    async fn insert_account(&self, account: &Account) -> StorageResult<()> {
+        self.data.write().unwrap().accounts.insert(account.id, account.clone());
+        Ok(())
+    }
+
+    async fn list_accounts(&self, user_id: Uuid, limit: i64, start: PageStart) -> StorageResult<Vec<Account>> {
+        let data = self.data.read().unwrap();
+        let mut accounts: Vec<&Account> = data
+            .accounts
+            .values()
+            .filter(|a| a.user_id == user_id)
+            .filter(|a| match start {
+                PageStart::After(cursor) => (SortValue::Time(a.created_at), a.id) > (cursor.value, cursor.id),
+                PageStart::Offset(_) => true,
+            })
+            .collect();
+        accounts.sort_by_key(|a| (a.created_at, a.id));
+        Ok(page(accounts, limit, start))
+    }
+
+    async fn count_accounts(&self, user_id: Uuid) -> StorageResult<i64> {
+        let data = self.data.read().unwrap();
+        Ok(data.accounts.values().filter(|a| a.user_id == user_id).count() as i64)
+    }
+
+    async fn find_account(&self, account_id: Uuid) -> StorageResult<Option<Account>> {
+        Ok(self.data.read().unwrap().accounts.get(&account_id).cloned())
+    }
+
+    async fn update_account(&self, account: &Account) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if let Some(existing) = data.accounts.get_mut(&account.id) {
+            // 余额只随交易变化
+            *existing = Account {
+                balance: existing.balance,
+                opening_balance: existing.opening_balance,
+                ..account.clone()
+            };
+        }
+        Ok(())
+    }
+
+    async fn delete_account(&self, account_id: Uuid) -> StorageResult<bool> {
+        let mut data = self.data.write().unwrap();
+        // 涉及该账户的转账整笔删除，冲销对方账户的余额
+        let transfer_ids: Vec<Uuid> = data
+            .transactions
+            .values()
+            .filter(|t| t.account_id == account_id)
+            .filter_map(|t| t.transfer_id)
+            .collect();
+        let leg_ids: Vec<Uuid> = data
+            .transactions
+            .values()
+            .filter(|t| t.transfer_id.is_some_and(|id| transfer_ids.contains(&id)))
+            .map(|t| t.id)
+            .collect();
+        for leg_id in leg_ids {
+            delete_ledger_entry(&mut data, leg_id);
+        }
+        // 与数据库外键 ON DELETE CASCADE 行为一致
+        data.transactions.retain(|_, t| t.account_id != account_id);
+        data.recurring.retain(|_, r| r.account_id != account_id);
+        data.installments.retain(|_, p| p.account_id != account_id);
+        data.investment_events.retain(|_, e| e.account_id != account_id);
+        Ok(data.accounts.remove(&account_id).is_some())
+    }
+
+    async fn list_payment_due_accounts(&self) -> StorageResult<Vec<Account>> {
+        let data = self.data.read().unwrap();
+        let mut accounts: Vec<Account> = data
+            .accounts
+            .values()
+            .filter(|a| a.is_active && a.payment_due_day.is_some())
+            .cloned()
+            .collect();
+        accounts.sort_by_key(|a| (a.created_at, a.id));
+        Ok(accounts)
+    }
+
+    async fn recalculate_balance(&self, account_id: Uuid) -> StorageResult<Option<Decimal>> {
+        let mut data = self.data.write().unwrap();
+        let ledger: Decimal = data
+            .transactions
+            .values()
+            .filter(|t| t.account_id == account_id)
+            .map(Transaction::balance_effect)
+            .sum();
+        let Some(account) = data.accounts.get_mut(&account_id) else {
+            return Ok(None);
+        };
+        account.balance = account.opening_balance + ledger;
+        account.updated_at = Utc::now();
+        Ok(Some(account.balance))
+    }
+}
+
+#[async_trait]
+impl TransactionRepository for MemoryStorage {
+    async fn insert_transaction(&self, transaction: &Transaction) -> StorageResult<()> {
+        insert_ledger_entry(&mut self.data.write().unwrap(), transaction);
+        Ok(())
+    }
+
+    async fn list_transactions(
+        &self,
+        user_id: Uuid,
+        filter: &TransactionFilter,
+        sort: TransactionSort,
+        limit: i64,
+        start: PageStart,
+    ) -> StorageResult<Vec<Transaction>> {
+        let data = self.data.read().unwrap();
+        let mut transactions: Vec<&Transaction> = data
+            .transactions
+            .values()
+            .filter(|t| t.user_id == user_id && filter.matches(t))
+            .filter(|t| match start {
+                PageStart::After(cursor) => sort.is_after(t, &cursor),
+                PageStart::Offset(_) => true,
+            })
+            .collect();
+        transactions.sort_by(|a, b| {
+            let ordering = match sort.field {
+                TransactionSortField::TransactionDate => a.transaction_date.cmp(&b.transaction_date),
+                TransactionSortField::Amount => a.amount.cmp(&b.amount),
+                TransactionSortField::CreatedAt => a.created_at.cmp(&b.created_at),
+            }
+            .then(a.id.cmp(&b.id));
+            match sort.order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        });
+        Ok(page(transactions, limit, start))
+    }
+
+    async fn count_transactions(&self, user_id: Uuid, filter: &TransactionFilter) -> StorageResult<i64> {
+        let data = self.data.read().unwrap();
+        Ok(data
+            .transactions
+            .values()
+            .filter(|t| t.user_id == user_id && filter.matches(t))
+            .count() as i64)
+    }
+
+    async fn find_transaction(&self, transaction_id: Uuid) -> StorageResult<Option<Transaction>> {
+        Ok(self.data.read().unwrap().transactions.get(&transaction_id).cloned())
+    }
+
+    async fn update_transaction(&self, transaction: &Transaction) -> StorageResult<()> {
+        update_ledger_entry(&mut self.data.write().unwrap(), transaction);
+        Ok(())
+    }
+
+    async fn delete_transaction(&self, transaction_id: Uuid) -> StorageResult<bool> {
+        Ok(delete_ledger_entry(&mut self.data.write().unwrap(), transaction_id))
+    }
+
+    async fn insert_transfer(&self, legs: &[Transaction]) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        for leg in legs {
+            insert_ledger_entry(&mut data, leg);
+        }
+        Ok(())
+    }
+
+    async fn update_transfer(&self, transfer_id: Uuid, legs: &[Transaction]) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        let existing_ids: Vec<Uuid> = data
+            .transactions
+            .values()
+            .filter(|t| t.transfer_id == Some(transfer_id))
+            .map(|t| t.id)
+            .collect();
+        for leg_id in existing_ids.iter().filter(|id| !legs.iter().any(|leg| leg.id == **id)) {
+            delete_ledger_entry(&mut data, *leg_id);
+        }
+        for leg in legs {
+            if existing_ids.contains(&leg.id) {
+                update_ledger_entry(&mut data, leg);
+            } else {
+                insert_ledger_entry(&mut data, leg);
+            }
+        }
+        Ok(())
+    }
+
+    async fn delete_transfer(&self, transfer_id: Uuid) -> StorageResult<bool> {
+        let mut data = self.data.write().unwrap();
+        let leg_ids: Vec<Uuid> = data
+            .transactions
+            .values()
+            .filter(|t| t.transfer_id == Some(transfer_id))
+            .map(|t| t.id)
+            .collect();
+        for leg_id in &leg_ids {
+            delete_ledger_entry(&mut data, *leg_id);
+        }
+        Ok(!leg_ids.is_empty())
+    }
+
+    async fn list_transfer_legs(&self, transfer_id: Uuid) -> StorageResult<Vec<Transaction>> {
+        let data = self.data.read().unwrap();
+        let mut legs: Vec<Transaction> = data
+            .transactions
+            .values()
+            .filter(|t| t.transfer_id == Some(transfer_id))
+            .cloned()
+            .collect();
+        legs.sort_by_key(|t| (t.created_at, t.id));
+        Ok(legs)
+    }
+
+    async fn sum_transactions_by_day(
+        &self,
+        user_id: Uuid,
+        filter: &TransactionFilter,
+    ) -> StorageResult<Vec<DailyTotal>> {
+        let data = self.data.read().unwrap();
+        Ok(daily_totals(
+            data.transactions
+                .values()
+                .filter(|t| t.user_id == user_id && filter.matches(t))
+                .map(|t| (t.transaction_type, t.currency.clone(), t.transaction_date, t.amount)),
+        ))
+    }
+}
+
+#[async_trait]
+impl BudgetRepository for MemoryStorage {
+    async fn insert_budget(&self, budget: &Budget) -> StorageResult<()> {
+        self.data.write().unwrap().budgets.insert(budget.id, budget.clone());
+        Ok(())
+    }
+
+    async fn list_budgets(&self, user_id: Uuid) -> StorageResult<Vec<Budget>> {
+        let data = self.data.read().unwrap();
+        let mut budgets: Vec<Budget> = data
+            .budgets
+            .values()
+            .filter(|b| b.user_id == user_id)
+            .cloned()
+            .collect();
+        budgets.sort_by_key(|b| (b.created_at, b.id));
+        Ok(budgets)
+    }
+
+    async fn find_budget(&self, budget_id: Uuid) -> StorageResult<Option<Budget>> {
+        Ok(self.data.read().unwrap().budgets.get(&budget_id).cloned())
+    }
+
+    async fn update_budget(&self, budget: &Budget) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if let Some(existing) = data.budgets.get_mut(&budget.id) {
+            *existing = budget.clone();
+        }
+        Ok(())
+    }
+
+    async fn delete_budget(&self, budget_id: Uuid) -> StorageResult<bool> {
+        Ok(self.data.write().unwrap().budgets.remove(&budget_id).is_some())
+    }
+}
+
+#[async_trait]
+impl RecurringTransactionRepository for MemoryStorage {
+    async fn insert_recurring(&self, recurring: &RecurringTransaction) -> StorageResult<()> {
+        self.data.write().unwrap().recurring.insert(recurring.id, recurring.clone());
+        Ok(())
+    }
+
+    async fn list_recurring(&self, user_id: Uuid) -> StorageResult<Vec<RecurringTransaction>> {
+        let data = self.data.read().unwrap();
+        let mut recurring: Vec<RecurringTransaction> = data
+            .recurring
+            .values()
+            .filter(|r| r.user_id == user_id)
+            .cloned()
+            .collect();
+        recurring.sort_by_key(|r| (r.created_at, r.id));
+        Ok(recurring)
+    }
+
+    async fn list_due_recurring(&self, date: NaiveDate) -> StorageResult<Vec<RecurringTransaction>> {
+        let data = self.data.read().unwrap();
+        let mut recurring: Vec<RecurringTransaction> = data
+            .recurring
+            .values()
+            .filter(|r| !r.is_paused && r.next_date.is_some_and(|next| next <= date))
+            .cloned()
+            .collect();
+        recurring.sort_by_key(|r| (r.next_date, r.id));
+        Ok(recurring)
+    }
+
+    async fn find_recurring(&self, recurring_id: Uuid) -> StorageResult<Option<RecurringTransaction>> {
+        Ok(self.data.read().unwrap().recurring.get(&recurring_id).cloned())
+    }
+
+    async fn update_recurring(
+        &self,
+        recurring: &RecurringTransaction,
+        expected_count: u32,
+        successor: Option<&RecurringTransaction>,
+    ) -> StorageResult<bool> {
+        let mut data = self.data.write().unwrap();
+        if !update_recurring_entry(&mut data, recurring, expected_count) {
+            return Ok(false);
+        }
+        if let Some(successor) = successor {
+            data.recurring.insert(successor.id, successor.clone());
+        }
+        Ok(true)
+    }
+
+    async fn post_recurring(
+        &self,
+        recurring: &RecurringTransaction,
+        expected_count: u32,
+        transaction: &Transaction,
+    ) -> StorageResult<bool> {
+        let mut data = self.data.write().unwrap();
+        if !update_recurring_entry(&mut data, recurring, expected_count) {
+            return Ok(false);
+        }
+        insert_ledger_entry(&mut data, transaction);
+        Ok(true)
+    }
+
+    async fn delete_recurring(&self, recurring_id: Uuid) -> StorageResult<bool> {
+        let mut data = self.data.write().unwrap();
+        for transaction in data.transactions.values_mut() {
+            if transaction.recurring_id == Some(recurring_id) {
+                transaction.recurring_id = None;
+            }
+        }
+        Ok(data.recurring.remove(&recurring_id).is_some())
+    }
+}
+
+// 与 SQL 实现相同的乐观锁：次数与 expected_count 一致时才更新
+fn update_recurring_entry(data: &mut MemoryData, recurring: &RecurringTransaction, expected_count: u32) -> bool {
+    match data.recurring.get_mut(&recurring.id) {
+        Some(existing) if existing.occurrence_count == expected_count => {
+            *existing = recurring.clone();
+            true
+        }
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl InstallmentRepository for MemoryStorage {
+    async fn insert_installment(
+        &self,
+        plan: &InstallmentPlan,
+        fee_charges: Option<&RecurringTransaction>,
+    ) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if let Some(fee_charges) = fee_charges {
+            data.recurring.insert(fee_charges.id, fee_charges.clone());
+        }
+        data.installments.insert(plan.id, plan.clone());
+        Ok(())
+    }
+
+    async fn list_installments(&self, user_id: Uuid) -> StorageResult<Vec<InstallmentPlan>> {
+        let data = self.data.read().unwrap();
+        let mut plans: Vec<InstallmentPlan> =
+            data.installments.values().filter(|p| p.user_id == user_id).cloned().collect();
+        plans.sort_by_key(|p| (p.created_at, p.id));
+        Ok(plans)
+    }
+
+    async fn list_account_installments(&self, account_id: Uuid) -> StorageResult<Vec<InstallmentPlan>> {
+        let data = self.data.read().unwrap();
+        let mut plans: Vec<InstallmentPlan> =
+            data.installments.values().filter(|p| p.account_id == account_id).cloned().collect();
+        plans.sort_by_key(|p| (p.created_at, p.id));
+        Ok(plans)
+    }
+
+    async fn find_installment(&self, plan_id: Uuid) -> StorageResult<Option<InstallmentPlan>> {
+        Ok(self.data.read().unwrap().installments.get(&plan_id).cloned())
+    }
+
+    async fn find_transaction_installment(&self, transaction_id: Uuid) -> StorageResult<Option<InstallmentPlan>> {
+        let data = self.data.read().unwrap();
+        Ok(data.installments.values().find(|p| p.transaction_id == transaction_id).cloned())
+    }
+
+    async fn update_installment(&self, plan: &InstallmentPlan) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if let Some(existing) = data.installments.get_mut(&plan.id) {
+            existing.recurring_id = plan.recurring_id;
+            existing.paid_off_date = plan.paid_off_date;
+            existing.updated_at = plan.updated_at;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InvestmentRepository for MemoryStorage {
+    async fn insert_investment_event(&self, event: &InvestmentEvent) -> StorageResult<()> {
+        self.data.write().unwrap().investment_events.insert(event.id, event.clone());
+        Ok(())
+    }
+
+    async fn list_investment_events(&self, account_id: Uuid) -> StorageResult<Vec<InvestmentEvent>> {
+        let data = self.data.read().unwrap();
+        let mut events: Vec<InvestmentEvent> = data
+            .investment_events
+            .values()
+            .filter(|e| e.account_id == account_id)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| (e.event_date, e.created_at, e.id));
+        Ok(events)
+    }
+
+    async fn find_investment_event(&self, event_id: Uuid) -> StorageResult<Option<InvestmentEvent>> {
+        Ok(self.data.read().unwrap().investment_events.get(&event_id).cloned())
+    }
+
+    async fn update_investment_event(&self, event: &InvestmentEvent) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if let Some(existing) = data.investment_events.get_mut(&event.id) {
+            *existing = event.clone();
+        }
+        Ok(())
+    }
+
+    async fn delete_investment_event(&self, event_id: Uuid) -> StorageResult<bool> {
+        Ok(self.data.write().unwrap().investment_events.remove(&event_id).is_some())
+    }
+}
+
+#[async_trait]
+impl SecurityPriceRepository for MemoryStorage {
+    async fn upsert_security_price(&self, price: &SecurityPrice) -> StorageResult<()> {
+        let key = (price.user_id, price.symbol.clone(), price.price_date);
+        self.data.write().unwrap().security_prices.insert(key, price.clone());
+        Ok(())
+    }
+
+    async fn list_security_prices(&self, user_id: Uuid, symbol: Option<&str>) -> StorageResult<Vec<SecurityPrice>> {
+        let data = self.data.read().unwrap();
+        let mut prices: Vec<SecurityPrice> = data
+            .security_prices
+            .values()
+            .filter(|p| p.user_id == user_id && symbol.is_none_or(|symbol| p.symbol == symbol))
+            .cloned()
+            .collect();
+        prices.sort_by(|a, b| (&a.symbol, a.price_date).cmp(&(&b.symbol, b.price_date)));
+        Ok(prices)
+    }
+}
+
+#[async_trait]
+impl NotificationRepository for MemoryStorage {
+    async fn insert_notification(&self, notification: &Notification) -> StorageResult<bool> {
+        let mut data = self.data.write().unwrap();
+        let exists = data.notifications.values().any(|n| {
+            n.user_id == notification.user_id
+                && n.kind == notification.kind
+                && n.source_id == notification.source_id
+                && n.due_date == notification.due_date
+        });
+        if !exists {
+            data.notifications.insert(notification.id, notification.clone());
+        }
+        Ok(!exists)
+    }
+
+    async fn list_notifications(&self, user_id: Uuid, unread_only: bool) -> StorageResult<Vec<Notification>> {
+        let data = self.data.read().unwrap();
+        let mut notifications: Vec<Notification> = data
+            .notifications
+            .values()
+            .filter(|n| n.user_id == user_id && (!unread_only || n.read_at.is_none()))
+            .cloned()
+            .collect();
+        notifications.sort_by(|a, b| {
+            (b.due_date, b.created_at)
+                .cmp(&(a.due_date, a.created_at))
+                .then(a.id.cmp(&b.id))
+        });
+        Ok(notifications)
+    }
+
+    async fn find_notification(&self, notification_id: Uuid) -> StorageResult<Option<Notification>> {
+        Ok(self.data.read().unwrap().notifications.get(&notification_id).cloned())
+    }
+
+    async fn mark_notification_read(&self, notification_id: Uuid, read_at: DateTime<Utc>) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if let Some(notification) = data.notifications.get_mut(&notification_id) {
+            notification.read_at.get_or_insert(read_at);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExchangeRateRepository for MemoryStorage {
+    async fn upsert_exchange_rates(&self, rates: &[ExchangeRate]) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        for rate in rates {
+            data.exchange_rates.insert(
+                (rate.base_currency.clone(), rate.quote_currency.clone(), rate.rate_date),
+                rate.clone(),
+            );
+        }
+        Ok(())
+    }
+
+    async fn find_exchange_rate(&self, base: &str, quote: &str, date: NaiveDate) -> StorageResult<Option<ExchangeRate>> {
+        let data = self.data.read().unwrap();
+        Ok(data
+            .exchange_rates
+            .values()
+            .filter(|r| r.base_currency == base && r.quote_currency == quote && r.rate_date <= date)
+            .max_by_key(|r| r.rate_date)
+            .cloned())
+    }
+}
+
+#[async_trait]
+impl CategoryRepository for MemoryStorage {
+    async fn list_categories(&self, user_id: Uuid) -> StorageResult<Vec<Category>> {
+        let data = self.data.read().unwrap();
+        let mut categories: Vec<Category> = data
+            .categories
+            .values()
+            .filter(|c| c.is_system || c.user_id == Some(user_id))
+            .cloned()
+            .collect();
+        categories.sort_by(|a, b| {
+            (a.transaction_type.as_str(), &a.name, a.id).cmp(&(b.transaction_type.as_str(), &b.name, b.id))
+        });
+        Ok(categories)
+    }
+
+    async fn list_category_translations(&self, locale: &str) -> StorageResult<HashMap<Uuid, String>> {
+        Ok(SYSTEM_CATEGORIES
+            .iter()
+            .filter_map(|&(id, _, zh_cn, en, _, _)| {
+                let name = match locale {
+                    "zh-CN" => zh_cn,
+                    "en" => en,
+                    _ => return None,
+                };
+                Some((Uuid::from_u128(id), name.to_string()))
+            })
+            .collect())
+    }
+
+    async fn find_category(&self, category_id: Uuid) -> StorageResult<Option<Category>> {
+        Ok(self.data.read().unwrap().categories.get(&category_id).cloned())
+    }
+
+    async fn insert_category(&self, category: &Category) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if data.categories.contains_key(&category.id) {
+            return Err(StorageError::Conflict(format!("category {}", category.id)));
+        }
+        data.categories.insert(category.id, category.clone());
+        Ok(())
+    }
+
+    async fn update_category(&self, category: &Category) -> StorageResult<()> {
+        let mut data = self.data.write().unwrap();
+        if let Some(existing) = data.categories.get_mut(&category.id) {
+            *existing = category.clone();
+        }
+        Ok(())
+    }
+
+    async fn archive_category(&self, category_id: Uuid) -> StorageResult<bool> {
+        let mut data = self.data.write().unwrap();
+        let mut archived = false;
+        for category in data.categories.values_mut() {
+            if category.id == category_id || category.parent_id == Some(category_id) {
+                category.is_archived = true;
+                archived = true;
+            }
+        }
+        Ok(archived)
+    }
+
+    async fn merge_category(&self, source_id: Uuid, target_id: Uuid) -> StorageResult<u64> {
+        let mut data = self.data.write().unwrap();
+        let now = Utc::now();
+        let mut moved = 0;
+        for transaction in data.transactions.values_mut() {
+            if transaction.category_id == Some(source_id) {
+                transaction.category_id = Some(target_id);
+                transaction.updated_at = now;
+                moved += 1;
+            }
+        }
+        for category in data.categories.values_mut() {
+            if category.parent_id == Some(source_id) {
+                category.parent_id = Some(target_id);
+            }
+            if category.id == source_id {
+                category.is_archived = true;
+            }
+        }
+        for budget in data.budgets.values_mut() {
+            if budget.category_id == Some(source_id) {
+                budget.category_id = Some(target_id);
+                budget.updated_at = now;
+            }
+        }
+        for recurring in data.recurring.values_mut() {
+            if recurring.category_id == Some(source_id) {
+                recurring.category_id = Some(target_id);
+                recurring.updated_at = now;
+            }
+        }
+        Ok(moved)
+    }
+}
+
+// 内存存储不单独保存标签，标签从交易中汇总
+#[async_trait]
+impl TagRepository for MemoryStorage {
+    async fn list_tags(&self, user_id: Uuid) -> StorageResult<Vec<Tag>> {
+        let data = self.data.read().unwrap();
+        let mut tags: HashMap<&str, Tag> = HashMap::new();
+        for transaction in data.transactions.values().filter(|t| t.user_id == user_id) {
+            for name in &transaction.tags {
+                let tag = tags.entry(name).or_insert_with(|| Tag {
+                    name: name.clone(),
+                    usage_count: 0,
+                    last_used_at: None,
+                });
+                tag.usage_count += 1;
+                tag.last_used_at = tag.last_used_at.max(Some(transaction.transaction_date));
+            }
+        }
+        let mut tags: Vec<Tag> = tags.into_values().collect();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tags)
+    }
+
+    async fn merge_tag(&self, user_id: Uuid, source: &str, target: &str) -> StorageResult<u64> {
+        let mut data = self.data.write().unwrap();
+        let now = Utc::now();
+        let mut updated = 0;
+        for transaction in data.transactions.values_mut().filter(|t| t.user_id == user_id) {
+            if source == target || !transaction.tags.iter().any(|tag| tag == source) {
+                continue;
+            }
+            transaction.tags.retain(|tag| tag != source);
+            if !transaction.tags.iter().any(|tag| tag == target) {
+                transaction.tags.push(target.to_string());
+                transaction.tags.sort();
+            }
+            transaction.updated_at = now;
+            updated += 1;
+        }
+        for budget in data.budgets.values_mut() {
+            if source != target && budget.user_id == user_id && budget.tag.as_deref() == Some(source) {
+                budget.tag = Some(target.to_string());
+                budget.updated_at = now;
+            }
+        }
+        for recurring in data.recurring.values_mut().filter(|r| r.user_id == user_id) {
+            if source == target || !recurring.tags.iter().any(|tag| tag == source) {
+                continue;
+            }
+            recurring.tags.retain(|tag| tag != source);
+            if !recurring.tags.iter().any(|tag| tag == target) {
+                recurring.tags.push(target.to_string());
+            }
+            recurring.updated_at = now;
+        }
+        Ok(updated)
+    }
+
+    async fn sum_transactions_by_tag(
+        &self,
+        user_id: Uuid,
+        filter: &TransactionFilter,
+    ) -> StorageResult<Vec<TagDailyTotal>> {
+        let data = self.data.read().unwrap();
+        let rows = data
+            .transactions
+            .values()
+            .filter(|t| t.user_id == user_id && filter.matches(t))
+            .flat_map(|t| {
+                t.tags
+                    .iter()
+                    .map(|tag| (tag.clone(), t.currency.clone(), t.transaction_date, t.amount))
+            });
+        Ok(tag_daily_totals(rows))
+    }
+}
+
+// 内存存储没有表结构，迁移均为空操作
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn run_migrations(&self) -> StorageResult<()> {
+        Ok(())
+    }
+
+    async fn revert_last_migration(&self) -> StorageResult<Option<i64>> {
+        Ok(None)
+    }
+
+    async fn migration_status(&self) -> StorageResult<Vec<MigrationStatus>> {
+        Ok(Vec::new())
+    }
+}
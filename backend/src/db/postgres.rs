@@ -0,0 +1,26 @@
+use sqlx::migrate::Migrator;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::Postgres;
+
+use super::sql::impl_sql_storage;
+
+// 编译期嵌入 migrations/postgres 下的迁移脚本
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+// PostgreSQL 存储
+#[derive(Debug, Clone)]
+pub struct PostgresStorage {
+    pool: PgPool,
+}
+
+impl PostgresStorage {
+    pub async fn connect(database_url: &str) -> Result<Self, sqlx::Error> {
+        let pool = PgPoolOptions::new()
+            .max_connections(10)
+            .connect(database_url)
+            .await?;
+        Ok(Self { pool })
+    }
+}
+
+impl_sql_storage!(PostgresStorage, Postgres, MIGRATOR, ("", ""));
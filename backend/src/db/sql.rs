@@ -0,0 +1,2179 @@
+// PostgreSQL 与 SQLite 共用的 SQL 实现
+//
+// 两个后端的 SQL 语句完全一致（SQLite 同样支持 $N 占位符），
+// 差异只在列类型上：金额在 PostgreSQL 中为 NUMERIC，在 SQLite 中以 TEXT 精确保存，
+// 由 Money 类型分别编解码；按金额比较和排序时由各后端提供数值转换表达式。
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::migrate::Migrator;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::sqlite::{SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Encode, Postgres, Sqlite, Type};
+use uuid::Uuid;
+
+use crate::db::MigrationStatus;
+use crate::models::{
+    Account, Budget, Category, ExchangeRate, InstallmentPlan, InvestmentEvent, LoanTerms, Notification, RecurrenceSchedule, RecurringTransaction, SecurityPrice,
+    Transaction, User,
+};
+
+// ==================== 金额列 ====================
+
+#[derive(Debug, Clone, Copy)]
+pub struct Money(pub Decimal);
+
+impl Type<Postgres> for Money {
+    fn type_info() -> PgTypeInfo {
+        <Decimal as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <Decimal as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for Money {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <Decimal as Encode<Postgres>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Money {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Money(<Decimal as Decode<Postgres>>::decode(value)?))
+    }
+}
+
+impl Type<Sqlite> for Money {
+    fn type_info() -> SqliteTypeInfo {
+        <String as Type<Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <String as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for Money {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
+        <String as Encode<Sqlite>>::encode(self.0.to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Money {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        let text = <&str as Decode<Sqlite>>::decode(value)?;
+        Ok(Money(text.parse()?))
+    }
+}
+
+// ==================== 数据库实体 ====================
+
+// 枚举字段解析失败时转换为解码错误
+pub fn decode_error(message: String) -> sqlx::Error {
+    sqlx::Error::Decode(message.into())
+}
+
+// 用户表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct UserEntity {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub base_currency: String,
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<UserEntity> for User {
+    fn from(entity: UserEntity) -> Self {
+        Self {
+            id: entity.id,
+            username: entity.username,
+            email: entity.email,
+            display_name: entity.display_name,
+            avatar_url: entity.avatar_url,
+            base_currency: entity.base_currency,
+            password_hash: entity.password_hash,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        }
+    }
+}
+
+// 账户表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct AccountEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub account_type: String,
+    pub currency: String,
+    pub balance: Money,
+    pub opening_balance: Money,
+    pub credit_limit: Option<Money>,
+    pub statement_closing_day: Option<i32>,
+    pub payment_due_day: Option<i32>,
+    pub loan_principal: Option<Money>,
+    pub loan_interest_rate: Option<Money>,
+    pub loan_term_months: Option<i32>,
+    pub loan_first_payment_date: Option<NaiveDate>,
+    pub loan_repayment_method: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<AccountEntity> for Account {
+    type Error = sqlx::Error;
+
+    fn try_from(entity: AccountEntity) -> Result<Self, Self::Error> {
+        let loan = match (
+            entity.loan_principal,
+            entity.loan_interest_rate,
+            entity.loan_term_months,
+            entity.loan_first_payment_date,
+            entity.loan_repayment_method,
+        ) {
+            (Some(principal), Some(rate), Some(term_months), Some(first_payment_date), Some(method)) => Some(LoanTerms {
+                principal: principal.0.normalize(),
+                annual_interest_rate: rate.0.normalize(),
+                term_months: term_months as u32,
+                first_payment_date,
+                repayment_method: method.parse().map_err(decode_error)?,
+            }),
+            _ => None,
+        };
+        Ok(Self {
+            id: entity.id,
+            user_id: entity.user_id,
+            name: entity.name,
+            account_type: entity.account_type.parse().map_err(decode_error)?,
+            currency: entity.currency,
+            balance: entity.balance.0,
+            opening_balance: entity.opening_balance.0,
+            credit_limit: entity.credit_limit.map(|limit| limit.0.normalize()),
+            statement_closing_day: entity.statement_closing_day.map(|day| day as u32),
+            payment_due_day: entity.payment_due_day.map(|day| day as u32),
+            loan,
+            is_active: entity.is_active,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        })
+    }
+}
+
+// 汇率表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExchangeRateEntity {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate_date: NaiveDate,
+    pub rate: Money,
+    pub source: String,
+}
+
+impl From<ExchangeRateEntity> for ExchangeRate {
+    fn from(entity: ExchangeRateEntity) -> Self {
+        Self {
+            base_currency: entity.base_currency,
+            quote_currency: entity.quote_currency,
+            rate_date: entity.rate_date,
+            rate: entity.rate.0.normalize(),
+            source: entity.source,
+        }
+    }
+}
+
+// 分类表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct CategoryEntity {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    pub icon: String,
+    pub color: String,
+    pub transaction_type: String,
+    pub is_system: bool,
+    pub is_archived: bool,
+    pub catalog_version: Option<i32>,
+}
+
+impl TryFrom<CategoryEntity> for Category {
+    type Error = sqlx::Error;
+
+    fn try_from(entity: CategoryEntity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: entity.id,
+            user_id: entity.user_id,
+            parent_id: entity.parent_id,
+            name: entity.name,
+            icon: entity.icon,
+            color: entity.color,
+            transaction_type: entity.transaction_type.parse().map_err(decode_error)?,
+            is_system: entity.is_system,
+            is_archived: entity.is_archived,
+            catalog_version: entity.catalog_version,
+        })
+    }
+}
+
+// 预算表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct BudgetEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub category_id: Option<Uuid>,
+    pub tag: Option<String>,
+    pub period: String,
+    pub amount: Money,
+    pub currency: String,
+    pub rollover: bool,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<BudgetEntity> for Budget {
+    type Error = sqlx::Error;
+
+    fn try_from(entity: BudgetEntity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: entity.id,
+            user_id: entity.user_id,
+            name: entity.name,
+            category_id: entity.category_id,
+            tag: entity.tag,
+            period: entity.period.parse().map_err(decode_error)?,
+            amount: entity.amount.0.normalize(),
+            currency: entity.currency,
+            rollover: entity.rollover,
+            start_date: entity.start_date,
+            end_date: entity.end_date,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        })
+    }
+}
+
+// 重复交易表实体，标签以 JSON 数组保存
+#[derive(Debug, sqlx::FromRow)]
+pub struct RecurringTransactionEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub transaction_type: String,
+    pub amount: Money,
+    pub description: String,
+    pub notes: Option<String>,
+    pub tags: String,
+    pub frequency: String,
+    pub interval_count: i32,
+    pub day_of_month: Option<i32>,
+    pub last_business_day: bool,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub occurrence_limit: Option<i32>,
+    pub next_date: Option<NaiveDate>,
+    pub occurrence_count: i32,
+    pub is_paused: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<RecurringTransactionEntity> for RecurringTransaction {
+    type Error = sqlx::Error;
+
+    fn try_from(entity: RecurringTransactionEntity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: entity.id,
+            user_id: entity.user_id,
+            account_id: entity.account_id,
+            category_id: entity.category_id,
+            transaction_type: entity.transaction_type.parse().map_err(decode_error)?,
+            amount: entity.amount.0.normalize(),
+            description: entity.description,
+            notes: entity.notes,
+            tags: serde_json::from_str(&entity.tags).map_err(|err| decode_error(err.to_string()))?,
+            schedule: RecurrenceSchedule {
+                frequency: entity.frequency.parse().map_err(decode_error)?,
+                interval: entity.interval_count as u32,
+                day_of_month: entity.day_of_month.map(|day| day as u32),
+                last_business_day: entity.last_business_day,
+                start_date: entity.start_date,
+                end_date: entity.end_date,
+                count: entity.occurrence_limit.map(|count| count as u32),
+            },
+            next_date: entity.next_date,
+            occurrence_count: entity.occurrence_count as u32,
+            is_paused: entity.is_paused,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        })
+    }
+}
+
+// 分期表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct InstallmentPlanEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub transaction_id: Uuid,
+    pub principal: Money,
+    pub periods: i32,
+    pub fee_per_period: Money,
+    pub first_period_date: NaiveDate,
+    pub recurring_id: Option<Uuid>,
+    pub paid_off_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<InstallmentPlanEntity> for InstallmentPlan {
+    fn from(entity: InstallmentPlanEntity) -> Self {
+        Self {
+            id: entity.id,
+            user_id: entity.user_id,
+            account_id: entity.account_id,
+            transaction_id: entity.transaction_id,
+            principal: entity.principal.0.normalize(),
+            periods: entity.periods as u32,
+            fee_per_period: entity.fee_per_period.0.normalize(),
+            first_period_date: entity.first_period_date,
+            recurring_id: entity.recurring_id,
+            paid_off_date: entity.paid_off_date,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        }
+    }
+}
+
+// 投资事件表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct InvestmentEventEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub symbol: String,
+    pub asset_class: String,
+    pub event_type: String,
+    pub event_date: DateTime<Utc>,
+    pub quantity: Option<Money>,
+    pub price: Option<Money>,
+    pub amount: Option<Money>,
+    pub fee: Money,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<InvestmentEventEntity> for InvestmentEvent {
+    type Error = sqlx::Error;
+
+    fn try_from(entity: InvestmentEventEntity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: entity.id,
+            user_id: entity.user_id,
+            account_id: entity.account_id,
+            symbol: entity.symbol,
+            asset_class: entity.asset_class.parse().map_err(decode_error)?,
+            event_type: entity.event_type.parse().map_err(decode_error)?,
+            event_date: entity.event_date,
+            quantity: entity.quantity.map(|quantity| quantity.0.normalize()),
+            price: entity.price.map(|price| price.0.normalize()),
+            amount: entity.amount.map(|amount| amount.0.normalize()),
+            fee: entity.fee.0.normalize(),
+            notes: entity.notes,
+            created_at: entity.created_at,
+            updated_at: entity.updated_at,
+        })
+    }
+}
+
+// 品种价格表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct SecurityPriceEntity {
+    pub user_id: Uuid,
+    pub symbol: String,
+    pub price_date: NaiveDate,
+    pub price: Money,
+    pub currency: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SecurityPriceEntity> for SecurityPrice {
+    fn from(entity: SecurityPriceEntity) -> Self {
+        Self {
+            user_id: entity.user_id,
+            symbol: entity.symbol,
+            price_date: entity.price_date,
+            price: entity.price.0.normalize(),
+            currency: entity.currency,
+            updated_at: entity.updated_at,
+        }
+    }
+}
+
+// 到期提醒表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct NotificationEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub source_id: Uuid,
+    pub due_date: NaiveDate,
+    pub title: String,
+    pub amount: Money,
+    pub currency: String,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<NotificationEntity> for Notification {
+    type Error = sqlx::Error;
+
+    fn try_from(entity: NotificationEntity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: entity.id,
+            user_id: entity.user_id,
+            kind: entity.kind.parse().map_err(decode_error)?,
+            source_id: entity.source_id,
+            due_date: entity.due_date,
+            title: entity.title,
+            amount: entity.amount.0.normalize(),
+            currency: entity.currency,
+            created_at: entity.created_at,
+            read_at: entity.read_at,
+        })
+    }
+}
+
+// 交易表实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct TransactionEntity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub transaction_type: String,
+    pub amount: Money,
+    pub currency: String,
+    pub description: String,
+    pub notes: Option<String>,
+    pub transaction_date: DateTime<Utc>,
+    pub transfer_id: Option<Uuid>,
+    pub transfer_direction: Option<String>,
+    pub recurring_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TransactionEntity {
+    // 标签存放在关联表中，需要单独查询后组装
+    pub fn into_model(self, tags: Vec<String>) -> Result<Transaction, sqlx::Error> {
+        Ok(Transaction {
+            id: self.id,
+            user_id: self.user_id,
+            account_id: self.account_id,
+            category_id: self.category_id,
+            transaction_type: self.transaction_type.parse().map_err(decode_error)?,
+            amount: self.amount.0,
+            currency: self.currency,
+            description: self.description,
+            notes: self.notes,
+            tags,
+            transaction_date: self.transaction_date,
+            transfer_id: self.transfer_id,
+            transfer_direction: self
+                .transfer_direction
+                .map(|direction| direction.parse())
+                .transpose()
+                .map_err(decode_error)?,
+            recurring_id: self.recurring_id,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+// ==================== 数据库迁移 ====================
+
+// 根据已应用的版本列出所有迁移状态
+pub fn migration_status(migrator: &Migrator, applied: &[i64]) -> Vec<MigrationStatus> {
+    migrator
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            applied: applied.contains(&migration.version),
+        })
+        .collect()
+}
+
+// 最近一次迁移的版本号，以及回滚它需要退回到的目标版本
+pub fn last_migration(applied: &[i64]) -> Option<(i64, i64)> {
+    let last = applied.iter().copied().max()?;
+    let target = applied.iter().copied().filter(|v| *v < last).max().unwrap_or(0);
+    Some((last, target))
+}
+
+// ==================== 仓储实现 ====================
+
+// LIKE 模式中的通配符按字面匹配，配合 ESCAPE '\' 使用
+pub fn escape_like(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// 为持有 `pool` 字段的存储类型生成全部仓储接口实现
+// $numeric 为包裹金额列或参数的 (前缀, 后缀)，使其按数值比较
+macro_rules! impl_sql_storage {
+    ($storage:ty, $db:ty, $migrator:expr, $numeric:expr) => {
+        #[async_trait::async_trait]
+        impl $crate::db::UserRepository for $storage {
+            async fn insert_user(&self, user: &$crate::models::User) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "INSERT INTO users (id, username, email, display_name, avatar_url, base_currency, password_hash,
+                                        created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                )
+                .bind(user.id)
+                .bind(&user.username)
+                .bind(&user.email)
+                .bind(&user.display_name)
+                .bind(&user.avatar_url)
+                .bind(&user.base_currency)
+                .bind(&user.password_hash)
+                .bind(user.created_at)
+                .bind(user.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn find_user(&self, user_id: uuid::Uuid) -> $crate::db::StorageResult<Option<$crate::models::User>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::UserEntity>("SELECT * FROM users WHERE id = $1")
+                    .bind(user_id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+                Ok(entity.map($crate::models::User::from))
+            }
+
+            async fn find_user_by_username(&self, username: &str) -> $crate::db::StorageResult<Option<$crate::models::User>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::UserEntity>("SELECT * FROM users WHERE username = $1")
+                    .bind(username)
+                    .fetch_optional(&self.pool)
+                    .await?;
+                Ok(entity.map($crate::models::User::from))
+            }
+
+            async fn update_user(&self, user: &$crate::models::User) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "UPDATE users SET display_name = $2, avatar_url = $3, base_currency = $4, updated_at = $5
+                     WHERE id = $1",
+                )
+                .bind(user.id)
+                .bind(&user.display_name)
+                .bind(&user.avatar_url)
+                .bind(&user.base_currency)
+                .bind(user.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::AccountRepository for $storage {
+            async fn insert_account(&self, account: &$crate::models::Account) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "INSERT INTO accounts (id, user_id, name, account_type, currency, balance, opening_balance,
+                                           credit_limit, statement_closing_day, payment_due_day, loan_principal,
+                                           loan_interest_rate, loan_term_months, loan_first_payment_date,
+                                           loan_repayment_method, is_active, created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)",
+                )
+                .bind(account.id)
+                .bind(account.user_id)
+                .bind(&account.name)
+                .bind(account.account_type.as_str())
+                .bind(&account.currency)
+                .bind($crate::db::sql::Money(account.balance))
+                .bind($crate::db::sql::Money(account.opening_balance))
+                .bind(account.credit_limit.map($crate::db::sql::Money))
+                .bind(account.statement_closing_day.map(|day| day as i32))
+                .bind(account.payment_due_day.map(|day| day as i32))
+                .bind(account.loan.as_ref().map(|loan| $crate::db::sql::Money(loan.principal)))
+                .bind(account.loan.as_ref().map(|loan| $crate::db::sql::Money(loan.annual_interest_rate)))
+                .bind(account.loan.as_ref().map(|loan| loan.term_months as i32))
+                .bind(account.loan.as_ref().map(|loan| loan.first_payment_date))
+                .bind(account.loan.as_ref().map(|loan| loan.repayment_method.as_str()))
+                .bind(account.is_active)
+                .bind(account.created_at)
+                .bind(account.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn list_accounts(
+                &self,
+                user_id: uuid::Uuid,
+                limit: i64,
+                start: $crate::db::PageStart,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::Account>> {
+                let mut query = sqlx::QueryBuilder::<$db>::new("SELECT * FROM accounts WHERE user_id = ");
+                query.push_bind(user_id);
+                if let $crate::db::PageStart::After(cursor) = start {
+                    Self::push_after_cursor(&mut query, "created_at", ">", &cursor);
+                }
+                query.push(" ORDER BY created_at, id LIMIT ").push_bind(limit);
+                Self::push_offset(&mut query, start);
+                let entities: Vec<$crate::db::sql::AccountEntity> =
+                    query.build_query_as().fetch_all(&self.pool).await?;
+                Ok(entities
+                    .into_iter()
+                    .map($crate::models::Account::try_from)
+                    .collect::<Result<_, _>>()?)
+            }
+
+            async fn count_accounts(&self, user_id: uuid::Uuid) -> $crate::db::StorageResult<i64> {
+                Ok(sqlx::query_scalar("SELECT COUNT(*) FROM accounts WHERE user_id = $1")
+                    .bind(user_id)
+                    .fetch_one(&self.pool)
+                    .await?)
+            }
+
+            async fn find_account(&self, account_id: uuid::Uuid) -> $crate::db::StorageResult<Option<$crate::models::Account>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::AccountEntity>("SELECT * FROM accounts WHERE id = $1")
+                    .bind(account_id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+                Ok(entity.map($crate::models::Account::try_from).transpose()?)
+            }
+
+            async fn update_account(&self, account: &$crate::models::Account) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "UPDATE accounts SET name = $2, account_type = $3, credit_limit = $4, statement_closing_day = $5,
+                                         payment_due_day = $6, loan_principal = $7, loan_interest_rate = $8,
+                                         loan_term_months = $9, loan_first_payment_date = $10,
+                                         loan_repayment_method = $11, is_active = $12, updated_at = $13
+                     WHERE id = $1",
+                )
+                .bind(account.id)
+                .bind(&account.name)
+                .bind(account.account_type.as_str())
+                .bind(account.credit_limit.map($crate::db::sql::Money))
+                .bind(account.statement_closing_day.map(|day| day as i32))
+                .bind(account.payment_due_day.map(|day| day as i32))
+                .bind(account.loan.as_ref().map(|loan| $crate::db::sql::Money(loan.principal)))
+                .bind(account.loan.as_ref().map(|loan| $crate::db::sql::Money(loan.annual_interest_rate)))
+                .bind(account.loan.as_ref().map(|loan| loan.term_months as i32))
+                .bind(account.loan.as_ref().map(|loan| loan.first_payment_date))
+                .bind(account.loan.as_ref().map(|loan| loan.repayment_method.as_str()))
+                .bind(account.is_active)
+                .bind(account.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn list_payment_due_accounts(&self) -> $crate::db::StorageResult<Vec<$crate::models::Account>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::AccountEntity>(
+                    "SELECT * FROM accounts WHERE payment_due_day IS NOT NULL AND is_active = TRUE
+                     ORDER BY created_at, id",
+                )
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities
+                    .into_iter()
+                    .map($crate::models::Account::try_from)
+                    .collect::<Result<_, _>>()?)
+            }
+
+            async fn delete_account(&self, account_id: uuid::Uuid) -> $crate::db::StorageResult<bool> {
+                let mut tx = self.pool.begin().await?;
+                // 账户的交易随外键级联删除，涉及该账户的转账需要整笔删除，以冲销对方账户的余额
+                let leg_ids: Vec<uuid::Uuid> = sqlx::query_scalar(
+                    "SELECT id FROM transactions
+                     WHERE transfer_id IN (SELECT transfer_id FROM transactions
+                                           WHERE account_id = $1 AND transfer_id IS NOT NULL)",
+                )
+                .bind(account_id)
+                .fetch_all(&mut *tx)
+                .await?;
+                for leg_id in leg_ids {
+                    Self::delete_ledger_entry(&mut tx, leg_id).await?;
+                }
+                let result = sqlx::query("DELETE FROM accounts WHERE id = $1")
+                    .bind(account_id)
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+                Ok(result.rows_affected() > 0)
+            }
+
+            async fn recalculate_balance(
+                &self,
+                account_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Option<rust_decimal::Decimal>> {
+                let mut tx = self.pool.begin().await?;
+                if !Self::lock_account(&mut tx, account_id, chrono::Utc::now()).await? {
+                    return Ok(None);
+                }
+
+                let opening_balance: $crate::db::sql::Money =
+                    sqlx::query_scalar("SELECT opening_balance FROM accounts WHERE id = $1")
+                        .bind(account_id)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                // 在应用层求和，SQLite 的 TEXT 金额列无法精确 SUM
+                let entities = sqlx::query_as::<_, $crate::db::sql::TransactionEntity>(
+                    "SELECT * FROM transactions WHERE account_id = $1",
+                )
+                .bind(account_id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+                let mut balance = opening_balance.0;
+                for entity in entities {
+                    balance += entity.into_model(Vec::new())?.balance_effect();
+                }
+
+                sqlx::query("UPDATE accounts SET balance = $2 WHERE id = $1")
+                    .bind(account_id)
+                    .bind($crate::db::sql::Money(balance))
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+                Ok(Some(balance))
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::TransactionRepository for $storage {
+            async fn insert_transaction(&self, transaction: &$crate::models::Transaction) -> $crate::db::StorageResult<()> {
+                let mut tx = self.pool.begin().await?;
+                Self::insert_ledger_entry(&mut tx, transaction).await?;
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn list_transactions(
+                &self,
+                user_id: uuid::Uuid,
+                filter: &$crate::db::TransactionFilter,
+                sort: $crate::db::TransactionSort,
+                limit: i64,
+                start: $crate::db::PageStart,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::Transaction>> {
+                let (numeric_prefix, numeric_suffix) = $numeric;
+                let column = match sort.field {
+                    $crate::models::TransactionSortField::TransactionDate => "transaction_date".to_string(),
+                    $crate::models::TransactionSortField::Amount => {
+                        format!("{}amount{}", numeric_prefix, numeric_suffix)
+                    }
+                    $crate::models::TransactionSortField::CreatedAt => "created_at".to_string(),
+                };
+                let (direction, after) = match sort.order {
+                    $crate::models::SortOrder::Asc => ("ASC", ">"),
+                    $crate::models::SortOrder::Desc => ("DESC", "<"),
+                };
+
+                let mut query = sqlx::QueryBuilder::<$db>::new("SELECT * FROM transactions");
+                Self::push_transaction_filter(&mut query, user_id, filter);
+                if let $crate::db::PageStart::After(cursor) = start {
+                    Self::push_after_cursor(&mut query, &column, after, &cursor);
+                }
+                query
+                    .push(format!(" ORDER BY {} {}, id {}", column, direction, direction))
+                    .push(" LIMIT ")
+                    .push_bind(limit);
+                Self::push_offset(&mut query, start);
+
+                let entities: Vec<$crate::db::sql::TransactionEntity> =
+                    query.build_query_as().fetch_all(&self.pool).await?;
+                self.with_tags(entities).await
+            }
+
+            async fn count_transactions(
+                &self,
+                user_id: uuid::Uuid,
+                filter: &$crate::db::TransactionFilter,
+            ) -> $crate::db::StorageResult<i64> {
+                let mut query = sqlx::QueryBuilder::<$db>::new("SELECT COUNT(*) FROM transactions");
+                Self::push_transaction_filter(&mut query, user_id, filter);
+                Ok(query.build_query_scalar().fetch_one(&self.pool).await?)
+            }
+
+            async fn find_transaction(
+                &self,
+                transaction_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Option<$crate::models::Transaction>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::TransactionEntity>(
+                    "SELECT * FROM transactions WHERE id = $1",
+                )
+                .bind(transaction_id)
+                .fetch_optional(&self.pool)
+                .await?;
+                Ok(self.with_tags(entity.into_iter().collect()).await?.pop())
+            }
+
+            async fn update_transaction(&self, transaction: &$crate::models::Transaction) -> $crate::db::StorageResult<()> {
+                let mut tx = self.pool.begin().await?;
+                Self::update_ledger_entry(&mut tx, transaction).await?;
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn delete_transaction(&self, transaction_id: uuid::Uuid) -> $crate::db::StorageResult<bool> {
+                let mut tx = self.pool.begin().await?;
+                let deleted = Self::delete_ledger_entry(&mut tx, transaction_id).await?;
+                tx.commit().await?;
+                Ok(deleted)
+            }
+
+            async fn insert_transfer(&self, legs: &[$crate::models::Transaction]) -> $crate::db::StorageResult<()> {
+                let mut tx = self.pool.begin().await?;
+                for leg in legs {
+                    Self::insert_ledger_entry(&mut tx, leg).await?;
+                }
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn update_transfer(
+                &self,
+                transfer_id: uuid::Uuid,
+                legs: &[$crate::models::Transaction],
+            ) -> $crate::db::StorageResult<()> {
+                let mut tx = self.pool.begin().await?;
+                let existing_ids: Vec<uuid::Uuid> =
+                    sqlx::query_scalar("SELECT id FROM transactions WHERE transfer_id = $1")
+                        .bind(transfer_id)
+                        .fetch_all(&mut *tx)
+                        .await?;
+                for leg_id in existing_ids.iter().filter(|id| !legs.iter().any(|leg| leg.id == **id)) {
+                    Self::delete_ledger_entry(&mut tx, *leg_id).await?;
+                }
+                for leg in legs {
+                    if existing_ids.contains(&leg.id) {
+                        Self::update_ledger_entry(&mut tx, leg).await?;
+                    } else {
+                        Self::insert_ledger_entry(&mut tx, leg).await?;
+                    }
+                }
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn delete_transfer(&self, transfer_id: uuid::Uuid) -> $crate::db::StorageResult<bool> {
+                let mut tx = self.pool.begin().await?;
+                let leg_ids: Vec<uuid::Uuid> =
+                    sqlx::query_scalar("SELECT id FROM transactions WHERE transfer_id = $1")
+                        .bind(transfer_id)
+                        .fetch_all(&mut *tx)
+                        .await?;
+                for leg_id in &leg_ids {
+                    Self::delete_ledger_entry(&mut tx, *leg_id).await?;
+                }
+                tx.commit().await?;
+                Ok(!leg_ids.is_empty())
+            }
+
+            async fn list_transfer_legs(
+                &self,
+                transfer_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::Transaction>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::TransactionEntity>(
+                    "SELECT * FROM transactions WHERE transfer_id = $1 ORDER BY created_at, id",
+                )
+                .bind(transfer_id)
+                .fetch_all(&self.pool)
+                .await?;
+                self.with_tags(entities).await
+            }
+
+            async fn sum_transactions_by_day(
+                &self,
+                user_id: uuid::Uuid,
+                filter: &$crate::db::TransactionFilter,
+            ) -> $crate::db::StorageResult<Vec<$crate::db::DailyTotal>> {
+                let mut query = sqlx::QueryBuilder::<$db>::new(
+                    "SELECT transaction_type, currency, transaction_date, amount FROM transactions",
+                );
+                Self::push_transaction_filter(&mut query, user_id, filter);
+                let rows: Vec<(String, String, chrono::DateTime<chrono::Utc>, $crate::db::sql::Money)> =
+                    query.build_query_as().fetch_all(&self.pool).await?;
+
+                let rows = rows
+                    .into_iter()
+                    .map(|(transaction_type, currency, transaction_date, amount)| {
+                        let transaction_type = transaction_type
+                            .parse()
+                            .map_err($crate::db::sql::decode_error)?;
+                        Ok((transaction_type, currency, transaction_date, amount.0))
+                    })
+                    .collect::<Result<Vec<_>, sqlx::Error>>()?;
+                Ok($crate::db::daily_totals(rows))
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::CategoryRepository for $storage {
+            async fn list_categories(
+                &self,
+                user_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::Category>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::CategoryEntity>(
+                    "SELECT * FROM categories WHERE is_system OR user_id = $1
+                     ORDER BY transaction_type, name, id",
+                )
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities
+                    .into_iter()
+                    .map($crate::models::Category::try_from)
+                    .collect::<Result<_, _>>()?)
+            }
+
+            async fn list_category_translations(
+                &self,
+                locale: &str,
+            ) -> $crate::db::StorageResult<std::collections::HashMap<uuid::Uuid, String>> {
+                let rows: Vec<(uuid::Uuid, String)> =
+                    sqlx::query_as("SELECT category_id, name FROM category_translations WHERE locale = $1")
+                        .bind(locale)
+                        .fetch_all(&self.pool)
+                        .await?;
+                Ok(rows.into_iter().collect())
+            }
+
+            async fn find_category(
+                &self,
+                category_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Option<$crate::models::Category>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::CategoryEntity>(
+                    "SELECT * FROM categories WHERE id = $1",
+                )
+                .bind(category_id)
+                .fetch_optional(&self.pool)
+                .await?;
+                Ok(entity.map($crate::models::Category::try_from).transpose()?)
+            }
+
+            async fn insert_category(&self, category: &$crate::models::Category) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "INSERT INTO categories (id, user_id, parent_id, name, icon, color, transaction_type,
+                                             is_system, is_archived)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                )
+                .bind(category.id)
+                .bind(category.user_id)
+                .bind(category.parent_id)
+                .bind(&category.name)
+                .bind(&category.icon)
+                .bind(&category.color)
+                .bind(category.transaction_type.as_str())
+                .bind(category.is_system)
+                .bind(category.is_archived)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn update_category(&self, category: &$crate::models::Category) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "UPDATE categories SET parent_id = $2, name = $3, icon = $4, color = $5, is_archived = $6
+                     WHERE id = $1",
+                )
+                .bind(category.id)
+                .bind(category.parent_id)
+                .bind(&category.name)
+                .bind(&category.icon)
+                .bind(&category.color)
+                .bind(category.is_archived)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn archive_category(&self, category_id: uuid::Uuid) -> $crate::db::StorageResult<bool> {
+                let result = sqlx::query("UPDATE categories SET is_archived = TRUE WHERE id = $1 OR parent_id = $1")
+                    .bind(category_id)
+                    .execute(&self.pool)
+                    .await?;
+                Ok(result.rows_affected() > 0)
+            }
+
+            async fn merge_category(
+                &self,
+                source_id: uuid::Uuid,
+                target_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<u64> {
+                let mut tx = self.pool.begin().await?;
+                let moved = sqlx::query(
+                    "UPDATE transactions SET category_id = $2, updated_at = $3 WHERE category_id = $1",
+                )
+                .bind(source_id)
+                .bind(target_id)
+                .bind(chrono::Utc::now())
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+                sqlx::query("UPDATE categories SET parent_id = $2 WHERE parent_id = $1")
+                    .bind(source_id)
+                    .bind(target_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE budgets SET category_id = $2, updated_at = $3 WHERE category_id = $1")
+                    .bind(source_id)
+                    .bind(target_id)
+                    .bind(chrono::Utc::now())
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(
+                    "UPDATE recurring_transactions SET category_id = $2, updated_at = $3 WHERE category_id = $1",
+                )
+                .bind(source_id)
+                .bind(target_id)
+                .bind(chrono::Utc::now())
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query("UPDATE categories SET is_archived = TRUE WHERE id = $1")
+                    .bind(source_id)
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+                Ok(moved)
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::TagRepository for $storage {
+            async fn list_tags(&self, user_id: uuid::Uuid) -> $crate::db::StorageResult<Vec<$crate::models::Tag>> {
+                let rows: Vec<(String, i64, Option<chrono::DateTime<chrono::Utc>>)> = sqlx::query_as(
+                    "SELECT t.name, COUNT(*), MAX(tr.transaction_date) FROM tags t
+                     JOIN transaction_tags tt ON tt.tag_id = t.id
+                     JOIN transactions tr ON tr.id = tt.transaction_id
+                     WHERE t.user_id = $1 GROUP BY t.name ORDER BY t.name",
+                )
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(rows
+                    .into_iter()
+                    .map(|(name, usage_count, last_used_at)| $crate::models::Tag {
+                        name,
+                        usage_count: usage_count as u64,
+                        last_used_at,
+                    })
+                    .collect())
+            }
+
+            async fn merge_tag(
+                &self,
+                user_id: uuid::Uuid,
+                source: &str,
+                target: &str,
+            ) -> $crate::db::StorageResult<u64> {
+                let now = chrono::Utc::now();
+                let mut tx = self.pool.begin().await?;
+                let source_id: Option<uuid::Uuid> =
+                    sqlx::query_scalar("SELECT id FROM tags WHERE user_id = $1 AND name = $2")
+                        .bind(user_id)
+                        .bind(source)
+                        .fetch_optional(&mut *tx)
+                        .await?;
+                let Some(source_id) = source_id.filter(|_| source != target) else {
+                    return Ok(0);
+                };
+
+                sqlx::query(
+                    "INSERT INTO tags (id, user_id, name, created_at) VALUES ($1, $2, $3, $4)
+                     ON CONFLICT (user_id, name) DO NOTHING",
+                )
+                .bind(uuid::Uuid::new_v4())
+                .bind(user_id)
+                .bind(target)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+                let target_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM tags WHERE user_id = $1 AND name = $2")
+                    .bind(user_id)
+                    .bind(target)
+                    .fetch_one(&mut *tx)
+                    .await?;
+
+                let transaction_ids: Vec<uuid::Uuid> =
+                    sqlx::query_scalar("SELECT transaction_id FROM transaction_tags WHERE tag_id = $1")
+                        .bind(source_id)
+                        .fetch_all(&mut *tx)
+                        .await?;
+                sqlx::query(
+                    "INSERT INTO transaction_tags (transaction_id, tag_id)
+                     SELECT transaction_id, $2 FROM transaction_tags WHERE tag_id = $1
+                     ON CONFLICT DO NOTHING",
+                )
+                .bind(source_id)
+                .bind(target_id)
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query("DELETE FROM transaction_tags WHERE tag_id = $1")
+                    .bind(source_id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM tags WHERE id = $1")
+                    .bind(source_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                for transaction_id in &transaction_ids {
+                    sqlx::query("UPDATE transactions SET updated_at = $2 WHERE id = $1")
+                        .bind(transaction_id)
+                        .bind(now)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                sqlx::query("UPDATE budgets SET tag = $3, updated_at = $4 WHERE user_id = $1 AND tag = $2")
+                    .bind(user_id)
+                    .bind(source)
+                    .bind(target)
+                    .bind(now)
+                    .execute(&mut *tx)
+                    .await?;
+                // 重复交易模板的标签存为 JSON，在应用层替换
+                let templates: Vec<(uuid::Uuid, String)> =
+                    sqlx::query_as("SELECT id, tags FROM recurring_transactions WHERE user_id = $1")
+                        .bind(user_id)
+                        .fetch_all(&mut *tx)
+                        .await?;
+                for (recurring_id, tags) in templates {
+                    let mut tags: Vec<String> =
+                        serde_json::from_str(&tags).map_err(|err| sqlx::Error::Decode(err.into()))?;
+                    if !tags.iter().any(|tag| tag == source) {
+                        continue;
+                    }
+                    tags.retain(|tag| tag != source);
+                    if !tags.iter().any(|tag| tag == target) {
+                        tags.push(target.to_string());
+                    }
+                    sqlx::query("UPDATE recurring_transactions SET tags = $2, updated_at = $3 WHERE id = $1")
+                        .bind(recurring_id)
+                        .bind(serde_json::to_string(&tags).map_err(|err| sqlx::Error::Encode(err.into()))?)
+                        .bind(now)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                tx.commit().await?;
+                Ok(transaction_ids.len() as u64)
+            }
+
+            async fn sum_transactions_by_tag(
+                &self,
+                user_id: uuid::Uuid,
+                filter: &$crate::db::TransactionFilter,
+            ) -> $crate::db::StorageResult<Vec<$crate::db::TagDailyTotal>> {
+                let mut query = sqlx::QueryBuilder::<$db>::new(
+                    "SELECT t.name, x.currency, x.transaction_date, x.amount
+                     FROM (SELECT id, currency, transaction_date, amount FROM transactions",
+                );
+                Self::push_transaction_filter(&mut query, user_id, filter);
+                query.push(
+                    ") x JOIN transaction_tags tt ON tt.transaction_id = x.id
+                     JOIN tags t ON t.id = tt.tag_id",
+                );
+                let rows: Vec<(String, String, chrono::DateTime<chrono::Utc>, $crate::db::sql::Money)> =
+                    query.build_query_as().fetch_all(&self.pool).await?;
+                Ok($crate::db::tag_daily_totals(
+                    rows.into_iter()
+                        .map(|(tag, currency, transaction_date, amount)| (tag, currency, transaction_date, amount.0)),
+                ))
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::BudgetRepository for $storage {
+            async fn insert_budget(&self, budget: &$crate::models::Budget) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "INSERT INTO budgets (id, user_id, name, category_id, tag, period, amount, currency, rollover,
+                                          start_date, end_date, created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+                )
+                .bind(budget.id)
+                .bind(budget.user_id)
+                .bind(&budget.name)
+                .bind(budget.category_id)
+                .bind(&budget.tag)
+                .bind(budget.period.as_str())
+                .bind($crate::db::sql::Money(budget.amount))
+                .bind(&budget.currency)
+                .bind(budget.rollover)
+                .bind(budget.start_date)
+                .bind(budget.end_date)
+                .bind(budget.created_at)
+                .bind(budget.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn list_budgets(&self, user_id: uuid::Uuid) -> $crate::db::StorageResult<Vec<$crate::models::Budget>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::BudgetEntity>(
+                    "SELECT * FROM budgets WHERE user_id = $1 ORDER BY created_at, id",
+                )
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities
+                    .into_iter()
+                    .map($crate::models::Budget::try_from)
+                    .collect::<Result<_, _>>()?)
+            }
+
+            async fn find_budget(&self, budget_id: uuid::Uuid) -> $crate::db::StorageResult<Option<$crate::models::Budget>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::BudgetEntity>("SELECT * FROM budgets WHERE id = $1")
+                    .bind(budget_id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+                Ok(entity.map($crate::models::Budget::try_from).transpose()?)
+            }
+
+            async fn update_budget(&self, budget: &$crate::models::Budget) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "UPDATE budgets SET name = $2, amount = $3, rollover = $4, end_date = $5, updated_at = $6
+                     WHERE id = $1",
+                )
+                .bind(budget.id)
+                .bind(&budget.name)
+                .bind($crate::db::sql::Money(budget.amount))
+                .bind(budget.rollover)
+                .bind(budget.end_date)
+                .bind(budget.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn delete_budget(&self, budget_id: uuid::Uuid) -> $crate::db::StorageResult<bool> {
+                let result = sqlx::query("DELETE FROM budgets WHERE id = $1")
+                    .bind(budget_id)
+                    .execute(&self.pool)
+                    .await?;
+                Ok(result.rows_affected() > 0)
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::RecurringTransactionRepository for $storage {
+            async fn insert_recurring(
+                &self,
+                recurring: &$crate::models::RecurringTransaction,
+            ) -> $crate::db::StorageResult<()> {
+                let mut tx = self.pool.begin().await?;
+                Self::insert_recurring_entry(&mut tx, recurring).await?;
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn list_recurring(
+                &self,
+                user_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::RecurringTransaction>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::RecurringTransactionEntity>(
+                    "SELECT * FROM recurring_transactions WHERE user_id = $1 ORDER BY created_at, id",
+                )
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities
+                    .into_iter()
+                    .map($crate::models::RecurringTransaction::try_from)
+                    .collect::<Result<_, _>>()?)
+            }
+
+            async fn list_due_recurring(
+                &self,
+                date: chrono::NaiveDate,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::RecurringTransaction>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::RecurringTransactionEntity>(
+                    "SELECT * FROM recurring_transactions
+                     WHERE next_date <= $1 AND is_paused = FALSE ORDER BY next_date, id",
+                )
+                .bind(date)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities
+                    .into_iter()
+                    .map($crate::models::RecurringTransaction::try_from)
+                    .collect::<Result<_, _>>()?)
+            }
+
+            async fn find_recurring(
+                &self,
+                recurring_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Option<$crate::models::RecurringTransaction>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::RecurringTransactionEntity>(
+                    "SELECT * FROM recurring_transactions WHERE id = $1",
+                )
+                .bind(recurring_id)
+                .fetch_optional(&self.pool)
+                .await?;
+                Ok(entity.map($crate::models::RecurringTransaction::try_from).transpose()?)
+            }
+
+            async fn update_recurring(
+                &self,
+                recurring: &$crate::models::RecurringTransaction,
+                expected_count: u32,
+                successor: Option<&$crate::models::RecurringTransaction>,
+            ) -> $crate::db::StorageResult<bool> {
+                let mut tx = self.pool.begin().await?;
+                if !Self::update_recurring_entry(&mut tx, recurring, expected_count).await? {
+                    return Ok(false);
+                }
+                if let Some(successor) = successor {
+                    Self::insert_recurring_entry(&mut tx, successor).await?;
+                }
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn post_recurring(
+                &self,
+                recurring: &$crate::models::RecurringTransaction,
+                expected_count: u32,
+                transaction: &$crate::models::Transaction,
+            ) -> $crate::db::StorageResult<bool> {
+                let mut tx = self.pool.begin().await?;
+                if !Self::update_recurring_entry(&mut tx, recurring, expected_count).await? {
+                    return Ok(false);
+                }
+                Self::insert_ledger_entry(&mut tx, transaction).await?;
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn delete_recurring(&self, recurring_id: uuid::Uuid) -> $crate::db::StorageResult<bool> {
+                let mut tx = self.pool.begin().await?;
+                sqlx::query("UPDATE transactions SET recurring_id = NULL WHERE recurring_id = $1")
+                    .bind(recurring_id)
+                    .execute(&mut *tx)
+                    .await?;
+                let result = sqlx::query("DELETE FROM recurring_transactions WHERE id = $1")
+                    .bind(recurring_id)
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+                Ok(result.rows_affected() > 0)
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::InstallmentRepository for $storage {
+            async fn insert_installment(
+                &self,
+                plan: &$crate::models::InstallmentPlan,
+                fee_charges: Option<&$crate::models::RecurringTransaction>,
+            ) -> $crate::db::StorageResult<()> {
+                let mut tx = self.pool.begin().await?;
+                if let Some(fee_charges) = fee_charges {
+                    Self::insert_recurring_entry(&mut tx, fee_charges).await?;
+                }
+                sqlx::query(
+                    "INSERT INTO installment_plans (id, user_id, account_id, transaction_id, principal, periods,
+                                                    fee_per_period, first_period_date, recurring_id, paid_off_date,
+                                                    created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                )
+                .bind(plan.id)
+                .bind(plan.user_id)
+                .bind(plan.account_id)
+                .bind(plan.transaction_id)
+                .bind($crate::db::sql::Money(plan.principal))
+                .bind(plan.periods as i32)
+                .bind($crate::db::sql::Money(plan.fee_per_period))
+                .bind(plan.first_period_date)
+                .bind(plan.recurring_id)
+                .bind(plan.paid_off_date)
+                .bind(plan.created_at)
+                .bind(plan.updated_at)
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn list_installments(
+                &self,
+                user_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::InstallmentPlan>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::InstallmentPlanEntity>(
+                    "SELECT * FROM installment_plans WHERE user_id = $1 ORDER BY created_at, id",
+                )
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities.into_iter().map($crate::models::InstallmentPlan::from).collect())
+            }
+
+            async fn list_account_installments(
+                &self,
+                account_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::InstallmentPlan>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::InstallmentPlanEntity>(
+                    "SELECT * FROM installment_plans WHERE account_id = $1 ORDER BY created_at, id",
+                )
+                .bind(account_id)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities.into_iter().map($crate::models::InstallmentPlan::from).collect())
+            }
+
+            async fn find_installment(
+                &self,
+                plan_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Option<$crate::models::InstallmentPlan>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::InstallmentPlanEntity>(
+                    "SELECT * FROM installment_plans WHERE id = $1",
+                )
+                .bind(plan_id)
+                .fetch_optional(&self.pool)
+                .await?;
+                Ok(entity.map($crate::models::InstallmentPlan::from))
+            }
+
+            async fn find_transaction_installment(
+                &self,
+                transaction_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Option<$crate::models::InstallmentPlan>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::InstallmentPlanEntity>(
+                    "SELECT * FROM installment_plans WHERE transaction_id = $1",
+                )
+                .bind(transaction_id)
+                .fetch_optional(&self.pool)
+                .await?;
+                Ok(entity.map($crate::models::InstallmentPlan::from))
+            }
+
+            async fn update_installment(
+                &self,
+                plan: &$crate::models::InstallmentPlan,
+            ) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "UPDATE installment_plans SET recurring_id = $2, paid_off_date = $3, updated_at = $4 WHERE id = $1",
+                )
+                .bind(plan.id)
+                .bind(plan.recurring_id)
+                .bind(plan.paid_off_date)
+                .bind(plan.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::InvestmentRepository for $storage {
+            async fn insert_investment_event(
+                &self,
+                event: &$crate::models::InvestmentEvent,
+            ) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "INSERT INTO investment_events (id, user_id, account_id, symbol, asset_class, event_type, event_date,
+                                                    quantity, price, amount, fee, notes, created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+                )
+                .bind(event.id)
+                .bind(event.user_id)
+                .bind(event.account_id)
+                .bind(&event.symbol)
+                .bind(event.asset_class.as_str())
+                .bind(event.event_type.as_str())
+                .bind(event.event_date)
+                .bind(event.quantity.map($crate::db::sql::Money))
+                .bind(event.price.map($crate::db::sql::Money))
+                .bind(event.amount.map($crate::db::sql::Money))
+                .bind($crate::db::sql::Money(event.fee))
+                .bind(&event.notes)
+                .bind(event.created_at)
+                .bind(event.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn list_investment_events(
+                &self,
+                account_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::InvestmentEvent>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::InvestmentEventEntity>(
+                    "SELECT * FROM investment_events WHERE account_id = $1 ORDER BY event_date, created_at, id",
+                )
+                .bind(account_id)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities
+                    .into_iter()
+                    .map($crate::models::InvestmentEvent::try_from)
+                    .collect::<Result<Vec<_>, _>>()?)
+            }
+
+            async fn find_investment_event(
+                &self,
+                event_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Option<$crate::models::InvestmentEvent>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::InvestmentEventEntity>(
+                    "SELECT * FROM investment_events WHERE id = $1",
+                )
+                .bind(event_id)
+                .fetch_optional(&self.pool)
+                .await?;
+                Ok(entity.map($crate::models::InvestmentEvent::try_from).transpose()?)
+            }
+
+            async fn update_investment_event(
+                &self,
+                event: &$crate::models::InvestmentEvent,
+            ) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "UPDATE investment_events SET asset_class = $2, event_date = $3, quantity = $4, price = $5,
+                                                  amount = $6, fee = $7, notes = $8, updated_at = $9
+                     WHERE id = $1",
+                )
+                .bind(event.id)
+                .bind(event.asset_class.as_str())
+                .bind(event.event_date)
+                .bind(event.quantity.map($crate::db::sql::Money))
+                .bind(event.price.map($crate::db::sql::Money))
+                .bind(event.amount.map($crate::db::sql::Money))
+                .bind($crate::db::sql::Money(event.fee))
+                .bind(&event.notes)
+                .bind(event.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn delete_investment_event(&self, event_id: uuid::Uuid) -> $crate::db::StorageResult<bool> {
+                let result = sqlx::query("DELETE FROM investment_events WHERE id = $1")
+                    .bind(event_id)
+                    .execute(&self.pool)
+                    .await?;
+                Ok(result.rows_affected() > 0)
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::SecurityPriceRepository for $storage {
+            async fn upsert_security_price(
+                &self,
+                price: &$crate::models::SecurityPrice,
+            ) -> $crate::db::StorageResult<()> {
+                sqlx::query(
+                    "INSERT INTO security_prices (user_id, symbol, price_date, price, currency, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6)
+                     ON CONFLICT (user_id, symbol, price_date)
+                     DO UPDATE SET price = excluded.price, currency = excluded.currency, updated_at = excluded.updated_at",
+                )
+                .bind(price.user_id)
+                .bind(&price.symbol)
+                .bind(price.price_date)
+                .bind($crate::db::sql::Money(price.price))
+                .bind(&price.currency)
+                .bind(price.updated_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(())
+            }
+
+            async fn list_security_prices(
+                &self,
+                user_id: uuid::Uuid,
+                symbol: Option<&str>,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::SecurityPrice>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::SecurityPriceEntity>(
+                    "SELECT * FROM security_prices
+                     WHERE user_id = $1 AND ($2 IS NULL OR symbol = $2)
+                     ORDER BY symbol, price_date",
+                )
+                .bind(user_id)
+                .bind(symbol)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities.into_iter().map($crate::models::SecurityPrice::from).collect())
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::NotificationRepository for $storage {
+            async fn insert_notification(
+                &self,
+                notification: &$crate::models::Notification,
+            ) -> $crate::db::StorageResult<bool> {
+                let result = sqlx::query(
+                    "INSERT INTO notifications (id, user_id, kind, source_id, due_date, title, amount, currency,
+                                                created_at, read_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                     ON CONFLICT (user_id, kind, source_id, due_date) DO NOTHING",
+                )
+                .bind(notification.id)
+                .bind(notification.user_id)
+                .bind(notification.kind.as_str())
+                .bind(notification.source_id)
+                .bind(notification.due_date)
+                .bind(&notification.title)
+                .bind($crate::db::sql::Money(notification.amount))
+                .bind(&notification.currency)
+                .bind(notification.created_at)
+                .bind(notification.read_at)
+                .execute(&self.pool)
+                .await?;
+                Ok(result.rows_affected() > 0)
+            }
+
+            async fn list_notifications(
+                &self,
+                user_id: uuid::Uuid,
+                unread_only: bool,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::Notification>> {
+                let entities = sqlx::query_as::<_, $crate::db::sql::NotificationEntity>(
+                    "SELECT * FROM notifications WHERE user_id = $1 AND ($2 = FALSE OR read_at IS NULL)
+                     ORDER BY due_date DESC, created_at DESC, id",
+                )
+                .bind(user_id)
+                .bind(unread_only)
+                .fetch_all(&self.pool)
+                .await?;
+                Ok(entities
+                    .into_iter()
+                    .map($crate::models::Notification::try_from)
+                    .collect::<Result<_, _>>()?)
+            }
+
+            async fn find_notification(
+                &self,
+                notification_id: uuid::Uuid,
+            ) -> $crate::db::StorageResult<Option<$crate::models::Notification>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::NotificationEntity>(
+                    "SELECT * FROM notifications WHERE id = $1",
+                )
+                .bind(notification_id)
+                .fetch_optional(&self.pool)
+                .await?;
+                Ok(entity.map($crate::models::Notification::try_from).transpose()?)
+            }
+
+            async fn mark_notification_read(
+                &self,
+                notification_id: uuid::Uuid,
+                read_at: chrono::DateTime<chrono::Utc>,
+            ) -> $crate::db::StorageResult<()> {
+                sqlx::query("UPDATE notifications SET read_at = $2 WHERE id = $1 AND read_at IS NULL")
+                    .bind(notification_id)
+                    .bind(read_at)
+                    .execute(&self.pool)
+                    .await?;
+                Ok(())
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::ExchangeRateRepository for $storage {
+            async fn upsert_exchange_rates(
+                &self,
+                rates: &[$crate::models::ExchangeRate],
+            ) -> $crate::db::StorageResult<()> {
+                let mut tx = self.pool.begin().await?;
+                for rate in rates {
+                    sqlx::query(
+                        "INSERT INTO exchange_rates (base_currency, quote_currency, rate_date, rate, source, created_at)
+                         VALUES ($1, $2, $3, $4, $5, $6)
+                         ON CONFLICT (base_currency, quote_currency, rate_date)
+                         DO UPDATE SET rate = excluded.rate, source = excluded.source",
+                    )
+                    .bind(&rate.base_currency)
+                    .bind(&rate.quote_currency)
+                    .bind(rate.rate_date)
+                    .bind($crate::db::sql::Money(rate.rate))
+                    .bind(&rate.source)
+                    .bind(chrono::Utc::now())
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn find_exchange_rate(
+                &self,
+                base: &str,
+                quote: &str,
+                date: chrono::NaiveDate,
+            ) -> $crate::db::StorageResult<Option<$crate::models::ExchangeRate>> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::ExchangeRateEntity>(
+                    "SELECT * FROM exchange_rates
+                     WHERE base_currency = $1 AND quote_currency = $2 AND rate_date <= $3
+                     ORDER BY rate_date DESC LIMIT 1",
+                )
+                .bind(base)
+                .bind(quote)
+                .bind(date)
+                .fetch_optional(&self.pool)
+                .await?;
+                Ok(entity.map($crate::models::ExchangeRate::from))
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::Storage for $storage {
+            async fn run_migrations(&self) -> $crate::db::StorageResult<()> {
+                $migrator.run(&self.pool).await?;
+                Ok(())
+            }
+
+            async fn revert_last_migration(&self) -> $crate::db::StorageResult<Option<i64>> {
+                let applied = self.applied_migrations().await?;
+                let Some((last, target)) = $crate::db::sql::last_migration(&applied) else {
+                    return Ok(None);
+                };
+                $migrator.undo(&self.pool, target).await?;
+                Ok(Some(last))
+            }
+
+            async fn migration_status(&self) -> $crate::db::StorageResult<Vec<$crate::db::MigrationStatus>> {
+                let applied = self.applied_migrations().await?;
+                Ok($crate::db::sql::migration_status(&$migrator, &applied))
+            }
+        }
+
+        impl $storage {
+            // 已应用的迁移版本号
+            async fn applied_migrations(&self) -> $crate::db::StorageResult<Vec<i64>> {
+                use sqlx::migrate::Migrate;
+
+                let mut conn = self.pool.acquire().await?;
+                conn.ensure_migrations_table().await?;
+                let applied = conn.list_applied_migrations().await?;
+                Ok(applied.into_iter().map(|migration| migration.version).collect())
+            }
+
+            // 排在游标之后：(column, id) 按 op 比较，column 为排序表达式
+            fn push_after_cursor(
+                query: &mut sqlx::QueryBuilder<'_, $db>,
+                column: &str,
+                op: &str,
+                cursor: &$crate::db::Cursor,
+            ) {
+                query.push(format!(" AND ({} {} ", column, op));
+                Self::push_sort_value(query, cursor.value);
+                query.push(format!(" OR ({} = ", column));
+                Self::push_sort_value(query, cursor.value);
+                query.push(format!(" AND id {} ", op)).push_bind(cursor.id).push("))");
+            }
+
+            fn push_sort_value(query: &mut sqlx::QueryBuilder<'_, $db>, value: $crate::db::SortValue) {
+                match value {
+                    $crate::db::SortValue::Time(time) => {
+                        query.push_bind(time);
+                    }
+                    $crate::db::SortValue::Amount(amount) => {
+                        let (numeric_prefix, numeric_suffix) = $numeric;
+                        query
+                            .push(numeric_prefix)
+                            .push_bind($crate::db::sql::Money(amount))
+                            .push(numeric_suffix);
+                    }
+                }
+            }
+
+            fn push_offset(query: &mut sqlx::QueryBuilder<'_, $db>, start: $crate::db::PageStart) {
+                if let $crate::db::PageStart::Offset(offset) = start {
+                    query.push(" OFFSET ").push_bind(offset);
+                }
+            }
+
+            // 拼接交易列表的 WHERE 子句，与 TransactionFilter::matches 语义一致
+            fn push_transaction_filter(
+                query: &mut sqlx::QueryBuilder<'_, $db>,
+                user_id: uuid::Uuid,
+                filter: &$crate::db::TransactionFilter,
+            ) {
+                let (numeric_prefix, numeric_suffix) = $numeric;
+                query.push(" WHERE user_id = ").push_bind(user_id);
+                if let Some(start_date) = filter.start_date {
+                    query.push(" AND transaction_date >= ").push_bind(start_date);
+                }
+                if let Some(end_date) = filter.end_date {
+                    query.push(" AND transaction_date <= ").push_bind(end_date);
+                }
+                if !filter.account_ids.is_empty() {
+                    query.push(" AND account_id IN (");
+                    let mut ids = query.separated(", ");
+                    for account_id in &filter.account_ids {
+                        ids.push_bind(*account_id);
+                    }
+                    query.push(")");
+                }
+                if !filter.category_ids.is_empty() {
+                    query.push(" AND category_id IN (");
+                    let mut ids = query.separated(", ");
+                    for category_id in &filter.category_ids {
+                        ids.push_bind(*category_id);
+                    }
+                    query.push(")");
+                }
+                if let Some(transaction_type) = filter.transaction_type {
+                    query.push(" AND transaction_type = ").push_bind(transaction_type.as_str());
+                }
+                for (bound, operator) in [(filter.min_amount, ">="), (filter.max_amount, "<=")] {
+                    if let Some(amount) = bound {
+                        query
+                            .push(format!(
+                                " AND {}amount{} {} {}",
+                                numeric_prefix, numeric_suffix, operator, numeric_prefix
+                            ))
+                            .push_bind($crate::db::sql::Money(amount))
+                            .push(numeric_suffix);
+                    }
+                }
+                if !filter.tags.is_empty() {
+                    // 标签已去重，全部命中即命中的标签数等于筛选的标签数
+                    query.push(if filter.match_all_tags {
+                        " AND (SELECT COUNT(*)"
+                    } else {
+                        " AND EXISTS (SELECT 1"
+                    });
+                    query.push(
+                        " FROM transaction_tags tt JOIN tags t ON t.id = tt.tag_id
+                         WHERE tt.transaction_id = transactions.id AND t.name IN (",
+                    );
+                    let mut names = query.separated(", ");
+                    for tag in &filter.tags {
+                        names.push_bind(tag.clone());
+                    }
+                    query.push(")");
+                    if filter.match_all_tags {
+                        query.push(") = ").push_bind(filter.tags.len() as i64);
+                    } else {
+                        query.push(")");
+                    }
+                }
+                for term in &filter.search_terms {
+                    let pattern = format!("%{}%", $crate::db::sql::escape_like(term));
+                    query
+                        .push(" AND (LOWER(description) LIKE ")
+                        .push_bind(pattern.clone())
+                        .push(" ESCAPE '\\' OR LOWER(COALESCE(notes, '')) LIKE ")
+                        .push_bind(pattern)
+                        .push(" ESCAPE '\\')");
+                }
+            }
+
+            // 锁定账户行：先写后读，PostgreSQL 持有行锁、SQLite 持有写锁直到事务结束，
+            // 避免并发的余额调整互相覆盖。账户不存在时返回 false
+            async fn lock_account(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                account_id: uuid::Uuid,
+                now: chrono::DateTime<chrono::Utc>,
+            ) -> Result<bool, sqlx::Error> {
+                let result = sqlx::query("UPDATE accounts SET updated_at = $2 WHERE id = $1")
+                    .bind(account_id)
+                    .bind(now)
+                    .execute(&mut **tx)
+                    .await?;
+                Ok(result.rows_affected() > 0)
+            }
+
+            // 在事务内调整账户余额
+            async fn adjust_balance(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                account_id: uuid::Uuid,
+                delta: rust_decimal::Decimal,
+                now: chrono::DateTime<chrono::Utc>,
+            ) -> Result<(), sqlx::Error> {
+                if delta.is_zero() || !Self::lock_account(tx, account_id, now).await? {
+                    return Ok(());
+                }
+                let balance: $crate::db::sql::Money =
+                    sqlx::query_scalar("SELECT balance FROM accounts WHERE id = $1")
+                        .bind(account_id)
+                        .fetch_one(&mut **tx)
+                        .await?;
+                sqlx::query("UPDATE accounts SET balance = $2 WHERE id = $1")
+                    .bind(account_id)
+                    .bind($crate::db::sql::Money(balance.0 + delta))
+                    .execute(&mut **tx)
+                    .await?;
+                Ok(())
+            }
+
+            async fn insert_recurring_entry(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                recurring: &$crate::models::RecurringTransaction,
+            ) -> Result<(), sqlx::Error> {
+                let schedule = &recurring.schedule;
+                sqlx::query(
+                    "INSERT INTO recurring_transactions (id, user_id, account_id, category_id, transaction_type, amount,
+                                                         description, notes, tags, frequency, interval_count,
+                                                         day_of_month, last_business_day, start_date, end_date,
+                                                         occurrence_limit, next_date, occurrence_count, is_paused,
+                                                         created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19,
+                             $20, $21)",
+                )
+                .bind(recurring.id)
+                .bind(recurring.user_id)
+                .bind(recurring.account_id)
+                .bind(recurring.category_id)
+                .bind(recurring.transaction_type.as_str())
+                .bind($crate::db::sql::Money(recurring.amount))
+                .bind(&recurring.description)
+                .bind(&recurring.notes)
+                .bind(serde_json::to_string(&recurring.tags).map_err(|err| sqlx::Error::Encode(err.into()))?)
+                .bind(schedule.frequency.as_str())
+                .bind(schedule.interval as i32)
+                .bind(schedule.day_of_month.map(|day| day as i32))
+                .bind(schedule.last_business_day)
+                .bind(schedule.start_date)
+                .bind(schedule.end_date)
+                .bind(schedule.count.map(|count| count as i32))
+                .bind(recurring.next_date)
+                .bind(recurring.occurrence_count as i32)
+                .bind(recurring.is_paused)
+                .bind(recurring.created_at)
+                .bind(recurring.updated_at)
+                .execute(&mut **tx)
+                .await?;
+                Ok(())
+            }
+
+            // 次数与 expected_count 一致时才更新，返回是否已更新
+            async fn update_recurring_entry(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                recurring: &$crate::models::RecurringTransaction,
+                expected_count: u32,
+            ) -> Result<bool, sqlx::Error> {
+                let schedule = &recurring.schedule;
+                let result = sqlx::query(
+                    "UPDATE recurring_transactions SET account_id = $3, category_id = $4, amount = $5, description = $6,
+                            notes = $7, tags = $8, frequency = $9, interval_count = $10, day_of_month = $11,
+                            last_business_day = $12, start_date = $13, end_date = $14, occurrence_limit = $15,
+                            next_date = $16, occurrence_count = $17, is_paused = $18, updated_at = $19
+                     WHERE id = $1 AND occurrence_count = $2",
+                )
+                .bind(recurring.id)
+                .bind(expected_count as i32)
+                .bind(recurring.account_id)
+                .bind(recurring.category_id)
+                .bind($crate::db::sql::Money(recurring.amount))
+                .bind(&recurring.description)
+                .bind(&recurring.notes)
+                .bind(serde_json::to_string(&recurring.tags).map_err(|err| sqlx::Error::Encode(err.into()))?)
+                .bind(schedule.frequency.as_str())
+                .bind(schedule.interval as i32)
+                .bind(schedule.day_of_month.map(|day| day as i32))
+                .bind(schedule.last_business_day)
+                .bind(schedule.start_date)
+                .bind(schedule.end_date)
+                .bind(schedule.count.map(|count| count as i32))
+                .bind(recurring.next_date)
+                .bind(recurring.occurrence_count as i32)
+                .bind(recurring.is_paused)
+                .bind(recurring.updated_at)
+                .execute(&mut **tx)
+                .await?;
+                Ok(result.rows_affected() > 0)
+            }
+
+            // 写入交易并计入账户余额
+            async fn insert_ledger_entry(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                transaction: &$crate::models::Transaction,
+            ) -> Result<(), sqlx::Error> {
+                sqlx::query(
+                    "INSERT INTO transactions (id, user_id, account_id, category_id, transaction_type, amount, currency,
+                                               description, notes, transaction_date, transfer_id, transfer_direction,
+                                               recurring_id, created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
+                )
+                .bind(transaction.id)
+                .bind(transaction.user_id)
+                .bind(transaction.account_id)
+                .bind(transaction.category_id)
+                .bind(transaction.transaction_type.as_str())
+                .bind($crate::db::sql::Money(transaction.amount))
+                .bind(&transaction.currency)
+                .bind(&transaction.description)
+                .bind(&transaction.notes)
+                .bind(transaction.transaction_date)
+                .bind(transaction.transfer_id)
+                .bind(transaction.transfer_direction.map(|direction| direction.as_str()))
+                .bind(transaction.recurring_id)
+                .bind(transaction.created_at)
+                .bind(transaction.updated_at)
+                .execute(&mut **tx)
+                .await?;
+                Self::replace_transaction_tags(tx, transaction).await?;
+                Self::adjust_balance(tx, transaction.account_id, transaction.balance_effect(), transaction.updated_at)
+                    .await
+            }
+
+            // 更新交易并调整余额：同一账户只调整差额，换账户时冲销原账户并计入新账户
+            async fn update_ledger_entry(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                transaction: &$crate::models::Transaction,
+            ) -> Result<(), sqlx::Error> {
+                let Some(old) = Self::find_ledger_entry(tx, transaction.id).await? else {
+                    return Ok(());
+                };
+                sqlx::query(
+                    "UPDATE transactions SET account_id = $2, category_id = $3, amount = $4, currency = $5,
+                            description = $6, notes = $7, transaction_date = $8, updated_at = $9
+                     WHERE id = $1",
+                )
+                .bind(transaction.id)
+                .bind(transaction.account_id)
+                .bind(transaction.category_id)
+                .bind($crate::db::sql::Money(transaction.amount))
+                .bind(&transaction.currency)
+                .bind(&transaction.description)
+                .bind(&transaction.notes)
+                .bind(transaction.transaction_date)
+                .bind(transaction.updated_at)
+                .execute(&mut **tx)
+                .await?;
+                Self::replace_transaction_tags(tx, transaction).await?;
+
+                let (old_effect, new_effect) = (old.balance_effect(), transaction.balance_effect());
+                if old.account_id == transaction.account_id {
+                    Self::adjust_balance(tx, old.account_id, new_effect - old_effect, transaction.updated_at).await
+                } else {
+                    Self::adjust_balance(tx, old.account_id, -old_effect, transaction.updated_at).await?;
+                    Self::adjust_balance(tx, transaction.account_id, new_effect, transaction.updated_at).await
+                }
+            }
+
+            // 删除交易并冲销其对余额的影响
+            async fn delete_ledger_entry(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                transaction_id: uuid::Uuid,
+            ) -> Result<bool, sqlx::Error> {
+                let Some(old) = Self::find_ledger_entry(tx, transaction_id).await? else {
+                    return Ok(false);
+                };
+                sqlx::query("DELETE FROM transactions WHERE id = $1")
+                    .bind(transaction_id)
+                    .execute(&mut **tx)
+                    .await?;
+                Self::adjust_balance(tx, old.account_id, -old.balance_effect(), chrono::Utc::now()).await?;
+                Ok(true)
+            }
+
+            // 在事务内读取交易（不含标签）
+            async fn find_ledger_entry(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                transaction_id: uuid::Uuid,
+            ) -> Result<Option<$crate::models::Transaction>, sqlx::Error> {
+                let entity = sqlx::query_as::<_, $crate::db::sql::TransactionEntity>(
+                    "SELECT * FROM transactions WHERE id = $1",
+                )
+                .bind(transaction_id)
+                .fetch_optional(&mut **tx)
+                .await?;
+                entity.map(|entity| entity.into_model(Vec::new())).transpose()
+            }
+
+            // 重写交易的标签关联，不存在的标签自动创建
+            async fn replace_transaction_tags(
+                tx: &mut sqlx::Transaction<'_, $db>,
+                transaction: &$crate::models::Transaction,
+            ) -> Result<(), sqlx::Error> {
+                sqlx::query("DELETE FROM transaction_tags WHERE transaction_id = $1")
+                    .bind(transaction.id)
+                    .execute(&mut **tx)
+                    .await?;
+
+                for name in &transaction.tags {
+                    sqlx::query(
+                        "INSERT INTO tags (id, user_id, name, created_at) VALUES ($1, $2, $3, $4)
+                         ON CONFLICT (user_id, name) DO NOTHING",
+                    )
+                    .bind(uuid::Uuid::new_v4())
+                    .bind(transaction.user_id)
+                    .bind(name)
+                    .bind(transaction.updated_at)
+                    .execute(&mut **tx)
+                    .await?;
+
+                    sqlx::query(
+                        "INSERT INTO transaction_tags (transaction_id, tag_id)
+                         SELECT $1, id FROM tags WHERE user_id = $2 AND name = $3
+                         ON CONFLICT DO NOTHING",
+                    )
+                    .bind(transaction.id)
+                    .bind(transaction.user_id)
+                    .bind(name)
+                    .execute(&mut **tx)
+                    .await?;
+                }
+                Ok(())
+            }
+
+            // 批量加载交易标签并组装为模型
+            async fn with_tags(
+                &self,
+                entities: Vec<$crate::db::sql::TransactionEntity>,
+            ) -> $crate::db::StorageResult<Vec<$crate::models::Transaction>> {
+                let mut tags: std::collections::HashMap<uuid::Uuid, Vec<String>> =
+                    std::collections::HashMap::new();
+                if !entities.is_empty() {
+                    let mut query = sqlx::QueryBuilder::<$db>::new(
+                        "SELECT tt.transaction_id, t.name FROM transaction_tags tt
+                         JOIN tags t ON t.id = tt.tag_id WHERE tt.transaction_id IN (",
+                    );
+                    let mut ids = query.separated(", ");
+                    for entity in &entities {
+                        ids.push_bind(entity.id);
+                    }
+                    query.push(") ORDER BY t.name");
+
+                    let rows: Vec<(uuid::Uuid, String)> =
+                        query.build_query_as().fetch_all(&self.pool).await?;
+                    for (transaction_id, name) in rows {
+                        tags.entry(transaction_id).or_default().push(name);
+                    }
+                }
+
+                Ok(entities
+                    .into_iter()
+                    .map(|entity| {
+                        let transaction_tags = tags.remove(&entity.id).unwrap_or_default();
+                        entity.into_model(transaction_tags)
+                    })
+                    .collect::<Result<_, _>>()?)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_sql_storage;
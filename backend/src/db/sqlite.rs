@@ -0,0 +1,33 @@
+use std::str::FromStr;
+
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Sqlite;
+
+use super::sql::impl_sql_storage;
+
+// 编译期嵌入 migrations/sqlite 下的迁移脚本
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+// SQLite 存储，适合单用户自托管
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    pub async fn connect(database_url: &str) -> Result<Self, sqlx::Error> {
+        // 数据库文件不存在时自动创建，并启用外键约束
+        let options = SqliteConnectOptions::from_str(database_url)?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await?;
+        Ok(Self { pool })
+    }
+}
+
+// TEXT 金额按数值比较和排序前转换为 REAL，极大金额的比较可能有精度误差
+impl_sql_storage!(SqliteStorage, Sqlite, MIGRATOR, ("CAST(", " AS REAL)"));
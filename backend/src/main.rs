@@ -8,20 +8,70 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 
 mod api;
+mod auth;
+mod db;
 mod models;  
+mod rates;
 mod services;
-// mod db;  // TODO: 待实现数据库功能时启用
+mod session;
+mod validation;
 // mod utils;  // TODO: 待实现工具函数时启用
 
-use services::{AppState};
+use services::{AppConfig, AppState};
 
 #[tokio::main]
 async fn main() {
     // 初始化日志
     tracing_subscriber::fmt::init();
     
+    // 加载 .env 配置
+    dotenv::dotenv().ok();
+    let config = AppConfig::default();
+
+    // 连接存储后端
+    let storage = db::connect(config.storage_backend, &config.database_url)
+        .await
+        .expect("无法连接数据库");
+
+    // 数据库迁移子命令: your_wallet_backend migrate up|down|status
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        let command = args.get(1).map(String::as_str).unwrap_or("status");
+        if let Err(err) = run_migrate_command(storage.as_ref(), command).await {
+            eprintln!("❌ 迁移失败: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // 启动时自动执行迁移
+    if config.auto_migrate {
+        storage
+            .run_migrations()
+            .await
+            .expect("数据库迁移失败");
+    }
+
+    // 连接会话存储
+    let sessions = session::connect(config.session_backend, &config.redis_url)
+        .await
+        .expect("无法连接 Redis");
+
+    // 汇率缓存和汇率来源
+    let rate_cache = rates::connect_cache(config.rate_cache_backend, &config.redis_url)
+        .await
+        .expect("无法连接 Redis");
+    let rate_provider = rates::provider(config.rate_provider, &config.rate_provider_source)
+        .expect("无法加载汇率来源");
+
     // 初始化应用状态
-    let state = Arc::new(AppState::new());
+    let port = config.port;
+    let state = Arc::new(AppState::new(config, storage, sessions, rate_cache, rate_provider));
+
+    // 后台生成到期的重复交易
+    tokio::spawn(services::run_recurring_materializer(state.clone()));
+    // 后台生成到期提醒
+    tokio::spawn(services::run_bill_reminders(state.clone()));
     
     // 构建路由
     let app = Router::new()
@@ -32,7 +82,7 @@ async fn main() {
         .with_state(state);
     
     // 启动服务器
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
     println!("🚀 YourWallet Backend server starting on http://{}", addr);
     println!("📋 API文档: http://{}/health", addr);
     println!("🔗 API端点: http://{}/api", addr);
@@ -41,6 +91,27 @@ async fn main() {
     axum::serve(listener, app).await.unwrap();
 }
 
+async fn run_migrate_command(storage: &dyn db::Storage, command: &str) -> Result<(), String> {
+    match command {
+        "up" => {
+            storage.run_migrations().await.map_err(|e| e.to_string())?;
+            println!("✅ 所有迁移已应用");
+        }
+        "down" => match storage.revert_last_migration().await.map_err(|e| e.to_string())? {
+            Some(version) => println!("✅ 已回滚迁移 {}", version),
+            None => println!("没有可回滚的迁移"),
+        },
+        "status" => {
+            for migration in storage.migration_status().await.map_err(|e| e.to_string())? {
+                let mark = if migration.applied { "✅" } else { "⏳" };
+                println!("{} {} {}", mark, migration.version, migration.description);
+            }
+        }
+        other => return Err(format!("未知的迁移命令: {}（可用: up, down, status）", other)),
+    }
+    Ok(())
+}
+
 async fn root() -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "message": "Welcome to YourWallet API",
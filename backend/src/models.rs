@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc, Weekday};
 use uuid::Uuid;
 use rust_decimal::Decimal;
 
@@ -11,26 +11,138 @@ pub struct User {
     pub email: String,
     pub display_name: String,
     pub avatar_url: Option<String>,
+    // 本位币，统计和报表中的金额都折算为该货币
+    pub base_currency: String,
+    // bcrypt 密码哈希，不出现在任何响应中
+    #[serde(skip)]
+    pub password_hash: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-// 创建用户请求
+// 创建用户请求（注册）
 #[derive(Debug, Deserialize)]
 pub struct CreateUserRequest {
     pub username: String,
     pub email: String,
     pub display_name: String,
+    pub password: String,
+    pub base_currency: Option<String>,
+}
+
+// 更新当前用户请求
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRequest {
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub base_currency: Option<String>,
+}
+
+// 登录请求
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+// 刷新令牌请求
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+// 认证响应
+#[derive(Debug, Serialize)]
+pub struct AuthResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub user: User,
 }
 
 // 账户类型枚举
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum AccountType {
     Cash,         // 现金
     BankCard,     // 银行卡
     CreditCard,   // 信用卡
     Investment,   // 投资账户
     Crypto,       // 加密货币
+    Loan,         // 贷款（欠他人的钱）
+    Receivable,   // 借出（他人欠的钱）
+}
+
+impl AccountType {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountType::Cash => "Cash",
+            AccountType::BankCard => "BankCard",
+            AccountType::CreditCard => "CreditCard",
+            AccountType::Investment => "Investment",
+            AccountType::Crypto => "Crypto",
+            AccountType::Loan => "Loan",
+            AccountType::Receivable => "Receivable",
+        }
+    }
+}
+
+impl std::str::FromStr for AccountType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Cash" => Ok(AccountType::Cash),
+            "BankCard" => Ok(AccountType::BankCard),
+            "CreditCard" => Ok(AccountType::CreditCard),
+            "Investment" => Ok(AccountType::Investment),
+            "Crypto" => Ok(AccountType::Crypto),
+            "Loan" => Ok(AccountType::Loan),
+            "Receivable" => Ok(AccountType::Receivable),
+            other => Err(format!("unknown account type: {}", other)),
+        }
+    }
+}
+
+// 贷款的还款方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RepaymentMethod {
+    EqualInstallment, // 等额本息
+    EqualPrincipal,   // 等额本金
+}
+
+impl RepaymentMethod {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RepaymentMethod::EqualInstallment => "EqualInstallment",
+            RepaymentMethod::EqualPrincipal => "EqualPrincipal",
+        }
+    }
+}
+
+impl std::str::FromStr for RepaymentMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "EqualInstallment" => Ok(RepaymentMethod::EqualInstallment),
+            "EqualPrincipal" => Ok(RepaymentMethod::EqualPrincipal),
+            other => Err(format!("unknown repayment method: {}", other)),
+        }
+    }
+}
+
+// 贷款和借出账户的借款条件：按月还款，从 first_payment_date 起每月同一天（超过当月天数时为月末）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LoanTerms {
+    pub principal: Decimal,
+    // 年利率，如 0.049 表示 4.9%
+    pub annual_interest_rate: Decimal,
+    pub term_months: u32,
+    pub first_payment_date: NaiveDate,
+    pub repayment_method: RepaymentMethod,
 }
 
 // 账户模型
@@ -41,23 +153,89 @@ pub struct Account {
     pub name: String,
     pub account_type: AccountType,
     pub currency: String,
+    // 当前余额由期初余额和交易流水维护，不能直接修改
     pub balance: Decimal,
+    pub opening_balance: Decimal,
+    // 信用卡的信用额度、每月的账单日和还款日，日期超过当月天数时为月末
+    pub credit_limit: Option<Decimal>,
+    pub statement_closing_day: Option<u32>,
+    pub payment_due_day: Option<u32>,
+    // 贷款和借出账户的借款条件
+    pub loan: Option<LoanTerms>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-// 创建账户请求
+// 创建账户请求，贷款和借出账户未指定期初余额时以借款本金为欠款
 #[derive(Debug, Deserialize)]
 pub struct CreateAccountRequest {
     pub name: String,
     pub account_type: AccountType,
     pub currency: String,
     pub initial_balance: Option<Decimal>,
+    pub credit_limit: Option<Decimal>,
+    pub statement_closing_day: Option<u32>,
+    pub payment_due_day: Option<u32>,
+    pub loan: Option<LoanTerms>,
+}
+
+// 更新账户请求，信用卡字段和借款条件传 null 时清除
+#[derive(Debug, Deserialize)]
+pub struct UpdateAccountRequest {
+    pub name: Option<String>,
+    pub account_type: Option<AccountType>,
+    pub is_active: Option<bool>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub credit_limit: Option<Option<Decimal>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub statement_closing_day: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub payment_due_day: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub loan: Option<Option<LoanTerms>>,
+}
+
+// 信用卡账单的还款状态
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum StatementStatus {
+    Paid,    // 已还清
+    Unpaid,  // 未还清，未过还款日
+    Overdue, // 过了还款日仍未还清
+}
+
+// 信用卡账单：period_start 到 closing_date（账单日）之间的交易计入本期账单
+// 金额均以正数表示欠款，账单日之后从其他账户转入的金额计为本期还款
+#[derive(Debug, Serialize, Clone)]
+pub struct CreditCardStatement {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub period_start: NaiveDate,
+    pub closing_date: NaiveDate,
+    pub due_date: Option<NaiveDate>,
+    // 账单日的欠款
+    pub statement_balance: Decimal,
+    pub paid_amount: Decimal,
+    // 本期账单和最低还款中尚未还的部分
+    pub remaining_balance: Decimal,
+    pub minimum_payment: Decimal,
+    // 账单日之后新增、计入下期账单的欠款
+    pub unbilled_balance: Decimal,
+    pub current_balance: Decimal,
+    pub credit_limit: Option<Decimal>,
+    // 超过额度时为负数
+    pub available_credit: Option<Decimal>,
+    pub status: StatementStatus,
+}
+
+// 信用卡账单查询参数，返回 date（默认今天）当天或之前最近一个账单日的账单，金额截至 date 当天
+#[derive(Debug, Deserialize)]
+pub struct StatementQuery {
+    pub date: Option<NaiveDate>,
 }
 
 // 交易类型枚举
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TransactionType {
     Income,    // 收入
     Expense,   // 支出
@@ -65,15 +243,177 @@ pub enum TransactionType {
     Investment, // 投资
 }
 
-// 交易分类
+impl TransactionType {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionType::Income => "Income",
+            TransactionType::Expense => "Expense",
+            TransactionType::Transfer => "Transfer",
+            TransactionType::Investment => "Investment",
+        }
+    }
+}
+
+impl std::str::FromStr for TransactionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Income" => Ok(TransactionType::Income),
+            "Expense" => Ok(TransactionType::Expense),
+            "Transfer" => Ok(TransactionType::Transfer),
+            "Investment" => Ok(TransactionType::Investment),
+            other => Err(format!("unknown transaction type: {}", other)),
+        }
+    }
+}
+
+// 转账方向
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    Out, // 转出，减少账户余额
+    In,  // 转入，增加账户余额
+}
+
+impl TransferDirection {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferDirection::Out => "Out",
+            TransferDirection::In => "In",
+        }
+    }
+}
+
+impl std::str::FromStr for TransferDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Out" => Ok(TransferDirection::Out),
+            "In" => Ok(TransferDirection::In),
+            other => Err(format!("unknown transfer direction: {}", other)),
+        }
+    }
+}
+
+// 交易分类，user_id 为空表示系统分类
+// 子分类通过 parent_id 关联上级分类，最多两级，与上级分类的交易类型相同
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Category {
     pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
     pub name: String,
     pub icon: String,
     pub color: String,
     pub transaction_type: TransactionType,
     pub is_system: bool,
+    // 归档的分类不能用于新交易，已有交易保留原分类
+    pub is_archived: bool,
+    // 系统分类所属的分类目录版本，用户分类为空
+    pub catalog_version: Option<i32>,
+}
+
+// 系统分类目录，客户端可按 version 缓存
+#[derive(Debug, Serialize)]
+pub struct CategoryCatalog {
+    pub version: i32,
+    pub locale: Locale,
+    pub categories: Vec<Category>,
+}
+
+// 系统分类名称支持的语言，由 Accept-Language 请求头选择
+#[derive(Debug, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Locale {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en",
+        }
+    }
+
+    // 取 q 值最高的受支持语言，zh-TW 等中文变体使用 zh-CN，都不支持时使用默认语言
+    pub fn from_accept_language(value: &str) -> Self {
+        let mut best: Option<(f32, Locale)> = None;
+        for item in value.split(',') {
+            let mut parts = item.split(';');
+            let tag = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let locale = match tag.split('-').next() {
+                Some("zh") => Locale::ZhCn,
+                Some("en") => Locale::En,
+                _ => continue,
+            };
+            if quality > 0.0 && best.is_none_or(|(q, _)| quality > q) {
+                best = Some((quality, locale));
+            }
+        }
+        best.map(|(_, locale)| locale).unwrap_or_default()
+    }
+}
+
+// 分类列表查询参数
+#[derive(Debug, Default, Deserialize)]
+pub struct CategoryQuery {
+    pub transaction_type: Option<TransactionType>,
+    pub include_archived: Option<bool>,
+}
+
+// 创建分类请求，子分类未指定图标和颜色时沿用上级分类
+#[derive(Debug, Deserialize)]
+pub struct CreateCategoryRequest {
+    pub name: String,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    pub transaction_type: TransactionType,
+    pub parent_id: Option<Uuid>,
+}
+
+// 更新分类请求，parent_id 传 null 时改为一级分类；交易类型不能修改
+#[derive(Debug, Deserialize)]
+pub struct UpdateCategoryRequest {
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub parent_id: Option<Option<Uuid>>,
+    pub is_archived: Option<bool>,
+}
+
+// 合并分类请求：来源分类的交易和子分类移到 target_id 下，之后归档来源分类
+#[derive(Debug, Deserialize)]
+pub struct MergeCategoryRequest {
+    pub target_id: Uuid,
+}
+
+// 合并分类结果
+#[derive(Debug, Serialize)]
+pub struct MergeCategoryResponse {
+    pub target: Category,
+    pub moved_transactions: u64,
+}
+
+// 区分字段缺省（None）和显式传 null（Some(None)）
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 // 交易记录模型
@@ -90,10 +430,25 @@ pub struct Transaction {
     pub notes: Option<String>,
     pub tags: Vec<String>,
     pub transaction_date: DateTime<Utc>,
+    // 属于转账时为转账ID，同一转账的各条交易共享
+    pub transfer_id: Option<Uuid>,
+    pub transfer_direction: Option<TransferDirection>,
+    // 由重复交易自动生成时为重复交易ID
+    pub recurring_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Transaction {
+    // 交易对所属账户余额的影响：收入和转入增加，其余减少
+    pub fn balance_effect(&self) -> Decimal {
+        match (self.transaction_type, self.transfer_direction) {
+            (_, Some(TransferDirection::In)) | (TransactionType::Income, None) => self.amount,
+            _ => -self.amount,
+        }
+    }
+}
+
 // 创建交易请求
 #[derive(Debug, Deserialize)]
 pub struct CreateTransactionRequest {
@@ -119,12 +474,70 @@ pub struct UpdateTransactionRequest {
     pub transaction_date: Option<DateTime<Utc>>,
 }
 
+// 转账，由转出和转入两条关联交易组成，有手续费时另加一条支出交易
+// amount/currency 为转出金额和货币，to_amount/to_currency 为转入金额和货币
+#[derive(Debug, Serialize)]
+pub struct Transfer {
+    pub id: Uuid,
+    pub from_account_id: Uuid,
+    pub to_account_id: Uuid,
+    pub amount: Decimal,
+    pub currency: String,
+    pub to_amount: Decimal,
+    pub to_currency: String,
+    // 实际汇率：1 单位转出货币兑换的转入货币数量
+    pub exchange_rate: Decimal,
+    // 手续费从转出账户扣除，以转出货币计
+    pub fee: Option<Decimal>,
+    pub description: String,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub transfer_date: DateTime<Utc>,
+    pub debit_transaction_id: Uuid,
+    pub credit_transaction_id: Uuid,
+    pub fee_transaction_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 创建转账请求，不同货币账户之间转账需提供 to_amount 或 exchange_rate 之一
+#[derive(Debug, Deserialize)]
+pub struct CreateTransferRequest {
+    pub from_account_id: Uuid,
+    pub to_account_id: Uuid,
+    pub amount: Decimal,
+    pub to_amount: Option<Decimal>,
+    pub exchange_rate: Option<Decimal>,
+    pub fee: Option<Decimal>,
+    pub description: String,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub transfer_date: Option<DateTime<Utc>>,
+}
+
+// 更新转账请求，fee 为 0 时移除手续费
+#[derive(Debug, Deserialize)]
+pub struct UpdateTransferRequest {
+    pub from_account_id: Option<Uuid>,
+    pub to_account_id: Option<Uuid>,
+    pub amount: Option<Decimal>,
+    pub to_amount: Option<Decimal>,
+    pub exchange_rate: Option<Decimal>,
+    pub fee: Option<Decimal>,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub transfer_date: Option<DateTime<Utc>>,
+}
+
 // API响应包装器
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ApiError>,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -134,25 +547,54 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             message: "Success".to_string(),
+            error: None,
             timestamp: Utc::now(),
         }
     }
 
-    pub fn error(message: String) -> Self {
+    pub fn error(error: ApiError, message: String) -> Self {
         Self {
             success: false,
             data: None,
             message,
+            error: Some(error),
             timestamp: Utc::now(),
         }
     }
 }
 
-// 分页查询参数
+// 错误信息，code 为稳定的机器可读错误码，客户端据此显示本地化提示
+#[derive(Debug, Serialize)]
+pub struct ApiError {
+    pub code: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+}
+
+// 字段级校验错误
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+// 分页查询参数，指定 cursor 时按游标分页，不能同时指定 page
 #[derive(Debug, Deserialize)]
 pub struct PaginationQuery {
     pub page: Option<u64>,
     pub limit: Option<u64>,
+    pub cursor: Option<String>,
 }
 
 impl Default for PaginationQuery {
@@ -160,27 +602,875 @@ impl Default for PaginationQuery {
         Self {
             page: Some(1),
             limit: Some(20),
+            cursor: None,
         }
     }
 }
 
-// 分页响应
+// 交易列表查询参数，多个账户、分类或标签用逗号分隔
+#[derive(Debug, Default, Deserialize)]
+pub struct TransactionQuery {
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+    pub cursor: Option<String>,
+    // 起止时间均包含在内
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub account_ids: Option<String>,
+    pub category_ids: Option<String>,
+    pub transaction_type: Option<TransactionType>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub tags: Option<String>,
+    pub tag_match: Option<TagMatch>,
+    // 在描述和备注中搜索，多个关键词用空格分隔，需全部命中
+    pub q: Option<String>,
+    pub sort_by: Option<TransactionSortField>,
+    pub sort_order: Option<SortOrder>,
+}
+
+// 按多个标签筛选时，命中任意一个还是全部
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSortField {
+    #[default]
+    TransactionDate,
+    Amount,
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+// 分页响应，游标分页时不返回 page 和 total
 #[derive(Debug, Serialize)]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
-    pub page: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
     pub limit: u64,
-    pub total: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
     pub has_next: bool,
+    // 下一页的游标，没有下一页时为空
+    pub next_cursor: Option<String>,
+}
+
+// 标签及其使用情况
+#[derive(Debug, Serialize, Clone)]
+pub struct Tag {
+    pub name: String,
+    pub usage_count: u64,
+    // 带该标签的交易中最近的交易时间
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+// 标签列表查询参数，prefix 用于输入时自动补全，不区分大小写
+#[derive(Debug, Default, Deserialize)]
+pub struct TagQuery {
+    pub prefix: Option<String>,
+    pub limit: Option<u64>,
+}
+
+// 重命名标签请求，新名称已存在时需使用合并
+#[derive(Debug, Deserialize)]
+pub struct RenameTagRequest {
+    pub name: String,
+}
+
+// 合并标签请求：交易中的该标签替换为 target
+#[derive(Debug, Deserialize)]
+pub struct MergeTagRequest {
+    pub target: String,
+}
+
+// 重命名或合并标签的结果
+#[derive(Debug, Serialize)]
+pub struct TagChangeResponse {
+    pub tag: Tag,
+    pub updated_transactions: u64,
+}
+
+// 按标签统计金额的查询参数，默认统计支出
+#[derive(Debug, Default, Deserialize)]
+pub struct TagSpendingQuery {
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub transaction_type: Option<TransactionType>,
+}
+
+// 某个标签的金额合计，按交易当天的汇率折算为用户本位币
+#[derive(Debug, Serialize)]
+pub struct TagSpending {
+    pub tag: String,
+    pub total: Decimal,
+    pub transaction_count: u64,
+}
+
+// 按标签统计的结果，一笔交易有多个标签时计入每个标签
+#[derive(Debug, Serialize)]
+pub struct TagSpendingReport {
+    pub base_currency: String,
+    pub transaction_type: TransactionType,
+    pub tags: Vec<TagSpending>,
+}
+
+// 预算周期，按 UTC 自然周（周一开始）、自然月、自然年划分；Custom 为 start_date 到 end_date 的单个周期
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetPeriod {
+    Weekly,
+    Monthly,
+    Yearly,
+    Custom,
+}
+
+impl BudgetPeriod {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Weekly => "Weekly",
+            BudgetPeriod::Monthly => "Monthly",
+            BudgetPeriod::Yearly => "Yearly",
+            BudgetPeriod::Custom => "Custom",
+        }
+    }
+}
+
+impl std::str::FromStr for BudgetPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Weekly" => Ok(BudgetPeriod::Weekly),
+            "Monthly" => Ok(BudgetPeriod::Monthly),
+            "Yearly" => Ok(BudgetPeriod::Yearly),
+            "Custom" => Ok(BudgetPeriod::Custom),
+            other => Err(format!("unknown budget period: {}", other)),
+        }
+    }
+}
+
+// 预算模型，category_id 和 tag 二选一作为统计范围，只统计支出
+// 分类预算包括其子分类；rollover 为 true 时上一周期未用完的金额计入下一周期
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Budget {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub category_id: Option<Uuid>,
+    pub tag: Option<String>,
+    pub period: BudgetPeriod,
+    pub amount: Decimal,
+    pub currency: String,
+    pub rollover: bool,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 创建预算请求，currency 缺省为用户本位币，start_date 缺省为当天；Custom 周期必须指定 end_date
+#[derive(Debug, Deserialize)]
+pub struct CreateBudgetRequest {
+    pub name: String,
+    pub category_id: Option<Uuid>,
+    pub tag: Option<String>,
+    pub period: BudgetPeriod,
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub rollover: Option<bool>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+// 更新预算请求，统计范围、周期和货币不能修改；end_date 传 null 时取消结束日期
+#[derive(Debug, Deserialize)]
+pub struct UpdateBudgetRequest {
+    pub name: Option<String>,
+    pub amount: Option<Decimal>,
+    pub rollover: Option<bool>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub end_date: Option<Option<NaiveDate>>,
+}
+
+// 预算执行情况查询参数，date 缺省为当天
+#[derive(Debug, Default, Deserialize)]
+pub struct BudgetStatusQuery {
+    pub date: Option<NaiveDate>,
+}
+
+// 预算在 date 所在周期的执行情况，金额均为预算货币
+#[derive(Debug, Serialize)]
+pub struct BudgetStatus {
+    pub budget_id: Uuid,
+    pub name: String,
+    pub currency: String,
+    pub period_start: NaiveDate,
+    // 周期最后一天（含）
+    pub period_end: NaiveDate,
+    pub amount: Decimal,
+    // 之前周期结转的金额
+    pub rolled_over: Decimal,
+    pub available: Decimal,
+    // 周期开始到 date 当天的支出
+    pub spent: Decimal,
+    pub remaining: Decimal,
+    // 按目前的日均支出推算的周期总支出
+    pub projected_spend: Decimal,
+    pub is_overspent: bool,
+    pub is_projected_overspent: bool,
+}
+
+// 重复频率
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl RecurrenceFrequency {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecurrenceFrequency::Daily => "Daily",
+            RecurrenceFrequency::Weekly => "Weekly",
+            RecurrenceFrequency::Monthly => "Monthly",
+        }
+    }
+}
+
+impl std::str::FromStr for RecurrenceFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Daily" => Ok(RecurrenceFrequency::Daily),
+            "Weekly" => Ok(RecurrenceFrequency::Weekly),
+            "Monthly" => Ok(RecurrenceFrequency::Monthly),
+            other => Err(format!("unknown recurrence frequency: {}", other)),
+        }
+    }
+}
+
+// 重复规则，相当于 iCalendar RRULE 的一个子集，日期均为 UTC 日期
+// Weekly 在 start_date 的星期几发生；Monthly 在每月 day_of_month 日（超过当月天数时为月末）
+// 或每月最后一个工作日（周一至周五）发生
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RecurrenceSchedule {
+    pub frequency: RecurrenceFrequency,
+    // 每隔几个周期发生一次
+    #[serde(default = "default_interval")]
+    pub interval: u32,
+    pub day_of_month: Option<u32>,
+    #[serde(default)]
+    pub last_business_day: bool,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    // 最多发生次数，跳过的也计入
+    pub count: Option<u32>,
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+impl RecurrenceSchedule {
+    // 按日期先后列出全部发生日期
+    pub fn occurrences(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        let interval = self.interval.max(1);
+        (0u32..)
+            .map_while(move |k| self.candidate(k.checked_mul(interval)?))
+            .filter(move |date| *date >= self.start_date)
+            .take_while(move |date| self.end_date.is_none_or(|end| *date <= end))
+            .take(self.count.map_or(usize::MAX, |count| count as usize))
+    }
+
+    // date 这次发生之后的下一次发生，count 为包括 date 在内已发生的次数；
+    // 从 date 所在的周期直接计算，不从开始日期遍历
+    pub fn next_after(&self, date: NaiveDate, count: u32) -> Option<NaiveDate> {
+        if self.count.is_some_and(|limit| count >= limit) {
+            return None;
+        }
+        let interval = self.interval.max(1);
+        let elapsed = match self.frequency {
+            RecurrenceFrequency::Daily => (date - self.start_date).num_days(),
+            RecurrenceFrequency::Weekly => (date - self.start_date).num_days() / 7,
+            RecurrenceFrequency::Monthly => {
+                (date.year() - self.start_date.year()) as i64 * 12 + date.month() as i64
+                    - self.start_date.month() as i64
+            }
+        };
+        let mut k = u32::try_from(elapsed.max(0)).ok()? / interval + 1;
+        loop {
+            let next = self.candidate(k.checked_mul(interval)?)?;
+            if next > date {
+                return Some(next).filter(|next| self.end_date.is_none_or(|end| *next <= end));
+            }
+            k += 1;
+        }
+    }
+
+    // 第 n 个周期中的日期，每月的日期可能早于 start_date
+    fn candidate(&self, n: u32) -> Option<NaiveDate> {
+        match self.frequency {
+            RecurrenceFrequency::Daily => self.start_date.checked_add_days(Days::new(n as u64)),
+            RecurrenceFrequency::Weekly => self.start_date.checked_add_days(Days::new(n as u64 * 7)),
+            RecurrenceFrequency::Monthly => {
+                let first = self.start_date.with_day(1)?.checked_add_months(Months::new(n))?;
+                let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+                if self.last_business_day {
+                    let mut date = last;
+                    while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                        date = date.pred_opt()?;
+                    }
+                    Some(date)
+                } else {
+                    let day = self.day_of_month.unwrap_or(self.start_date.day());
+                    first.with_day(day.min(last.day()))
+                }
+            }
+        }
+    }
+}
+
+// 重复交易：按规则定期生成交易的模板，已生成的交易不随模板修改
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecurringTransaction {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub transaction_type: TransactionType,
+    pub amount: Decimal,
+    pub description: String,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub schedule: RecurrenceSchedule,
+    // 下一次发生的日期，全部发生完毕后为空
+    pub next_date: Option<NaiveDate>,
+    // 已生成和跳过的次数
+    pub occurrence_count: u32,
+    pub is_paused: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 创建重复交易请求：交易字段与创建交易相同（不能指定 transaction_date），另加重复规则
+#[derive(Debug, Deserialize)]
+pub struct CreateRecurringTransactionRequest {
+    #[serde(flatten)]
+    pub template: CreateTransactionRequest,
+    pub schedule: RecurrenceSchedule,
+}
+
+// 修改重复交易请求，只影响 from_date 及之后的发生，之前的按原模板生成
+// from_date 缺省为下一次发生的日期；修改重复规则时从新规则的 start_date 起生效
+#[derive(Debug, Deserialize)]
+pub struct UpdateRecurringTransactionRequest {
+    pub account_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub category_id: Option<Option<Uuid>>,
+    pub amount: Option<Decimal>,
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub notes: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+    pub schedule: Option<RecurrenceSchedule>,
+    pub from_date: Option<NaiveDate>,
+}
+
+// 信用卡分期：把一笔信用卡消费的本金分摊到每月的账单中，每期另收手续费
+// 手续费由关联的重复交易按期记入信用卡，本金已在原消费交易中记账，只影响每期账单的金额
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallmentPlan {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub transaction_id: Uuid,
+    pub principal: Decimal,
+    pub periods: u32,
+    pub fee_per_period: Decimal,
+    // 第一期入账日期，之后每月同一天
+    pub first_period_date: NaiveDate,
+    // 记入手续费的重复交易，提前还清后为空
+    pub recurring_id: Option<Uuid>,
+    pub paid_off_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 分期中的一期；提前还清时剩余本金合并为还清当天的一期，不再收手续费
+#[derive(Debug, Serialize, Clone)]
+pub struct InstallmentPeriod {
+    pub number: u32,
+    pub date: NaiveDate,
+    pub principal: Decimal,
+    pub fee: Decimal,
+    pub is_billed: bool,
+}
+
+// 分期详情，剩余金额截至今天
+#[derive(Debug, Serialize)]
+pub struct InstallmentPlanDetails {
+    #[serde(flatten)]
+    pub plan: InstallmentPlan,
+    pub currency: String,
+    pub billed_periods: u32,
+    pub remaining_principal: Decimal,
+    pub remaining_fees: Decimal,
+    pub schedule: Vec<InstallmentPeriod>,
+}
+
+// 创建分期请求，本金为原交易金额；手续费按每期固定金额 fee_per_period 或每期费率 fee_rate（本金的比例）计算，
+// 都不指定时免手续费；first_period_date 缺省为消费当天
+#[derive(Debug, Deserialize)]
+pub struct CreateInstallmentPlanRequest {
+    pub periods: u32,
+    pub fee_per_period: Option<Decimal>,
+    pub fee_rate: Option<Decimal>,
+    pub first_period_date: Option<NaiveDate>,
+}
+
+// 提前还清请求，date 缺省为今天
+#[derive(Debug, Deserialize)]
+pub struct PayoffInstallmentPlanRequest {
+    pub date: Option<NaiveDate>,
+}
+
+// 还款计划表中的一期，remaining_principal 为本期还款后按计划剩余的本金
+#[derive(Debug, Serialize, Clone)]
+pub struct AmortizationRow {
+    pub number: u32,
+    pub date: NaiveDate,
+    pub payment: Decimal,
+    pub principal: Decimal,
+    pub interest: Decimal,
+    pub remaining_principal: Decimal,
+    pub is_paid: bool,
+}
+
+// 贷款和借出账户按借款条件计算的还款计划表；实际已还本金按账户余额计算，
+// 按计划累计本金不超过已还本金的各期为已还
+#[derive(Debug, Serialize)]
+pub struct AmortizationSchedule {
+    pub account_id: Uuid,
+    pub currency: String,
+    #[serde(flatten)]
+    pub terms: LoanTerms,
+    pub total_payment: Decimal,
+    pub total_interest: Decimal,
+    pub paid_principal: Decimal,
+    pub outstanding_principal: Decimal,
+    pub paid_periods: u32,
+    pub rows: Vec<AmortizationRow>,
+}
+
+// 贷款还款或收回借款请求，account_id 为付款（贷款）或收款（借出）的账户
+// 利息缺省为按剩余本金计算的一期利息，提前还款时可以指定为 0；金额缺省为下一期计划本金加利息
+#[derive(Debug, Deserialize)]
+pub struct LoanPaymentRequest {
+    pub account_id: Uuid,
+    pub amount: Option<Decimal>,
+    pub interest: Option<Decimal>,
+    pub description: Option<String>,
+    pub payment_date: Option<DateTime<Utc>>,
+}
+
+// 还款结果：本金部分为贷款（借出）账户与 account_id 之间的转账，
+// 利息部分为 account_id 上的一笔支出（贷款）或收入（借出）
+#[derive(Debug, Serialize)]
+pub struct LoanPayment {
+    pub principal: Decimal,
+    pub interest: Decimal,
+    pub outstanding_principal: Decimal,
+    pub principal_transfer: Option<Transfer>,
+    pub interest_transaction: Option<Transaction>,
+}
+
+// 投资品种的资产类别
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AssetClass {
+    Stock,  // 股票
+    Fund,   // 基金
+    Bond,   // 债券
+    Crypto, // 加密货币
+    Other,  // 其他
+}
+
+impl AssetClass {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetClass::Stock => "Stock",
+            AssetClass::Fund => "Fund",
+            AssetClass::Bond => "Bond",
+            AssetClass::Crypto => "Crypto",
+            AssetClass::Other => "Other",
+        }
+    }
+}
+
+impl std::str::FromStr for AssetClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Stock" => Ok(AssetClass::Stock),
+            "Fund" => Ok(AssetClass::Fund),
+            "Bond" => Ok(AssetClass::Bond),
+            "Crypto" => Ok(AssetClass::Crypto),
+            "Other" => Ok(AssetClass::Other),
+            other => Err(format!("unknown asset class: {}", other)),
+        }
+    }
+}
+
+// 投资事件类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum InvestmentEventType {
+    Buy,      // 买入，quantity 和 price
+    Sell,     // 卖出，quantity 和 price
+    Dividend, // 现金分红，amount
+    Split,    // 拆股或合股，quantity 为每一股变为的股数
+}
+
+impl InvestmentEventType {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InvestmentEventType::Buy => "Buy",
+            InvestmentEventType::Sell => "Sell",
+            InvestmentEventType::Dividend => "Dividend",
+            InvestmentEventType::Split => "Split",
+        }
+    }
+}
+
+impl std::str::FromStr for InvestmentEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Buy" => Ok(InvestmentEventType::Buy),
+            "Sell" => Ok(InvestmentEventType::Sell),
+            "Dividend" => Ok(InvestmentEventType::Dividend),
+            "Split" => Ok(InvestmentEventType::Split),
+            other => Err(format!("unknown investment event type: {}", other)),
+        }
+    }
+}
+
+// 投资账户中某个品种的一次买入、卖出、分红或拆股，持仓和成本由事件按时间先后重放得出
+// 只记录持仓变化，不影响账户余额，资金进出仍通过交易和转账记录；金额使用账户货币，手续费计入成本或从卖出所得中扣除
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvestmentEvent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub symbol: String,
+    pub asset_class: AssetClass,
+    pub event_type: InvestmentEventType,
+    pub event_date: DateTime<Utc>,
+    pub quantity: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    pub fee: Decimal,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 创建投资事件请求，asset_class 缺省沿用该品种已有事件的类别，没有时为 Other
+#[derive(Debug, Deserialize)]
+pub struct CreateInvestmentEventRequest {
+    pub symbol: String,
+    pub asset_class: Option<AssetClass>,
+    pub event_type: InvestmentEventType,
+    pub event_date: DateTime<Utc>,
+    pub quantity: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    pub fee: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+// 更新投资事件请求，品种和事件类型不能修改
+#[derive(Debug, Deserialize)]
+pub struct UpdateInvestmentEventRequest {
+    pub asset_class: Option<AssetClass>,
+    pub event_date: Option<DateTime<Utc>>,
+    pub quantity: Option<Decimal>,
+    pub price: Option<Decimal>,
+    pub amount: Option<Decimal>,
+    pub fee: Option<Decimal>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub notes: Option<Option<String>>,
+}
+
+// 卖出时的成本计算方法
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CostBasisMethod {
+    #[default]
+    Fifo,        // 先进先出，按卖出的批次计算成本
+    AverageCost, // 移动加权平均成本
+}
+
+// 持仓查询参数，date 缺省为今天
+#[derive(Debug, Deserialize)]
+pub struct HoldingsQuery {
+    pub method: Option<CostBasisMethod>,
+    pub date: Option<NaiveDate>,
+}
+
+// 一次买入形成的批次，卖出时按先进先出减少数量，拆股时数量和单位成本随之调整
+#[derive(Debug, Serialize, Clone)]
+pub struct Lot {
+    pub event_id: Uuid,
+    pub acquired_at: DateTime<Utc>,
+    pub quantity: Decimal,
+    pub unit_cost: Decimal,
+    pub cost_basis: Decimal,
+}
+
+// 某个品种的持仓，cost_basis 按所选方法计算；已全部卖出的品种数量为 0
+#[derive(Debug, Serialize, Clone)]
+pub struct Holding {
+    pub symbol: String,
+    pub asset_class: AssetClass,
+    pub quantity: Decimal,
+    pub cost_basis: Decimal,
+    pub average_cost: Decimal,
+    pub realized_gain: Decimal,
+    pub dividends: Decimal,
+    pub lots: Vec<Lot>,
+}
+
+// 一次卖出的已实现收益，proceeds 为扣除手续费后的卖出所得
+#[derive(Debug, Serialize, Clone)]
+pub struct RealizedGain {
+    pub event_id: Uuid,
+    pub symbol: String,
+    pub sold_at: DateTime<Utc>,
+    pub quantity: Decimal,
+    pub proceeds: Decimal,
+    pub cost_basis: Decimal,
+    pub gain: Decimal,
+}
+
+// 账户截至 date 当天的持仓和已实现收益
+#[derive(Debug, Serialize)]
+pub struct AccountHoldings {
+    pub account_id: Uuid,
+    pub currency: String,
+    pub method: CostBasisMethod,
+    pub date: NaiveDate,
+    pub total_cost_basis: Decimal,
+    pub total_realized_gain: Decimal,
+    pub total_dividends: Decimal,
+    pub holdings: Vec<Holding>,
+    pub realized_gains: Vec<RealizedGain>,
+}
+
+// 用户记录的品种价格，同一品种同一天只保留一个价格
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecurityPrice {
+    pub user_id: Uuid,
+    pub symbol: String,
+    pub price_date: NaiveDate,
+    pub price: Decimal,
+    pub currency: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 记录品种价格请求，已有同一天的价格时覆盖
+#[derive(Debug, Deserialize)]
+pub struct SecurityPriceRequest {
+    pub symbol: String,
+    pub price_date: NaiveDate,
+    pub price: Decimal,
+    pub currency: String,
+}
+
+// 品种价格查询参数
+#[derive(Debug, Deserialize)]
+pub struct SecurityPriceQuery {
+    pub symbol: Option<String>,
+}
+
+// 投资组合表现查询参数，统计 start_date 到 end_date（含）之间的表现
+// end_date 缺省为今天，start_date 缺省为最早的投资事件日期
+#[derive(Debug, Deserialize)]
+pub struct PerformanceQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub method: Option<CostBasisMethod>,
+}
+
+// 某个品种在 end_date 的市值，price_date 为空表示没有价格，按平均成本估值
+#[derive(Debug, Serialize, Clone)]
+pub struct HoldingValuation {
+    pub symbol: String,
+    pub asset_class: AssetClass,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub price_date: Option<NaiveDate>,
+    pub market_value: Decimal,
+    pub cost_basis: Decimal,
+    pub unrealized_gain: Decimal,
+}
+
+// 一段时间内的投资表现：start_value 为期初（start_date 前一天）市值，end_value 为期末市值，
+// net_contributions 为期间买入成本减去卖出所得和分红；收益率为小数，时间加权收益率为期间收益率，
+// 资金加权收益率（XIRR）为年化收益率，无法计算时为空
+#[derive(Debug, Serialize, Clone)]
+pub struct PerformanceSummary {
+    pub currency: String,
+    pub start_value: Decimal,
+    pub end_value: Decimal,
+    pub net_contributions: Decimal,
+    pub cost_basis: Decimal,
+    pub unrealized_gain: Decimal,
+    pub realized_gain: Decimal,
+    pub dividends: Decimal,
+    pub time_weighted_return: Option<Decimal>,
+    pub money_weighted_return: Option<Decimal>,
+}
+
+// 单个投资账户的表现，金额使用账户货币
+#[derive(Debug, Serialize)]
+pub struct AccountPerformance {
+    pub account_id: Uuid,
+    pub account_name: String,
+    #[serde(flatten)]
+    pub summary: PerformanceSummary,
+    pub holdings: Vec<HoldingValuation>,
+}
+
+// 全部投资账户的表现，合计金额按各日汇率折算为用户本位币
+#[derive(Debug, Serialize)]
+pub struct PortfolioPerformance {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub method: CostBasisMethod,
+    #[serde(flatten)]
+    pub total: PerformanceSummary,
+    pub accounts: Vec<AccountPerformance>,
+}
+
+// 即将到期款项的来源
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum UpcomingKind {
+    Recurring,     // 重复交易
+    CreditCardDue, // 信用卡还款
+}
+
+impl UpcomingKind {
+    // 数据库中存储的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpcomingKind::Recurring => "Recurring",
+            UpcomingKind::CreditCardDue => "CreditCardDue",
+        }
+    }
+}
+
+impl std::str::FromStr for UpcomingKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Recurring" => Ok(UpcomingKind::Recurring),
+            "CreditCardDue" => Ok(UpcomingKind::CreditCardDue),
+            other => Err(format!("unknown upcoming kind: {}", other)),
+        }
+    }
+}
+
+// 即将到期的款项，source_id 为重复交易或信用卡账户的 id
+// 信用卡还款的 transaction_type 为 Transfer，amount 为应还金额
+#[derive(Debug, Serialize, Clone)]
+pub struct UpcomingPayment {
+    pub kind: UpcomingKind,
+    pub source_id: Uuid,
+    pub account_id: Uuid,
+    pub due_date: NaiveDate,
+    pub description: String,
+    pub transaction_type: TransactionType,
+    pub amount: Decimal,
+    pub currency: String,
+}
+
+// 即将到期款项查询参数，列出今天起 days 天内（含今天）到期的款项，默认 30 天
+#[derive(Debug, Deserialize)]
+pub struct UpcomingQuery {
+    pub days: Option<u32>,
+}
+
+// 到期提醒，同一款项的同一到期日只提醒一次
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: UpcomingKind,
+    pub source_id: Uuid,
+    pub due_date: NaiveDate,
+    pub title: String,
+    pub amount: Decimal,
+    pub currency: String,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+// 提醒列表查询参数
+#[derive(Debug, Deserialize)]
+pub struct NotificationQuery {
+    pub unread_only: Option<bool>,
+}
+
+// 汇率：1 单位 base_currency 兑换 rate 单位 quote_currency
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExchangeRate {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate_date: NaiveDate,
+    pub rate: Decimal,
+    pub source: String,
+}
+
+// 汇率查询参数，date 缺省为当天
+#[derive(Debug, Deserialize)]
+pub struct ExchangeRateQuery {
+    pub base: String,
+    pub quote: String,
+    pub date: Option<NaiveDate>,
 }
 
-// 统计数据
+// 统计数据，收支金额按交易当天的汇率折算为用户本位币
 #[derive(Debug, Serialize)]
 pub struct FinancialSummary {
+    pub base_currency: String,
     pub total_income: Decimal,
     pub total_expense: Decimal,
     pub net_income: Decimal,
+    // 所有账户余额按当天汇率折算后的合计
+    pub total_balance: Decimal,
     pub account_balances: Vec<AccountBalance>,
+    // 无法获取汇率的货币，这些货币的收支和余额不计入合计
+    pub unconverted_currencies: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -189,4 +1479,6 @@ pub struct AccountBalance {
     pub account_name: String,
     pub balance: Decimal,
     pub currency: String,
+    // 无法获取汇率时为空
+    pub converted_balance: Option<Decimal>,
 }
\ No newline at end of file
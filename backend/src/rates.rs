@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::models::ExchangeRate;
+
+mod file;
+mod http;
+mod memory;
+mod redis;
+
+pub use self::file::FileRateProvider;
+pub use self::http::HttpRateProvider;
+pub use self::memory::MemoryRateCache;
+pub use self::redis::RedisRateCache;
+
+// 汇率数据来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateProviderKind {
+    None, // 只使用数据库中已有的汇率
+    File, // 本地 JSON 文件，离线环境和测试使用
+    Http, // Frankfurter 兼容的 HTTP 接口
+}
+
+impl std::str::FromStr for RateProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(RateProviderKind::None),
+            "file" => Ok(RateProviderKind::File),
+            "http" => Ok(RateProviderKind::Http),
+            other => Err(format!("unknown rate provider: {}", other)),
+        }
+    }
+}
+
+// 汇率缓存后端类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateCacheBackend {
+    Redis,
+    Memory,
+}
+
+impl std::str::FromStr for RateCacheBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "redis" => Ok(RateCacheBackend::Redis),
+            "memory" => Ok(RateCacheBackend::Memory),
+            other => Err(format!("unknown rate cache backend: {}", other)),
+        }
+    }
+}
+
+// 根据配置创建汇率来源，source 为文件路径或接口地址
+pub fn provider(kind: RateProviderKind, source: &str) -> RateResult<Option<Arc<dyn RateProvider>>> {
+    let provider: Arc<dyn RateProvider> = match kind {
+        RateProviderKind::None => return Ok(None),
+        RateProviderKind::File => Arc::new(FileRateProvider::load(source)?),
+        RateProviderKind::Http => Arc::new(HttpRateProvider::new(source)?),
+    };
+    Ok(Some(provider))
+}
+
+// 根据配置创建汇率缓存
+pub async fn connect_cache(backend: RateCacheBackend, redis_url: &str) -> RateResult<Arc<dyn RateCache>> {
+    let cache: Arc<dyn RateCache> = match backend {
+        RateCacheBackend::Redis => Arc::new(RedisRateCache::connect(redis_url).await?),
+        RateCacheBackend::Memory => Arc::new(MemoryRateCache::new()),
+    };
+    Ok(cache)
+}
+
+// 汇率子系统错误
+#[derive(Debug, thiserror::Error)]
+pub enum RateError {
+    #[error(transparent)]
+    Redis(#[from] ::redis::RedisError),
+
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+pub type RateResult<T> = Result<T, RateError>;
+
+// 某一天以 base 为基准的一组汇率，文件和 HTTP 接口使用相同的格式：
+// {"base": "USD", "date": "2026-01-02", "rates": {"CNY": 7.03, "EUR": 0.91}}
+#[derive(Debug, Clone, Deserialize)]
+struct RateSnapshot {
+    base: String,
+    date: NaiveDate,
+    rates: HashMap<String, Decimal>,
+}
+
+impl RateSnapshot {
+    fn into_rates(self, source: &str) -> Vec<ExchangeRate> {
+        self.rates
+            .into_iter()
+            .filter(|(quote, _)| *quote != self.base)
+            .map(|(quote, rate)| ExchangeRate {
+                base_currency: self.base.clone(),
+                quote_currency: quote,
+                rate_date: self.date,
+                rate: rate.normalize(),
+                source: source.to_string(),
+            })
+            .collect()
+    }
+}
+
+// 汇率来源
+#[async_trait]
+pub trait RateProvider: Send + Sync + std::fmt::Debug {
+    // 以 base 为基准的汇率，取 date 当天或之前最近一次发布的数据
+    async fn fetch_rates(&self, base: &str, date: NaiveDate) -> RateResult<Vec<ExchangeRate>>;
+}
+
+// 汇率查询结果缓存，按请求的日期缓存，避免重复查询数据库和外部接口
+#[async_trait]
+pub trait RateCache: Send + Sync + std::fmt::Debug {
+    async fn get_rate(&self, base: &str, quote: &str, date: NaiveDate) -> RateResult<Option<ExchangeRate>>;
+    async fn put_rate(&self, date: NaiveDate, rate: &ExchangeRate, ttl_secs: i64) -> RateResult<()>;
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use super::{RateProvider, RateResult, RateSnapshot};
+use crate::models::ExchangeRate;
+
+// 从本地 JSON 文件读取汇率，文件内容为 RateSnapshot 数组，启动时一次性加载
+#[derive(Debug)]
+pub struct FileRateProvider {
+    snapshots: Vec<RateSnapshot>,
+}
+
+impl FileRateProvider {
+    pub fn load(path: &str) -> RateResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let snapshots = serde_json::from_str(&content)?;
+        Ok(Self { snapshots })
+    }
+}
+
+#[async_trait]
+impl RateProvider for FileRateProvider {
+    async fn fetch_rates(&self, base: &str, date: NaiveDate) -> RateResult<Vec<ExchangeRate>> {
+        let latest = self
+            .snapshots
+            .iter()
+            .filter(|snapshot| snapshot.base == base && snapshot.date <= date)
+            .max_by_key(|snapshot| snapshot.date);
+        Ok(latest
+            .map(|snapshot| snapshot.clone().into_rates("file"))
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use super::{RateProvider, RateResult, RateSnapshot};
+use crate::models::ExchangeRate;
+
+// Frankfurter 兼容接口: GET {base_url}/{date}?from={base}
+// 非工作日返回之前最近一个工作日的汇率，响应中的 date 为实际日期
+#[derive(Debug)]
+pub struct HttpRateProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl HttpRateProvider {
+    pub fn new(base_url: &str) -> RateResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl RateProvider for HttpRateProvider {
+    async fn fetch_rates(&self, base: &str, date: NaiveDate) -> RateResult<Vec<ExchangeRate>> {
+        let snapshot: RateSnapshot = self
+            .client
+            .get(format!("{}/{}", self.base_url, date))
+            .query(&[("from", base)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(snapshot.into_rates("http"))
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use super::{RateCache, RateResult};
+use crate::models::ExchangeRate;
+
+#[derive(Debug, Clone)]
+struct CachedRate {
+    rate: ExchangeRate,
+    expires_at: DateTime<Utc>,
+}
+
+// 内存汇率缓存，用于单元测试和本地演示
+#[derive(Debug, Default)]
+pub struct MemoryRateCache {
+    rates: RwLock<HashMap<(String, String, NaiveDate), CachedRate>>,
+}
+
+impl MemoryRateCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateCache for MemoryRateCache {
+    async fn get_rate(&self, base: &str, quote: &str, date: NaiveDate) -> RateResult<Option<ExchangeRate>> {
+        let rates = self.rates.read().unwrap();
+        Ok(rates
+            .get(&(base.to_string(), quote.to_string(), date))
+            .filter(|cached| cached.expires_at > Utc::now())
+            .map(|cached| cached.rate.clone()))
+    }
+
+    async fn put_rate(&self, date: NaiveDate, rate: &ExchangeRate, ttl_secs: i64) -> RateResult<()> {
+        let mut rates = self.rates.write().unwrap();
+        let now = Utc::now();
+        rates.retain(|_, cached| cached.expires_at > now);
+        rates.insert(
+            (rate.base_currency.clone(), rate.quote_currency.clone(), date),
+            CachedRate {
+                rate: rate.clone(),
+                expires_at: now + Duration::seconds(ttl_secs),
+            },
+        );
+        Ok(())
+    }
+}
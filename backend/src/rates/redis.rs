@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+
+use super::{RateCache, RateResult};
+use crate::models::ExchangeRate;
+
+// 缓存键: exchange_rate:{base}:{quote}:{date} -> ExchangeRate JSON
+fn rate_key(base: &str, quote: &str, date: NaiveDate) -> String {
+    format!("exchange_rate:{}:{}:{}", base, quote, date)
+}
+
+// Redis 汇率缓存，多实例共享
+#[derive(Clone)]
+pub struct RedisRateCache {
+    conn: MultiplexedConnection,
+}
+
+impl std::fmt::Debug for RedisRateCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisRateCache").finish_non_exhaustive()
+    }
+}
+
+impl RedisRateCache {
+    pub async fn connect(redis_url: &str) -> RateResult<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let conn = client.get_multiplexed_async_connection().await?;
+        Ok(Self { conn })
+    }
+}
+
+#[async_trait]
+impl RateCache for RedisRateCache {
+    async fn get_rate(&self, base: &str, quote: &str, date: NaiveDate) -> RateResult<Option<ExchangeRate>> {
+        let mut conn = self.conn.clone();
+        let value: Option<String> = conn.get(rate_key(base, quote, date)).await?;
+        Ok(value.map(|value| serde_json::from_str(&value)).transpose()?)
+    }
+
+    async fn put_rate(&self, date: NaiveDate, rate: &ExchangeRate, ttl_secs: i64) -> RateResult<()> {
+        let mut conn = self.conn.clone();
+        let key = rate_key(&rate.base_currency, &rate.quote_currency, date);
+        conn.set_ex::<_, _, ()>(key, serde_json::to_string(rate)?, ttl_secs.max(1) as u64)
+            .await?;
+        Ok(())
+    }
+}
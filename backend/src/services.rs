@@ -1,14 +1,35 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::auth;
+use crate::db::{
+    Cursor, PageStart, SortValue, Storage, StorageBackend, StorageError, TransactionFilter, TransactionSort,
+};
+use crate::models::*;
+use crate::rates::{RateCache, RateCacheBackend, RateError, RateProvider, RateProviderKind};
+use crate::session::{RotateOutcome, SessionBackend, SessionError, SessionStore};
+use crate::validation::{currency_scale, Validator, MAX_EXCHANGE_RATE};
+
 // 应用状态结构
 #[derive(Debug)]
 pub struct AppState {
-    // TODO: 添加数据库连接池
-    // pub db: DatabasePool,
-    
-    // TODO: 添加缓存客户端
-    // pub redis: RedisPool,
-    
+    // 存储后端
+    pub storage: Arc<dyn Storage>,
+
+    // 登录会话存储
+    pub sessions: Arc<dyn SessionStore>,
+
+    // 汇率缓存和汇率来源，未配置来源时只使用数据库中已有的汇率
+    pub rate_cache: Arc<dyn RateCache>,
+    pub rate_provider: Option<Arc<dyn RateProvider>>,
+
     // 应用配置
     pub config: AppConfig,
 }
@@ -16,35 +37,283 @@ pub struct AppState {
 // 应用配置
 #[derive(Debug, Clone)]
 pub struct AppConfig {
+    pub storage_backend: StorageBackend,
     pub database_url: String,
+    pub session_backend: SessionBackend,
     pub redis_url: String,
+    pub rate_provider: RateProviderKind,
+    // 文件来源为 JSON 文件路径，HTTP 来源为接口地址
+    pub rate_provider_source: String,
+    pub rate_cache_backend: RateCacheBackend,
     pub jwt_secret: String,
+    // 访问令牌和刷新令牌有效期（秒）
+    pub access_token_ttl: i64,
+    pub refresh_token_ttl: i64,
     pub port: u16,
+    // 启动时是否自动执行数据库迁移
+    pub auto_migrate: bool,
+    // 后台检查到期重复交易的间隔（秒）
+    pub recurring_interval_secs: u64,
+    // 后台生成到期提醒的间隔（秒）和提前提醒的天数
+    pub reminder_interval_secs: u64,
+    pub reminder_days: u32,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
+        let storage_backend = std::env::var("STORAGE_BACKEND")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(StorageBackend::Postgres);
+        let default_database_url = match storage_backend {
+            StorageBackend::Sqlite => "sqlite://yourwallet.db",
+            _ => "postgresql://localhost/yourwallet",
+        };
+
+        // 内存存储通常用于测试，会话也默认放在内存中
+        let session_backend = std::env::var("SESSION_BACKEND")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(match storage_backend {
+                StorageBackend::Memory => SessionBackend::Memory,
+                _ => SessionBackend::Redis,
+            });
+
+        let rate_provider = std::env::var("RATE_PROVIDER")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(RateProviderKind::None);
+        let default_rate_provider_source = match rate_provider {
+            RateProviderKind::File => "fixtures/exchange_rates.json",
+            _ => "https://api.frankfurter.app",
+        };
+
+        // 汇率缓存默认与会话存储放在一起
+        let rate_cache_backend = std::env::var("RATE_CACHE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(match session_backend {
+                SessionBackend::Redis => RateCacheBackend::Redis,
+                SessionBackend::Memory => RateCacheBackend::Memory,
+            });
+
         Self {
+            storage_backend,
             database_url: std::env::var("DATABASE_URL")
-                .unwrap_or_else(|_| "postgresql://localhost/yourwallet".to_string()),
+                .unwrap_or_else(|_| default_database_url.to_string()),
+            session_backend,
             redis_url: std::env::var("REDIS_URL")
                 .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+            rate_provider,
+            rate_provider_source: std::env::var("RATE_PROVIDER_SOURCE")
+                .unwrap_or_else(|_| default_rate_provider_source.to_string()),
+            rate_cache_backend,
             jwt_secret: std::env::var("JWT_SECRET")
                 .unwrap_or_else(|_| "your-secret-key".to_string()),
+            access_token_ttl: std::env::var("ACCESS_TOKEN_TTL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(15 * 60),
+            refresh_token_ttl: std::env::var("REFRESH_TOKEN_TTL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30 * 24 * 60 * 60),
             port: std::env::var("PORT")
                 .unwrap_or_else(|_| "3000".to_string())
                 .parse()
                 .unwrap_or(3000),
+            auto_migrate: std::env::var("AUTO_MIGRATE")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+            recurring_interval_secs: std::env::var("RECURRING_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(60 * 60),
+            reminder_interval_secs: std::env::var("REMINDER_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(60 * 60),
+            reminder_days: std::env::var("REMINDER_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
         }
     }
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub fn new(
+        config: AppConfig,
+        storage: Arc<dyn Storage>,
+        sessions: Arc<dyn SessionStore>,
+        rate_cache: Arc<dyn RateCache>,
+        rate_provider: Option<Arc<dyn RateProvider>>,
+    ) -> Self {
         Self {
-            config: AppConfig::default(),
+            storage,
+            sessions,
+            rate_cache,
+            rate_provider,
+            config,
+        }
+    }
+}
+
+// 游标内容，scope 标识列表和排序方式，游标不能跨列表或排序方式使用
+#[derive(Serialize, Deserialize)]
+struct CursorToken {
+    scope: String,
+    value: String,
+    id: Uuid,
+}
+
+// 分页方式：按页码分页时统计总数；按游标分页时从上一页最后一条记录之后继续，
+// 插入新记录不会导致重复或遗漏，也不需要统计总数
+struct PageRequest {
+    page: Option<u64>,
+    limit: u64,
+    start: PageStart,
+    scope: String,
+}
+
+impl PageRequest {
+    // time_sorted 表示游标的排序值为时间，否则为金额
+    fn parse(pagination: &PaginationQuery, scope: String, time_sorted: bool) -> Result<Self, ServiceError> {
+        let limit = pagination.limit.unwrap_or(20).clamp(1, 100);
+        let Some(token) = pagination.cursor.as_deref() else {
+            let page = pagination.page.unwrap_or(1).max(1);
+            return Ok(Self {
+                page: Some(page),
+                limit,
+                start: PageStart::Offset(((page - 1) * limit) as i64),
+                scope,
+            });
+        };
+
+        let mut v = Validator::new();
+        v.check(pagination.page.is_none(), "page", "not_allowed", "按游标分页时不能指定页码");
+        let cursor = decode_cursor(token, &scope, time_sorted);
+        v.check(cursor.is_some(), "cursor", "invalid", "无效的分页游标");
+        v.finish()?;
+        Ok(Self {
+            page: None,
+            limit,
+            start: PageStart::After(cursor.unwrap()),
+            scope,
+        })
+    }
+
+    // 多取一条用于判断是否还有下一页
+    fn fetch_limit(&self) -> i64 {
+        self.limit as i64 + 1
+    }
+
+    fn respond<T>(
+        self,
+        mut data: Vec<T>,
+        total: Option<u64>,
+        cursor_of: impl Fn(&T) -> Cursor,
+    ) -> PaginatedResponse<T> {
+        let has_next = data.len() as u64 > self.limit;
+        data.truncate(self.limit as usize);
+        let next_cursor = match data.last() {
+            Some(last) if has_next => Some(encode_cursor(&self.scope, cursor_of(last))),
+            _ => None,
+        };
+        PaginatedResponse {
+            data,
+            page: self.page,
+            limit: self.limit,
+            total,
+            has_next,
+            next_cursor,
+        }
+    }
+}
+
+fn encode_cursor(scope: &str, cursor: Cursor) -> String {
+    let value = match cursor.value {
+        SortValue::Time(time) => time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        SortValue::Amount(amount) => amount.to_string(),
+    };
+    let token = CursorToken {
+        scope: scope.to_string(),
+        value,
+        id: cursor.id,
+    };
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(&token).unwrap_or_default())
+}
+
+fn decode_cursor(token: &str, scope: &str, time_sorted: bool) -> Option<Cursor> {
+    let bytes = URL_SAFE_NO_PAD.decode(token).ok()?;
+    let token: CursorToken = serde_json::from_slice(&bytes).ok()?;
+    if token.scope != scope {
+        return None;
+    }
+    let value = if time_sorted {
+        SortValue::Time(DateTime::parse_from_rfc3339(&token.value).ok()?.with_timezone(&Utc))
+    } else {
+        SortValue::Amount(token.value.parse().ok()?)
+    };
+    Some(Cursor { value, id: token.id })
+}
+
+// 逗号分隔的 UUID 列表，忽略空项
+fn parse_id_list(v: &mut Validator, field: &str, value: Option<&str>) -> Vec<Uuid> {
+    let mut ids = Vec::new();
+    for item in value.unwrap_or_default().split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match item.parse() {
+            Ok(id) if !ids.contains(&id) => ids.push(id),
+            Ok(_) => {}
+            Err(_) => {
+                v.add(field, "invalid_format", format!("无效的 ID: {}", item));
+                break;
+            }
+        }
+    }
+    ids
+}
+
+// 校验交易列表的查询参数并转换为存储层筛选条件
+fn transaction_filter(query: &TransactionQuery) -> Result<TransactionFilter, ServiceError> {
+    let mut v = Validator::new();
+    let account_ids = parse_id_list(&mut v, "account_ids", query.account_ids.as_deref());
+    let category_ids = parse_id_list(&mut v, "category_ids", query.category_ids.as_deref());
+
+    if let (Some(start), Some(end)) = (query.start_date, query.end_date) {
+        v.check(start <= end, "end_date", "invalid_range", "结束日期不能早于开始日期");
+    }
+    if let (Some(min), Some(max)) = (query.min_amount, query.max_amount) {
+        v.check(min <= max, "max_amount", "invalid_range", "最大金额不能小于最小金额");
+    }
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in query.tags.as_deref().unwrap_or_default().split(',').map(str::trim) {
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
         }
     }
+    v.tags("tags", &tags);
+
+    let q = query.q.as_deref().unwrap_or_default().trim();
+    v.check(q.chars().count() <= 100, "q", "too_long", "搜索关键词不能超过 100 个字符");
+    let search_terms = q.split_whitespace().map(str::to_lowercase).collect();
+    v.finish()?;
+
+    Ok(TransactionFilter {
+        start_date: query.start_date,
+        end_date: query.end_date,
+        account_ids,
+        category_ids,
+        transaction_type: query.transaction_type,
+        min_amount: query.min_amount,
+        max_amount: query.max_amount,
+        tags,
+        match_all_tags: query.tag_match == Some(TagMatch::All),
+        search_terms,
+    })
 }
 
 // 用户服务
@@ -56,12 +325,181 @@ impl UserService {
     pub fn new(state: Arc<AppState>) -> Self {
         Self { state }
     }
-    
-    // TODO: 实现用户相关业务逻辑
-    // pub async fn create_user(&self, request: CreateUserRequest) -> Result<User, ServiceError>
-    // pub async fn get_user(&self, user_id: Uuid) -> Result<User, ServiceError>
-    // pub async fn update_user(&self, user_id: Uuid, request: UpdateUserRequest) -> Result<User, ServiceError>
-    // pub async fn delete_user(&self, user_id: Uuid) -> Result<(), ServiceError>
+
+    pub async fn create_user(&self, request: CreateUserRequest) -> Result<User, ServiceError> {
+        if self.state.storage.find_user_by_username(&request.username).await?.is_some() {
+            return Err(ServiceError::Validation(vec![FieldError::new(
+                "username",
+                "already_exists",
+                "用户名已被占用",
+            )]));
+        }
+
+        let password = request.password;
+        let password_hash = tokio::task::spawn_blocking(move || auth::hash_password(&password))
+            .await
+            .map_err(|e| ServiceError::Internal(e.to_string()))?
+            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+
+        let now = Utc::now();
+        let user = User {
+            id: Uuid::new_v4(),
+            username: request.username,
+            email: request.email,
+            display_name: request.display_name,
+            avatar_url: None,
+            base_currency: request
+                .base_currency
+                .map(|currency| currency.trim().to_ascii_uppercase())
+                .unwrap_or_else(|| DEFAULT_BASE_CURRENCY.to_string()),
+            password_hash,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.state.storage.insert_user(&user).await?;
+        Ok(user)
+    }
+
+    pub async fn get_user(&self, user_id: Uuid) -> Result<User, ServiceError> {
+        self.state
+            .storage
+            .find_user(user_id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("user {}", user_id)))
+    }
+
+    pub async fn update_user(&self, user_id: Uuid, request: UpdateUserRequest) -> Result<User, ServiceError> {
+        let mut user = self.get_user(user_id).await?;
+        if let Some(display_name) = request.display_name {
+            user.display_name = display_name.trim().to_string();
+        }
+        if let Some(avatar_url) = request.avatar_url {
+            user.avatar_url = Some(avatar_url.trim().to_string()).filter(|url| !url.is_empty());
+        }
+        if let Some(base_currency) = request.base_currency {
+            user.base_currency = base_currency.trim().to_ascii_uppercase();
+        }
+        user.updated_at = Utc::now();
+
+        self.state.storage.update_user(&user).await?;
+        Ok(user)
+    }
+}
+
+// 未指定时的用户本位币
+const DEFAULT_BASE_CURRENCY: &str = "CNY";
+
+// 认证服务
+pub struct AuthService {
+    state: Arc<AppState>,
+}
+
+impl AuthService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    pub async fn register(&self, request: CreateUserRequest) -> Result<AuthResponse, ServiceError> {
+        let user = UserService::new(self.state.clone()).create_user(request).await?;
+        self.start_session(user).await
+    }
+
+    pub async fn login(&self, request: LoginRequest) -> Result<AuthResponse, ServiceError> {
+        let user = self.state.storage.find_user_by_username(&request.username).await?;
+
+        // 用户不存在时同样执行一次校验，两种失败的响应时间一致
+        let password = request.password;
+        let password_hash = user
+            .as_ref()
+            .map_or_else(|| auth::DUMMY_PASSWORD_HASH.to_string(), |user| user.password_hash.clone());
+        let valid = tokio::task::spawn_blocking(move || auth::verify_password(&password, &password_hash))
+            .await
+            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        match user {
+            Some(user) if valid => self.start_session(user).await,
+            _ => Err(ServiceError::AuthenticationFailed),
+        }
+    }
+
+    // 刷新令牌只能使用一次，每次刷新都会轮换
+    pub async fn refresh(&self, request: RefreshTokenRequest) -> Result<AuthResponse, ServiceError> {
+        let claims = self.decode_refresh_token(&request.refresh_token)?;
+        let user = self
+            .state
+            .storage
+            .find_user(claims.sub)
+            .await?
+            .ok_or(ServiceError::AuthenticationFailed)?;
+
+        let tokens = self.issue_tokens(user.id, claims.sid)?;
+        let outcome = self
+            .state
+            .sessions
+            .rotate_refresh_token(claims.sub, claims.sid, claims.jti, tokens.refresh_jti, self.state.config.refresh_token_ttl)
+            .await?;
+        match outcome {
+            RotateOutcome::Rotated => Ok(auth_response(tokens, user)),
+            RotateOutcome::Reused => {
+                // 旧令牌被重放，可能已泄露，整个令牌族作废
+                tracing::warn!("refresh token reuse detected, session {} revoked", claims.sid);
+                Err(ServiceError::AuthenticationFailed)
+            }
+            RotateOutcome::Revoked => Err(ServiceError::AuthenticationFailed),
+        }
+    }
+
+    // 注销刷新令牌所属的会话，该会话的访问令牌同时失效
+    pub async fn logout(&self, request: RefreshTokenRequest) -> Result<(), ServiceError> {
+        let claims = self.decode_refresh_token(&request.refresh_token)?;
+        self.state.sessions.revoke_session(claims.sub, claims.sid).await?;
+        Ok(())
+    }
+
+    // 注销用户在所有设备上的会话
+    pub async fn logout_all(&self, user_id: Uuid) -> Result<(), ServiceError> {
+        let revoked = self.state.sessions.revoke_all_sessions(user_id).await?;
+        tracing::info!("user {} logged out of {} sessions", user_id, revoked);
+        Ok(())
+    }
+
+    // 新登录开启一个会话
+    async fn start_session(&self, user: User) -> Result<AuthResponse, ServiceError> {
+        let session_id = Uuid::new_v4();
+        let tokens = self.issue_tokens(user.id, session_id)?;
+        self.state
+            .sessions
+            .create_session(user.id, session_id, tokens.refresh_jti, self.state.config.refresh_token_ttl)
+            .await?;
+        Ok(auth_response(tokens, user))
+    }
+
+    fn issue_tokens(&self, user_id: Uuid, session_id: Uuid) -> Result<auth::TokenPair, ServiceError> {
+        let config = &self.state.config;
+        auth::issue_token_pair(
+            user_id,
+            session_id,
+            config.access_token_ttl,
+            config.refresh_token_ttl,
+            &config.jwt_secret,
+        )
+        .map_err(|e| ServiceError::Internal(e.to_string()))
+    }
+
+    fn decode_refresh_token(&self, token: &str) -> Result<auth::Claims, ServiceError> {
+        auth::decode_token(token, auth::TokenType::Refresh, &self.state.config.jwt_secret)
+            .ok_or(ServiceError::AuthenticationFailed)
+    }
+}
+
+fn auth_response(tokens: auth::TokenPair, user: User) -> AuthResponse {
+    AuthResponse {
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
+        token_type: "Bearer".to_string(),
+        expires_in: tokens.expires_in,
+        user,
+    }
 }
 
 // 账户服务
@@ -73,13 +511,370 @@ impl AccountService {
     pub fn new(state: Arc<AppState>) -> Self {
         Self { state }
     }
-    
-    // TODO: 实现账户相关业务逻辑
-    // pub async fn create_account(&self, user_id: Uuid, request: CreateAccountRequest) -> Result<Account, ServiceError>
-    // pub async fn get_accounts(&self, user_id: Uuid) -> Result<Vec<Account>, ServiceError>
-    // pub async fn get_account(&self, account_id: Uuid) -> Result<Account, ServiceError>
-    // pub async fn update_account(&self, account_id: Uuid, request: UpdateAccountRequest) -> Result<Account, ServiceError>
-    // pub async fn delete_account(&self, account_id: Uuid) -> Result<(), ServiceError>
+
+    pub async fn create_account(
+        &self,
+        user_id: Uuid,
+        request: CreateAccountRequest,
+    ) -> Result<Account, ServiceError> {
+        let now = Utc::now();
+        // 贷款默认欠款为借款本金，借出默认应收为借款本金
+        let opening_balance = request.initial_balance.unwrap_or_else(|| match (&request.loan, request.account_type) {
+            (Some(loan), AccountType::Loan) => -loan.principal,
+            (Some(loan), AccountType::Receivable) => loan.principal,
+            _ => Decimal::ZERO,
+        });
+        let account = Account {
+            id: Uuid::new_v4(),
+            user_id,
+            name: request.name.trim().to_string(),
+            account_type: request.account_type,
+            currency: request.currency.trim().to_ascii_uppercase(),
+            balance: opening_balance,
+            opening_balance,
+            credit_limit: request.credit_limit,
+            statement_closing_day: request.statement_closing_day,
+            payment_due_day: request.payment_due_day,
+            loan: request.loan,
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        };
+        check_account_fields(&account)?;
+
+        self.state.storage.insert_account(&account).await?;
+        Ok(account)
+    }
+
+    pub async fn get_accounts(
+        &self,
+        user_id: Uuid,
+        pagination: PaginationQuery,
+    ) -> Result<PaginatedResponse<Account>, ServiceError> {
+        let request = PageRequest::parse(&pagination, "accounts".to_string(), true)?;
+        let accounts = self
+            .state
+            .storage
+            .list_accounts(user_id, request.fetch_limit(), request.start)
+            .await?;
+        let total = match request.page {
+            Some(_) => Some(self.state.storage.count_accounts(user_id).await? as u64),
+            None => None,
+        };
+
+        Ok(request.respond(accounts, total, |account| Cursor {
+            value: SortValue::Time(account.created_at),
+            id: account.id,
+        }))
+    }
+
+    pub async fn get_account(&self, user_id: Uuid, account_id: Uuid) -> Result<Account, ServiceError> {
+        match self.state.storage.find_account(account_id).await? {
+            Some(account) if account.user_id == user_id => Ok(account),
+            Some(_) => Err(ServiceError::AuthorizationFailed),
+            None => Err(ServiceError::NotFound(format!("account {}", account_id))),
+        }
+    }
+
+    pub async fn update_account(
+        &self,
+        user_id: Uuid,
+        account_id: Uuid,
+        request: UpdateAccountRequest,
+    ) -> Result<Account, ServiceError> {
+        let mut account = self.get_account(user_id, account_id).await?;
+
+        if let Some(name) = request.name {
+            account.name = name.trim().to_string();
+        }
+        if let Some(account_type) = request.account_type {
+            account.account_type = account_type;
+        }
+        if let Some(is_active) = request.is_active {
+            account.is_active = is_active;
+        }
+        if let Some(credit_limit) = request.credit_limit {
+            account.credit_limit = credit_limit;
+        }
+        if let Some(statement_closing_day) = request.statement_closing_day {
+            account.statement_closing_day = statement_closing_day;
+        }
+        if let Some(payment_due_day) = request.payment_due_day {
+            account.payment_due_day = payment_due_day;
+        }
+        if let Some(loan) = request.loan {
+            account.loan = loan;
+        }
+        check_account_fields(&account)?;
+        account.updated_at = Utc::now();
+
+        self.state.storage.update_account(&account).await?;
+        Ok(account)
+    }
+
+    // 请求体中引用的账户，不存在时作为该字段的校验错误返回
+    pub async fn get_referenced_account(
+        &self,
+        user_id: Uuid,
+        account_id: Uuid,
+        field: &str,
+    ) -> Result<Account, ServiceError> {
+        match self.get_account(user_id, account_id).await {
+            Err(ServiceError::NotFound(_)) => Err(ServiceError::Validation(vec![FieldError::new(
+                field,
+                "not_found",
+                "账户不存在",
+            )])),
+            result => result,
+        }
+    }
+
+    // 根据期初余额和交易流水重建余额
+    pub async fn recalculate_balance(&self, user_id: Uuid, account_id: Uuid) -> Result<Account, ServiceError> {
+        self.get_account(user_id, account_id).await?;
+        self.state
+            .storage
+            .recalculate_balance(account_id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("account {}", account_id)))?;
+        self.get_account(user_id, account_id).await
+    }
+
+    pub async fn delete_account(&self, user_id: Uuid, account_id: Uuid) -> Result<(), ServiceError> {
+        self.get_account(user_id, account_id).await?;
+        self.state.storage.delete_account(account_id).await?;
+        Ok(())
+    }
+
+    pub async fn get_statement(
+        &self,
+        user_id: Uuid,
+        account_id: Uuid,
+        query: StatementQuery,
+    ) -> Result<CreditCardStatement, ServiceError> {
+        let account = self.get_account(user_id, account_id).await?;
+        let mut v = Validator::new();
+        if account.account_type != AccountType::CreditCard {
+            v.add("account_type", "not_credit_card", "只有信用卡有账单");
+        } else {
+            v.check(
+                account.statement_closing_day.is_some(),
+                "statement_closing_day",
+                "required",
+                "信用卡未设置账单日",
+            );
+        }
+        v.finish()?;
+
+        let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+        self.statement(&account, account.statement_closing_day.unwrap_or_default(), date).await
+    }
+
+    // date 当天或之前最近一个账单日的账单，金额截至 date 当天
+    async fn statement(
+        &self,
+        account: &Account,
+        closing_day: u32,
+        date: NaiveDate,
+    ) -> Result<CreditCardStatement, ServiceError> {
+        let closing_date = match day_in_month(date, closing_day) {
+            closing_date if closing_date <= date => closing_date,
+            _ => day_in_month(date - Months::new(1), closing_day),
+        };
+        let filter = TransactionFilter {
+            account_ids: vec![account.id],
+            end_date: Some(end_of_day(date)),
+            ..Default::default()
+        };
+        let transactions = self
+            .state
+            .storage
+            .list_transactions(account.user_id, &filter, TransactionSort::default(), i64::MAX, PageStart::Offset(0))
+            .await?;
+
+        // 在应用层计算账单日和 date 当天的余额，以及账单日之后转入的还款
+        let closed_at = end_of_day(closing_date);
+        let mut balance_at_closing = account.opening_balance;
+        let mut balance = account.opening_balance;
+        let mut paid_amount = Decimal::ZERO;
+        for transaction in &transactions {
+            balance += transaction.balance_effect();
+            if transaction.transaction_date <= closed_at {
+                balance_at_closing += transaction.balance_effect();
+            } else if transaction.transfer_direction == Some(TransferDirection::In) {
+                paid_amount += transaction.amount;
+            }
+        }
+
+        // 分期消费在账单日之后才入账的本金不计入本期账单
+        let unbilled_installments = self.unbilled_installments(account, closing_date, closing_date).await?;
+        let statement_balance = (-balance_at_closing - unbilled_installments).max(Decimal::ZERO);
+        let remaining_balance = (statement_balance - paid_amount).max(Decimal::ZERO);
+        let minimum_payment = ((statement_balance * MINIMUM_PAYMENT_RATE).round_dp(currency_scale(&account.currency))
+            - paid_amount)
+            .clamp(Decimal::ZERO, remaining_balance);
+        let current_balance = (-balance).max(Decimal::ZERO);
+        let due_date = account.payment_due_day.map(|day| next_day_of_month(closing_date, day));
+        let status = if remaining_balance.is_zero() {
+            StatementStatus::Paid
+        } else if due_date.is_some_and(|due_date| date > due_date) {
+            StatementStatus::Overdue
+        } else {
+            StatementStatus::Unpaid
+        };
+
+        Ok(CreditCardStatement {
+            account_id: account.id,
+            currency: account.currency.clone(),
+            period_start: day_in_month(closing_date - Months::new(1), closing_day) + Duration::days(1),
+            closing_date,
+            due_date,
+            statement_balance: statement_balance.normalize(),
+            paid_amount: paid_amount.normalize(),
+            remaining_balance: remaining_balance.normalize(),
+            minimum_payment: minimum_payment.normalize(),
+            unbilled_balance: (current_balance - remaining_balance).max(Decimal::ZERO).normalize(),
+            current_balance: current_balance.normalize(),
+            credit_limit: account.credit_limit,
+            available_credit: account.credit_limit.map(|limit| (limit - current_balance).normalize()),
+            status,
+        })
+    }
+
+    // 信用卡在 start 到 end 之间的下一个还款日及应还金额
+    // 设置了账单日时，已出的账单未还清且未过还款日则为本期账单未还的部分，
+    // 否则为下一期账单的还款日，金额按目前的全部欠款估算；未设置账单日时为下一个还款日和全部欠款
+    async fn upcoming_card_payment(
+        &self,
+        account: &Account,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Option<UpcomingPayment>, ServiceError> {
+        let Some(due_day) = account.payment_due_day.filter(|_| account.is_active) else {
+            return Ok(None);
+        };
+        let (due_date, amount) = match account.statement_closing_day {
+            Some(closing_day) => {
+                let statement = self.statement(account, closing_day, start).await?;
+                match statement.due_date {
+                    Some(due_date) if due_date >= start && !statement.remaining_balance.is_zero() => {
+                        (due_date, statement.remaining_balance)
+                    }
+                    _ => {
+                        let next_closing = next_day_of_month(statement.closing_date, closing_day);
+                        let unbilled_installments = self.unbilled_installments(account, start, next_closing).await?;
+                        (
+                            next_day_of_month(next_closing, due_day),
+                            (statement.current_balance - unbilled_installments).max(Decimal::ZERO),
+                        )
+                    }
+                }
+            }
+            None => (
+                next_day_of_month(start - Duration::days(1), due_day),
+                (-account.balance).max(Decimal::ZERO),
+            ),
+        };
+        if due_date > end || amount.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(UpcomingPayment {
+            kind: UpcomingKind::CreditCardDue,
+            source_id: account.id,
+            account_id: account.id,
+            due_date,
+            description: account.name.clone(),
+            transaction_type: TransactionType::Transfer,
+            amount: amount.normalize(),
+            currency: account.currency.clone(),
+        }))
+    }
+
+    // 在 purchased_by 当天或之前消费、在 billed_by 之后才入账的分期本金
+    async fn unbilled_installments(
+        &self,
+        account: &Account,
+        purchased_by: NaiveDate,
+        billed_by: NaiveDate,
+    ) -> Result<Decimal, ServiceError> {
+        let scale = currency_scale(&account.currency);
+        let mut total = Decimal::ZERO;
+        for plan in self.state.storage.list_account_installments(account.id).await? {
+            let unbilled: Decimal = installment_schedule(&plan, scale)
+                .iter()
+                .filter(|period| period.date > billed_by)
+                .map(|period| period.principal)
+                .sum();
+            if unbilled.is_zero() {
+                continue;
+            }
+            let purchased = self
+                .state
+                .storage
+                .find_transaction(plan.transaction_id)
+                .await?
+                .is_some_and(|transaction| transaction.transaction_date <= end_of_day(purchased_by));
+            if purchased {
+                total += unbilled;
+            }
+        }
+        Ok(total)
+    }
+}
+
+// 最低还款为账单金额的 10%
+const MINIMUM_PAYMENT_RATE: Decimal = Decimal::from_parts(10, 0, 0, false, 2);
+
+// 额度、账单日和还款日只适用于信用卡，借款条件只适用于贷款和借出账户
+fn check_account_fields(account: &Account) -> Result<(), ServiceError> {
+    let mut v = Validator::new();
+    let credit_card = account.account_type == AccountType::CreditCard;
+    v.check(
+        credit_card || account.credit_limit.is_none(),
+        "credit_limit",
+        "not_allowed",
+        "只有信用卡可以设置信用额度",
+    );
+    if let Some(credit_limit) = account.credit_limit {
+        v.amount_scale("credit_limit", credit_limit, &account.currency);
+    }
+    v.check(
+        credit_card || account.statement_closing_day.is_none(),
+        "statement_closing_day",
+        "not_allowed",
+        "只有信用卡可以设置账单日",
+    );
+    v.check(
+        credit_card || account.payment_due_day.is_none(),
+        "payment_due_day",
+        "not_allowed",
+        "只有信用卡可以设置还款日",
+    );
+    v.check(
+        matches!(account.account_type, AccountType::Loan | AccountType::Receivable) || account.loan.is_none(),
+        "loan",
+        "not_allowed",
+        "只有贷款和借出账户可以设置借款条件",
+    );
+    if let Some(loan) = &account.loan {
+        v.amount_scale("loan.principal", loan.principal, &account.currency);
+    }
+    v.finish()
+}
+
+// date 所在月份的第 day 天，超过当月天数时为月末
+fn day_in_month(date: NaiveDate, day: u32) -> NaiveDate {
+    let first = date.with_day(1).unwrap();
+    let last = first + Months::new(1) - Duration::days(1);
+    first.with_day(day.min(last.day())).unwrap()
+}
+
+// date 之后第一个每月的第 day 天
+fn next_day_of_month(date: NaiveDate, day: u32) -> NaiveDate {
+    match day_in_month(date, day) {
+        next if next > date => next,
+        _ => day_in_month(date + Months::new(1), day),
+    }
 }
 
 // 交易服务
@@ -91,49 +886,4842 @@ impl TransactionService {
     pub fn new(state: Arc<AppState>) -> Self {
         Self { state }
     }
-    
-    // TODO: 实现交易相关业务逻辑
-    // pub async fn create_transaction(&self, user_id: Uuid, request: CreateTransactionRequest) -> Result<Transaction, ServiceError>
-    // pub async fn get_transactions(&self, user_id: Uuid, pagination: PaginationQuery) -> Result<PaginatedResponse<Transaction>, ServiceError>
-    // pub async fn get_transaction(&self, transaction_id: Uuid) -> Result<Transaction, ServiceError>
-    // pub async fn update_transaction(&self, transaction_id: Uuid, request: UpdateTransactionRequest) -> Result<Transaction, ServiceError>
-    // pub async fn delete_transaction(&self, transaction_id: Uuid) -> Result<(), ServiceError>
+
+    pub async fn create_transaction(
+        &self,
+        user_id: Uuid,
+        request: CreateTransactionRequest,
+    ) -> Result<Transaction, ServiceError> {
+        // 交易货币跟随账户
+        let account = AccountService::new(self.state.clone())
+            .get_referenced_account(user_id, request.account_id, "account_id")
+            .await?;
+
+        let mut v = Validator::new();
+        v.check(account.is_active, "account_id", "inactive", "账户已停用");
+        v.amount_scale("amount", request.amount, &account.currency);
+        if let Some(category_id) = request.category_id {
+            self.check_category(user_id, &mut v, category_id, request.transaction_type).await?;
+        }
+        v.finish()?;
+
+        let now = Utc::now();
+        let transaction = Transaction {
+            id: Uuid::new_v4(),
+            user_id,
+            account_id: account.id,
+            category_id: request.category_id,
+            transaction_type: request.transaction_type,
+            amount: request.amount,
+            currency: account.currency,
+            description: request.description.trim().to_string(),
+            notes: request.notes,
+            tags: trim_tags(request.tags.unwrap_or_default()),
+            transaction_date: request.transaction_date.unwrap_or(now),
+            transfer_id: None,
+            transfer_direction: None,
+            recurring_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.state.storage.insert_transaction(&transaction).await?;
+        Ok(transaction)
+    }
+
+    pub async fn get_transactions(
+        &self,
+        user_id: Uuid,
+        query: TransactionQuery,
+    ) -> Result<PaginatedResponse<Transaction>, ServiceError> {
+        let filter = transaction_filter(&query)?;
+        let sort = TransactionSort {
+            field: query.sort_by.unwrap_or_default(),
+            order: query.sort_order.unwrap_or_default(),
+        };
+        let request = PageRequest::parse(
+            &PaginationQuery {
+                page: query.page,
+                limit: query.limit,
+                cursor: query.cursor,
+            },
+            format!("transactions:{:?}:{:?}", sort.field, sort.order),
+            sort.field != TransactionSortField::Amount,
+        )?;
+        let transactions = self
+            .state
+            .storage
+            .list_transactions(user_id, &filter, sort, request.fetch_limit(), request.start)
+            .await?;
+        let total = match request.page {
+            Some(_) => Some(self.state.storage.count_transactions(user_id, &filter).await? as u64),
+            None => None,
+        };
+
+        Ok(request.respond(transactions, total, |transaction| Cursor {
+            value: sort.value_of(transaction),
+            id: transaction.id,
+        }))
+    }
+
+    pub async fn get_transaction(
+        &self,
+        user_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Transaction, ServiceError> {
+        match self.state.storage.find_transaction(transaction_id).await? {
+            Some(transaction) if transaction.user_id == user_id => Ok(transaction),
+            Some(_) => Err(ServiceError::AuthorizationFailed),
+            None => Err(ServiceError::NotFound(format!("transaction {}", transaction_id))),
+        }
+    }
+
+    pub async fn update_transaction(
+        &self,
+        user_id: Uuid,
+        transaction_id: Uuid,
+        request: UpdateTransactionRequest,
+    ) -> Result<Transaction, ServiceError> {
+        let mut transaction = self.get_transaction(user_id, transaction_id).await?;
+        if let Some(transfer_id) = transaction.transfer_id {
+            return self.update_transfer_leg(user_id, transaction, transfer_id, request).await;
+        }
+        let mut v = Validator::new();
+
+        // 分期的本金即原交易金额，分期后不能再修改金额和账户
+        if request.amount.is_some_and(|amount| amount != transaction.amount)
+            || request.account_id.is_some_and(|account_id| account_id != transaction.account_id)
+        {
+            let plan = self.state.storage.find_transaction_installment(transaction.id).await?;
+            v.check(plan.is_none(), "amount", "has_installment_plan", "交易已分期，不能修改金额和账户");
+        }
+        if let Some(account_id) = request.account_id {
+            let account = AccountService::new(self.state.clone())
+                .get_referenced_account(user_id, account_id, "account_id")
+                .await?;
+            v.check(account.is_active, "account_id", "inactive", "账户已停用");
+            transaction.account_id = account.id;
+            transaction.currency = account.currency;
+        }
+        // 保留原分类时不重新校验，分类归档后已有交易仍可修改其他字段
+        if let Some(category_id) = request.category_id.filter(|id| Some(*id) != transaction.category_id) {
+            self.check_category(user_id, &mut v, category_id, transaction.transaction_type).await?;
+            transaction.category_id = Some(category_id);
+        }
+        if let Some(amount) = request.amount {
+            transaction.amount = amount;
+        }
+        // 换账户后原金额也要符合新货币的精度
+        if request.amount.is_some() || request.account_id.is_some() {
+            v.amount_scale("amount", transaction.amount, &transaction.currency);
+        }
+        v.finish()?;
+
+        if let Some(description) = request.description {
+            transaction.description = description.trim().to_string();
+        }
+        if let Some(notes) = request.notes {
+            transaction.notes = Some(notes);
+        }
+        if let Some(tags) = request.tags {
+            transaction.tags = trim_tags(tags);
+        }
+        if let Some(transaction_date) = request.transaction_date {
+            transaction.transaction_date = transaction_date;
+        }
+        transaction.updated_at = Utc::now();
+
+        self.state.storage.update_transaction(&transaction).await?;
+        Ok(transaction)
+    }
+
+    pub async fn delete_transaction(
+        &self,
+        user_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        let transaction = self.get_transaction(user_id, transaction_id).await?;
+        // 删除转账的任意一条交易会删除整笔转账
+        match transaction.transfer_id {
+            Some(transfer_id) => {
+                TransferService::new(self.state.clone())
+                    .delete_transfer(user_id, transfer_id)
+                    .await
+            }
+            None => {
+                // 分期结束或提前还清后才能删除，分期随交易一起删除
+                if let Some(plan) = self.state.storage.find_transaction_installment(transaction_id).await? {
+                    let today = Utc::now().date_naive();
+                    let finished = plan.paid_off_date.is_some()
+                        || plan.first_period_date + Months::new(plan.periods - 1) <= today;
+                    if !finished {
+                        return Err(ServiceError::Conflict(format!(
+                            "transaction {} has an installment plan in progress",
+                            transaction_id
+                        )));
+                    }
+                }
+                self.state.storage.delete_transaction(transaction_id).await?;
+                Ok(())
+            }
+        }
+    }
+
+    // 修改转账中的一条交易时同步修改整笔转账
+    async fn update_transfer_leg(
+        &self,
+        user_id: Uuid,
+        transaction: Transaction,
+        transfer_id: Uuid,
+        request: UpdateTransactionRequest,
+    ) -> Result<Transaction, ServiceError> {
+        if request.category_id.is_some() {
+            return Err(ServiceError::Validation(vec![FieldError::new(
+                "category_id",
+                "not_allowed",
+                "转账不能设置分类",
+            )]));
+        }
+        // 转入交易对应转入账户和金额，手续费交易对应手续费，其余对应转出账户和金额
+        let mut transfer_request = UpdateTransferRequest {
+            from_account_id: None,
+            to_account_id: None,
+            amount: None,
+            to_amount: None,
+            exchange_rate: None,
+            fee: None,
+            description: request.description,
+            notes: request.notes,
+            tags: request.tags,
+            transfer_date: request.transaction_date,
+        };
+        match (transaction.transaction_type, transaction.transfer_direction) {
+            (TransactionType::Transfer, Some(TransferDirection::In)) => {
+                transfer_request.to_account_id = request.account_id;
+                transfer_request.to_amount = request.amount;
+            }
+            (TransactionType::Transfer, _) => {
+                transfer_request.from_account_id = request.account_id;
+                transfer_request.amount = request.amount;
+            }
+            _ => {
+                transfer_request.from_account_id = request.account_id;
+                transfer_request.fee = request.amount;
+            }
+        }
+        TransferService::new(self.state.clone())
+            .update_transfer(user_id, transfer_id, transfer_request)
+            .await?;
+        self.get_transaction(user_id, transaction.id).await
+    }
+
+    // 分类必须是系统分类或用户自己的分类，未归档且与交易类型一致
+    async fn check_category(
+        &self,
+        user_id: Uuid,
+        v: &mut Validator,
+        category_id: Uuid,
+        transaction_type: TransactionType,
+    ) -> Result<(), ServiceError> {
+        match self.state.storage.find_category(category_id).await? {
+            Some(category) if category.is_system || category.user_id == Some(user_id) => {
+                if category.is_archived {
+                    v.add("category_id", "archived", "分类已归档");
+                } else if category.transaction_type != transaction_type {
+                    v.add("category_id", "type_mismatch", "分类与交易类型不一致");
+                }
+            }
+            _ => v.add("category_id", "not_found", "分类不存在"),
+        }
+        Ok(())
+    }
 }
 
-// 统计服务
-pub struct StatisticsService {
+// 去除标签首尾空白和重复项，保留原有顺序
+fn trim_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result
+}
+
+// 转账服务
+pub struct TransferService {
     state: Arc<AppState>,
 }
 
-impl StatisticsService {
+// 组成一笔转账的各条交易
+struct TransferLegs {
+    debit: Transaction,
+    credit: Transaction,
+    fee: Option<Transaction>,
+}
+
+impl TransferService {
     pub fn new(state: Arc<AppState>) -> Self {
         Self { state }
     }
-    
-    // TODO: 实现统计相关业务逻辑
-    // pub async fn get_financial_summary(&self, user_id: Uuid) -> Result<FinancialSummary, ServiceError>
-    // pub async fn get_monthly_report(&self, user_id: Uuid, year: i32, month: u32) -> Result<MonthlyReport, ServiceError>
-    // pub async fn get_category_analysis(&self, user_id: Uuid, start_date: DateTime<Utc>, end_date: DateTime<Utc>) -> Result<CategoryAnalysis, ServiceError>
+
+    pub async fn create_transfer(
+        &self,
+        user_id: Uuid,
+        request: CreateTransferRequest,
+    ) -> Result<Transfer, ServiceError> {
+        let accounts = AccountService::new(self.state.clone());
+        let from = accounts
+            .get_referenced_account(user_id, request.from_account_id, "from_account_id")
+            .await?;
+        let to = accounts
+            .get_referenced_account(user_id, request.to_account_id, "to_account_id")
+            .await?;
+
+        let mut v = Validator::new();
+        v.check(from.is_active, "from_account_id", "inactive", "账户已停用");
+        v.check(to.is_active, "to_account_id", "inactive", "账户已停用");
+        v.amount_scale("amount", request.amount, &from.currency);
+        let to_amount = if from.currency == to.currency {
+            v.check(
+                request.to_amount.is_none_or(|to_amount| to_amount == request.amount),
+                "to_amount",
+                "amount_mismatch",
+                "同币种转账的转入金额必须等于转出金额",
+            );
+            request.amount
+        } else {
+            resolve_to_amount(&mut v, request.amount, request.to_amount, request.exchange_rate, None, &to.currency)
+        };
+        let fee = request.fee.filter(|fee| !fee.is_zero());
+        if let Some(fee) = fee {
+            v.amount_scale("fee", fee, &from.currency);
+        }
+        v.finish()?;
+
+        let now = Utc::now();
+        let transfer_id = Uuid::new_v4();
+        let leg = |account: &Account, transaction_type, amount, direction| Transaction {
+            id: Uuid::new_v4(),
+            user_id,
+            account_id: account.id,
+            category_id: None,
+            transaction_type,
+            amount,
+            currency: account.currency.clone(),
+            description: request.description.trim().to_string(),
+            notes: request.notes.clone(),
+            tags: trim_tags(request.tags.clone().unwrap_or_default()),
+            transaction_date: request.transfer_date.unwrap_or(now),
+            transfer_id: Some(transfer_id),
+            transfer_direction: Some(direction),
+            recurring_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let legs = TransferLegs {
+            debit: leg(&from, TransactionType::Transfer, request.amount, TransferDirection::Out),
+            credit: leg(&to, TransactionType::Transfer, to_amount, TransferDirection::In),
+            // 手续费记为转出账户的一笔支出
+            fee: fee.map(|fee| leg(&from, TransactionType::Expense, fee, TransferDirection::Out)),
+        };
+
+        self.state.storage.insert_transfer(&legs.to_vec()).await?;
+        Ok(legs.into_transfer(transfer_id))
+    }
+
+    pub async fn get_transfer(&self, user_id: Uuid, transfer_id: Uuid) -> Result<Transfer, ServiceError> {
+        let legs = self.get_legs(user_id, transfer_id).await?;
+        Ok(legs.into_transfer(transfer_id))
+    }
+
+    pub async fn update_transfer(
+        &self,
+        user_id: Uuid,
+        transfer_id: Uuid,
+        request: UpdateTransferRequest,
+    ) -> Result<Transfer, ServiceError> {
+        let TransferLegs { mut debit, mut credit, mut fee } = self.get_legs(user_id, transfer_id).await?;
+        let accounts = AccountService::new(self.state.clone());
+        let mut v = Validator::new();
+
+        let from_currency_changed = match request.from_account_id {
+            Some(account_id) => {
+                let account = accounts
+                    .get_referenced_account(user_id, account_id, "from_account_id")
+                    .await?;
+                v.check(account.is_active, "from_account_id", "inactive", "账户已停用");
+                debit.account_id = account.id;
+                let changed = account.currency != debit.currency;
+                debit.currency = account.currency;
+                changed
+            }
+            None => false,
+        };
+        let to_currency_changed = match request.to_account_id {
+            Some(account_id) => {
+                let account = accounts
+                    .get_referenced_account(user_id, account_id, "to_account_id")
+                    .await?;
+                v.check(account.is_active, "to_account_id", "inactive", "账户已停用");
+                credit.account_id = account.id;
+                let changed = account.currency != credit.currency;
+                credit.currency = account.currency;
+                changed
+            }
+            None => false,
+        };
+        v.check(
+            debit.account_id != credit.account_id,
+            "to_account_id",
+            "same_account",
+            "转出和转入账户不能相同",
+        );
+
+        if debit.currency == credit.currency {
+            // 同币种时转入金额始终按转出金额重新计算，包括由不同币种改为同币种的情况
+            let amount = request.amount.unwrap_or(debit.amount);
+            v.check(
+                request.to_amount.is_none_or(|to_amount| to_amount == amount),
+                "to_amount",
+                "amount_mismatch",
+                "同币种转账的转入金额必须等于转出金额",
+            );
+            debit.amount = amount;
+            credit.amount = amount;
+        } else {
+            if let Some(amount) = request.amount {
+                debit.amount = amount;
+            }
+            // 未提供转入金额和汇率时保留原转入金额，此时汇率随转出金额变化；
+            // 任一方货币变化后原转入金额不再适用，必须重新提供
+            let current = (!from_currency_changed && !to_currency_changed).then_some(credit.amount);
+            credit.amount = resolve_to_amount(
+                &mut v,
+                debit.amount,
+                request.to_amount,
+                request.exchange_rate,
+                current,
+                &credit.currency,
+            );
+        }
+        v.amount_scale("amount", debit.amount, &debit.currency);
+
+        let now = Utc::now();
+        match request.fee {
+            Some(amount) if amount.is_zero() => fee = None,
+            Some(amount) => {
+                let leg = fee.get_or_insert_with(|| Transaction {
+                    id: Uuid::new_v4(),
+                    transaction_type: TransactionType::Expense,
+                    created_at: now,
+                    ..debit.clone()
+                });
+                leg.amount = amount;
+            }
+            None => {}
+        }
+        if let Some(leg) = &mut fee {
+            // 手续费跟随转出账户
+            leg.account_id = debit.account_id;
+            leg.currency = debit.currency.clone();
+            v.amount_scale("fee", leg.amount, &leg.currency);
+        }
+        v.finish()?;
+
+        let mut legs = TransferLegs { debit, credit, fee };
+        for leg in legs.iter_mut() {
+            if let Some(description) = &request.description {
+                leg.description = description.trim().to_string();
+            }
+            if let Some(notes) = &request.notes {
+                leg.notes = Some(notes.clone());
+            }
+            if let Some(tags) = &request.tags {
+                leg.tags = trim_tags(tags.clone());
+            }
+            if let Some(transfer_date) = request.transfer_date {
+                leg.transaction_date = transfer_date;
+            }
+            leg.updated_at = now;
+        }
+
+        self.state.storage.update_transfer(transfer_id, &legs.to_vec()).await?;
+        Ok(legs.into_transfer(transfer_id))
+    }
+
+    pub async fn delete_transfer(&self, user_id: Uuid, transfer_id: Uuid) -> Result<(), ServiceError> {
+        self.get_legs(user_id, transfer_id).await?;
+        self.state.storage.delete_transfer(transfer_id).await?;
+        Ok(())
+    }
+
+    async fn get_legs(&self, user_id: Uuid, transfer_id: Uuid) -> Result<TransferLegs, ServiceError> {
+        let legs = self.state.storage.list_transfer_legs(transfer_id).await?;
+        if legs.is_empty() {
+            return Err(ServiceError::NotFound(format!("transfer {}", transfer_id)));
+        }
+        if legs.iter().any(|leg| leg.user_id != user_id) {
+            return Err(ServiceError::AuthorizationFailed);
+        }
+
+        let (mut debit, mut credit, mut fee) = (None, None, None);
+        for leg in legs {
+            match (leg.transaction_type, leg.transfer_direction) {
+                (TransactionType::Transfer, Some(TransferDirection::Out)) => debit = Some(leg),
+                (TransactionType::Transfer, Some(TransferDirection::In)) => credit = Some(leg),
+                (TransactionType::Expense, Some(TransferDirection::Out)) => fee = Some(leg),
+                _ => {}
+            }
+        }
+        match (debit, credit) {
+            (Some(debit), Some(credit)) => Ok(TransferLegs { debit, credit, fee }),
+            _ => Err(ServiceError::Internal(format!("transfer {} is missing a leg", transfer_id))),
+        }
+    }
 }
 
-// 服务错误类型
-#[derive(Debug, thiserror::Error)]
-pub enum ServiceError {
-    #[error("Database error: {0}")]
-    Database(String),
-    
-    #[error("Not found: {0}")]
-    NotFound(String),
-    
-    #[error("Invalid input: {0}")]
-    InvalidInput(String),
-    
-    #[error("Authentication failed")]
-    AuthenticationFailed,
-    
-    #[error("Authorization failed")]
-    AuthorizationFailed,
-    
-    #[error("Internal server error: {0}")]
-    Internal(String),
-}
\ No newline at end of file
+impl TransferLegs {
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Transaction> {
+        [&mut self.debit, &mut self.credit].into_iter().chain(self.fee.as_mut())
+    }
+
+    fn to_vec(&self) -> Vec<Transaction> {
+        let mut legs = vec![self.debit.clone(), self.credit.clone()];
+        legs.extend(self.fee.clone());
+        legs
+    }
+
+    fn into_transfer(self, transfer_id: Uuid) -> Transfer {
+        let TransferLegs { debit, credit, fee } = self;
+        Transfer {
+            id: transfer_id,
+            from_account_id: debit.account_id,
+            to_account_id: credit.account_id,
+            exchange_rate: (credit.amount / debit.amount).round_dp(EXCHANGE_RATE_SCALE).normalize(),
+            amount: debit.amount,
+            currency: debit.currency,
+            to_amount: credit.amount,
+            to_currency: credit.currency,
+            fee: fee.as_ref().map(|leg| leg.amount),
+            description: debit.description,
+            notes: debit.notes,
+            tags: debit.tags,
+            transfer_date: debit.transaction_date,
+            debit_transaction_id: debit.id,
+            credit_transaction_id: credit.id,
+            fee_transaction_id: fee.as_ref().map(|leg| leg.id),
+            created_at: debit.created_at,
+            updated_at: debit.updated_at.max(credit.updated_at),
+        }
+    }
+}
+
+// 汇率保留的小数位数
+const EXCHANGE_RATE_SCALE: u32 = 8;
+
+// 不同币种转账的转入金额：直接给出，或按汇率换算并舍入到转入货币精度；都未给出时沿用 current
+fn resolve_to_amount(
+    v: &mut Validator,
+    amount: Decimal,
+    to_amount: Option<Decimal>,
+    exchange_rate: Option<Decimal>,
+    current: Option<Decimal>,
+    to_currency: &str,
+) -> Decimal {
+    let resolved = match (to_amount, exchange_rate) {
+        (Some(_), Some(_)) => {
+            v.add("exchange_rate", "conflict", "转入金额和汇率只能提供一个");
+            return amount;
+        }
+        (Some(to_amount), None) => to_amount,
+        (None, Some(rate)) => match amount.checked_mul(rate) {
+            Some(to_amount) => to_amount.round_dp(currency_scale(to_currency)).normalize(),
+            None => {
+                v.add("exchange_rate", "too_large", "汇率过大");
+                return amount;
+            }
+        },
+        (None, None) => match current {
+            Some(current) => current,
+            None => {
+                v.add("to_amount", "required", "不同币种转账需要提供转入金额或汇率");
+                return amount;
+            }
+        },
+    };
+    v.check(resolved > Decimal::ZERO, "to_amount", "not_positive", "金额必须大于 0");
+    v.amount_scale("to_amount", resolved, to_currency);
+    resolved
+}
+
+// 未指定图标和颜色的一级分类使用的默认值
+const DEFAULT_CATEGORY_ICON: &str = "category";
+const DEFAULT_CATEGORY_COLOR: &str = "#9E9E9E";
+
+// 分类服务
+pub struct CategoryService {
+    state: Arc<AppState>,
+}
+
+impl CategoryService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    // 按交易类型和名称排序，默认不包括已归档的分类
+    pub async fn get_categories(
+        &self,
+        user_id: Uuid,
+        query: CategoryQuery,
+        locale: Locale,
+    ) -> Result<Vec<Category>, ServiceError> {
+        let include_archived = query.include_archived.unwrap_or(false);
+        let mut categories = self.state.storage.list_categories(user_id).await?;
+        categories.retain(|c| {
+            (include_archived || !c.is_archived)
+                && query.transaction_type.is_none_or(|ty| c.transaction_type == ty)
+        });
+        self.localize(&mut categories, locale).await?;
+        categories.sort_by(|a, b| {
+            (a.transaction_type.as_str(), &a.name, a.id).cmp(&(b.transaction_type.as_str(), &b.name, b.id))
+        });
+        Ok(categories)
+    }
+
+    // 系统分类目录，版本为其中最新的分类目录版本
+    pub async fn get_catalog(&self, user_id: Uuid, locale: Locale) -> Result<CategoryCatalog, ServiceError> {
+        let query = CategoryQuery {
+            include_archived: Some(true),
+            ..Default::default()
+        };
+        let mut categories = self.get_categories(user_id, query, locale).await?;
+        categories.retain(|c| c.is_system);
+        Ok(CategoryCatalog {
+            version: categories.iter().filter_map(|c| c.catalog_version).max().unwrap_or_default(),
+            locale,
+            categories,
+        })
+    }
+
+    pub async fn get_category(
+        &self,
+        user_id: Uuid,
+        category_id: Uuid,
+        locale: Locale,
+    ) -> Result<Category, ServiceError> {
+        let mut category = self.find_category(user_id, category_id).await?;
+        self.localize(std::slice::from_mut(&mut category), locale).await?;
+        Ok(category)
+    }
+
+    // 系统分类使用请求语言的名称，用户分类保持原名
+    async fn localize(&self, categories: &mut [Category], locale: Locale) -> Result<(), ServiceError> {
+        if !categories.iter().any(|c| c.is_system) {
+            return Ok(());
+        }
+        let names = self.state.storage.list_category_translations(locale.as_str()).await?;
+        for category in categories.iter_mut().filter(|c| c.is_system) {
+            if let Some(name) = names.get(&category.id) {
+                category.name = name.clone();
+            }
+        }
+        Ok(())
+    }
+
+    // 系统分类所有用户可见
+    async fn find_category(&self, user_id: Uuid, category_id: Uuid) -> Result<Category, ServiceError> {
+        match self.state.storage.find_category(category_id).await? {
+            Some(category) if category.is_system || category.user_id == Some(user_id) => Ok(category),
+            Some(_) => Err(ServiceError::AuthorizationFailed),
+            None => Err(ServiceError::NotFound(format!("category {}", category_id))),
+        }
+    }
+
+    // 用户自己的分类，系统分类不能修改
+    async fn get_own_category(&self, user_id: Uuid, category_id: Uuid) -> Result<Category, ServiceError> {
+        let category = self.find_category(user_id, category_id).await?;
+        if category.is_system {
+            return Err(ServiceError::AuthorizationFailed);
+        }
+        Ok(category)
+    }
+
+    // 请求体中引用的分类，不存在时作为该字段的校验错误返回
+    async fn get_referenced_category(
+        &self,
+        user_id: Uuid,
+        category_id: Uuid,
+        field: &str,
+    ) -> Result<Category, ServiceError> {
+        match self.find_category(user_id, category_id).await {
+            Err(ServiceError::NotFound(_)) | Err(ServiceError::AuthorizationFailed) => {
+                Err(ServiceError::Validation(vec![FieldError::new(field, "not_found", "分类不存在")]))
+            }
+            result => result,
+        }
+    }
+
+    pub async fn create_category(
+        &self,
+        user_id: Uuid,
+        request: CreateCategoryRequest,
+    ) -> Result<Category, ServiceError> {
+        let parent = match request.parent_id {
+            Some(parent_id) => Some(self.get_referenced_category(user_id, parent_id, "parent_id").await?),
+            None => None,
+        };
+
+        let mut category = Category {
+            id: Uuid::new_v4(),
+            user_id: Some(user_id),
+            parent_id: request.parent_id,
+            name: request.name.trim().to_string(),
+            icon: DEFAULT_CATEGORY_ICON.to_string(),
+            color: DEFAULT_CATEGORY_COLOR.to_string(),
+            transaction_type: request.transaction_type,
+            is_system: false,
+            is_archived: false,
+            catalog_version: None,
+        };
+        if let Some(parent) = &parent {
+            category.icon = parent.icon.clone();
+            category.color = parent.color.clone();
+        }
+        if let Some(icon) = request.icon {
+            category.icon = icon.trim().to_string();
+        }
+        if let Some(color) = request.color {
+            category.color = color.to_ascii_uppercase();
+        }
+
+        let mut v = Validator::new();
+        if let Some(parent) = &parent {
+            self.check_parent(&mut v, &category, parent, false);
+        }
+        self.check_unique_name(user_id, &mut v, &category).await?;
+        v.finish()?;
+
+        self.state.storage.insert_category(&category).await?;
+        Ok(category)
+    }
+
+    pub async fn update_category(
+        &self,
+        user_id: Uuid,
+        category_id: Uuid,
+        request: UpdateCategoryRequest,
+    ) -> Result<Category, ServiceError> {
+        let mut category = self.get_own_category(user_id, category_id).await?;
+        let mut v = Validator::new();
+
+        if let Some(name) = request.name {
+            category.name = name.trim().to_string();
+        }
+        if let Some(icon) = request.icon {
+            category.icon = icon.trim().to_string();
+        }
+        if let Some(color) = request.color {
+            category.color = color.to_ascii_uppercase();
+        }
+        if let Some(is_archived) = request.is_archived {
+            category.is_archived = is_archived;
+        }
+        if let Some(parent_id) = request.parent_id {
+            category.parent_id = parent_id;
+        }
+
+        if let Some(parent_id) = category.parent_id {
+            let parent = self.get_referenced_category(user_id, parent_id, "parent_id").await?;
+            let has_children = self
+                .state
+                .storage
+                .list_categories(user_id)
+                .await?
+                .iter()
+                .any(|c| c.parent_id == Some(category.id));
+            self.check_parent(&mut v, &category, &parent, has_children);
+        }
+        if !category.is_archived {
+            self.check_unique_name(user_id, &mut v, &category).await?;
+        }
+        v.finish()?;
+
+        // 归档时子分类一并归档
+        self.state.storage.update_category(&category).await?;
+        if category.is_archived {
+            self.state.storage.archive_category(category.id).await?;
+        }
+        Ok(category)
+    }
+
+    // 分类不做物理删除，已有交易保留原分类
+    pub async fn archive_category(&self, user_id: Uuid, category_id: Uuid) -> Result<(), ServiceError> {
+        self.get_own_category(user_id, category_id).await?;
+        self.state.storage.archive_category(category_id).await?;
+        Ok(())
+    }
+
+    pub async fn merge_category(
+        &self,
+        user_id: Uuid,
+        source_id: Uuid,
+        request: MergeCategoryRequest,
+        locale: Locale,
+    ) -> Result<MergeCategoryResponse, ServiceError> {
+        let source = self.get_own_category(user_id, source_id).await?;
+        let target = self
+            .get_referenced_category(user_id, request.target_id, "target_id")
+            .await?;
+
+        let mut v = Validator::new();
+        v.check(target.id != source.id, "target_id", "same_category", "不能合并到分类自身");
+        v.check(!target.is_archived, "target_id", "archived", "分类已归档");
+        v.check(
+            target.transaction_type == source.transaction_type,
+            "target_id",
+            "type_mismatch",
+            "分类的交易类型不一致",
+        );
+        v.check(
+            target.parent_id != Some(source.id),
+            "target_id",
+            "is_child",
+            "不能合并到自己的子分类",
+        );
+        // 子分类移到目标分类下，目标分类本身是子分类时会超过两级
+        if target.parent_id.is_some() {
+            let has_children = self
+                .state
+                .storage
+                .list_categories(user_id)
+                .await?
+                .iter()
+                .any(|c| c.parent_id == Some(source.id));
+            v.check(!has_children, "target_id", "too_deep", "来源分类有子分类，只能合并到一级分类");
+        }
+        v.finish()?;
+
+        let moved_transactions = self.state.storage.merge_category(source.id, target.id).await?;
+        let target = self.get_category(user_id, target.id, locale).await?;
+        Ok(MergeCategoryResponse {
+            target,
+            moved_transactions,
+        })
+    }
+
+    // 上级分类必须是未归档的一级分类，交易类型相同；有子分类的分类不能再作为子分类
+    fn check_parent(&self, v: &mut Validator, category: &Category, parent: &Category, has_children: bool) {
+        if parent.id == category.id {
+            v.add("parent_id", "invalid", "不能以自身作为上级分类");
+        } else if parent.parent_id.is_some() || has_children {
+            v.add("parent_id", "too_deep", "分类最多两级");
+        } else if parent.is_archived && !category.is_archived {
+            v.add("parent_id", "archived", "分类已归档");
+        } else if parent.transaction_type != category.transaction_type {
+            v.add("parent_id", "type_mismatch", "与上级分类的交易类型不一致");
+        }
+    }
+
+    // 同一上级分类下未归档的分类不能重名
+    async fn check_unique_name(
+        &self,
+        user_id: Uuid,
+        v: &mut Validator,
+        category: &Category,
+    ) -> Result<(), ServiceError> {
+        let duplicate = self.state.storage.list_categories(user_id).await?.iter().any(|c| {
+            c.id != category.id
+                && !c.is_archived
+                && c.parent_id == category.parent_id
+                && c.transaction_type == category.transaction_type
+                && c.name.to_lowercase() == category.name.to_lowercase()
+        });
+        v.check(!duplicate, "name", "already_exists", "已有同名分类");
+        Ok(())
+    }
+}
+
+// 自动补全最多返回的标签数
+const MAX_TAG_SUGGESTIONS: u64 = 100;
+
+// 标签服务，标签随交易创建，没有单独的创建和删除
+pub struct TagService {
+    state: Arc<AppState>,
+}
+
+impl TagService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    // 按使用次数从多到少排序，次数相同时按名称
+    pub async fn get_tags(&self, user_id: Uuid, query: TagQuery) -> Result<Vec<Tag>, ServiceError> {
+        let prefix = query.prefix.as_deref().unwrap_or_default().trim().to_lowercase();
+        let mut tags = self.state.storage.list_tags(user_id).await?;
+        tags.retain(|tag| tag.name.to_lowercase().starts_with(&prefix));
+        tags.sort_by(|a, b| b.usage_count.cmp(&a.usage_count).then_with(|| a.name.cmp(&b.name)));
+        if let Some(limit) = query.limit {
+            tags.truncate(limit.clamp(1, MAX_TAG_SUGGESTIONS) as usize);
+        }
+        Ok(tags)
+    }
+
+    async fn find_tag(&self, user_id: Uuid, name: &str) -> Result<Option<Tag>, ServiceError> {
+        let tags = self.state.storage.list_tags(user_id).await?;
+        Ok(tags.into_iter().find(|tag| tag.name == name))
+    }
+
+    async fn get_tag(&self, user_id: Uuid, name: &str) -> Result<Tag, ServiceError> {
+        self.find_tag(user_id, name)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("tag {}", name)))
+    }
+
+    // 新名称已被使用时不会自动合并，避免误操作
+    pub async fn rename_tag(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        request: RenameTagRequest,
+    ) -> Result<TagChangeResponse, ServiceError> {
+        let tag = self.get_tag(user_id, name).await?;
+        let new_name = request.name.trim();
+        if new_name == tag.name {
+            return Ok(TagChangeResponse {
+                tag,
+                updated_transactions: 0,
+            });
+        }
+
+        let mut v = Validator::new();
+        let exists = self.find_tag(user_id, new_name).await?.is_some();
+        v.check(!exists, "name", "already_exists", "已有同名标签，请使用合并");
+        v.finish()?;
+
+        self.change_tag(user_id, &tag.name, new_name).await
+    }
+
+    pub async fn merge_tag(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        request: MergeTagRequest,
+    ) -> Result<TagChangeResponse, ServiceError> {
+        let tag = self.get_tag(user_id, name).await?;
+        let target = request.target.trim();
+
+        let mut v = Validator::new();
+        v.check(target != tag.name, "target", "same_tag", "不能合并到标签自身");
+        let exists = self.find_tag(user_id, target).await?.is_some();
+        v.check(exists, "target", "not_found", "标签不存在");
+        v.finish()?;
+
+        self.change_tag(user_id, &tag.name, target).await
+    }
+
+    async fn change_tag(&self, user_id: Uuid, source: &str, target: &str) -> Result<TagChangeResponse, ServiceError> {
+        let updated_transactions = self.state.storage.merge_tag(user_id, source, target).await?;
+        Ok(TagChangeResponse {
+            tag: self.get_tag(user_id, target).await?,
+            updated_transactions,
+        })
+    }
+
+    // 按交易当天的汇率折算为用户本位币后按金额从大到小排序
+    pub async fn get_tag_spending(
+        &self,
+        user_id: Uuid,
+        query: TagSpendingQuery,
+    ) -> Result<TagSpendingReport, ServiceError> {
+        let mut v = Validator::new();
+        if let (Some(start), Some(end)) = (query.start_date, query.end_date) {
+            v.check(start <= end, "end_date", "invalid_range", "结束日期不能早于开始日期");
+        }
+        let transaction_type = query.transaction_type.unwrap_or(TransactionType::Expense);
+        v.check(
+            transaction_type != TransactionType::Transfer,
+            "transaction_type",
+            "not_allowed",
+            "转账不计入收支统计",
+        );
+        v.finish()?;
+
+        let user = UserService::new(self.state.clone()).get_user(user_id).await?;
+        let base_currency = user.base_currency;
+        let scale = currency_scale(&base_currency);
+        let mut rates = RateTable::new(ExchangeRateService::new(self.state.clone()), &base_currency);
+
+        let filter = TransactionFilter {
+            start_date: query.start_date,
+            end_date: query.end_date,
+            transaction_type: Some(transaction_type),
+            ..Default::default()
+        };
+        let mut totals: HashMap<String, (Decimal, u64)> = HashMap::new();
+        for total in self.state.storage.sum_transactions_by_tag(user_id, &filter).await? {
+            let converted = rates.convert(total.amount, &total.currency, total.date).await?;
+            let entry = totals.entry(total.tag).or_default();
+            entry.0 = entry.0.checked_add(converted).ok_or_else(|| rates.overflow(&total.currency))?;
+            entry.1 += total.transaction_count;
+        }
+
+        let mut tags: Vec<TagSpending> = totals
+            .into_iter()
+            .map(|(tag, (total, transaction_count))| TagSpending {
+                tag,
+                total: total.round_dp(scale).normalize(),
+                transaction_count,
+            })
+            .collect();
+        tags.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.tag.cmp(&b.tag)));
+
+        Ok(TagSpendingReport {
+            base_currency,
+            transaction_type,
+            tags,
+        })
+    }
+}
+
+// 预算服务
+pub struct BudgetService {
+    state: Arc<AppState>,
+}
+
+impl BudgetService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    pub async fn create_budget(&self, user_id: Uuid, request: CreateBudgetRequest) -> Result<Budget, ServiceError> {
+        let currency = match request.currency {
+            Some(currency) => currency.trim().to_ascii_uppercase(),
+            None => UserService::new(self.state.clone()).get_user(user_id).await?.base_currency,
+        };
+        let now = Utc::now();
+        let budget = Budget {
+            id: Uuid::new_v4(),
+            user_id,
+            name: request.name.trim().to_string(),
+            category_id: request.category_id,
+            tag: request.tag.map(|tag| tag.trim().to_string()),
+            period: request.period,
+            amount: request.amount,
+            currency,
+            rollover: request.rollover.unwrap_or(false),
+            start_date: request.start_date.unwrap_or_else(|| now.date_naive()),
+            end_date: request.end_date,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut v = Validator::new();
+        v.amount_scale("amount", budget.amount, &budget.currency);
+        check_budget_dates(&mut v, &budget);
+        if let Some(category_id) = budget.category_id {
+            let category = CategoryService::new(self.state.clone())
+                .get_referenced_category(user_id, category_id, "category_id")
+                .await?;
+            v.check(
+                category.transaction_type == TransactionType::Expense,
+                "category_id",
+                "type_mismatch",
+                "预算只能使用支出分类",
+            );
+            v.check(!category.is_archived, "category_id", "archived", "分类已归档");
+        }
+        v.finish()?;
+
+        self.state.storage.insert_budget(&budget).await?;
+        Ok(budget)
+    }
+
+    pub async fn get_budgets(&self, user_id: Uuid) -> Result<Vec<Budget>, ServiceError> {
+        Ok(self.state.storage.list_budgets(user_id).await?)
+    }
+
+    pub async fn get_budget(&self, user_id: Uuid, budget_id: Uuid) -> Result<Budget, ServiceError> {
+        match self.state.storage.find_budget(budget_id).await? {
+            Some(budget) if budget.user_id == user_id => Ok(budget),
+            Some(_) => Err(ServiceError::AuthorizationFailed),
+            None => Err(ServiceError::NotFound(format!("budget {}", budget_id))),
+        }
+    }
+
+    pub async fn update_budget(
+        &self,
+        user_id: Uuid,
+        budget_id: Uuid,
+        request: UpdateBudgetRequest,
+    ) -> Result<Budget, ServiceError> {
+        let mut budget = self.get_budget(user_id, budget_id).await?;
+
+        if let Some(name) = request.name {
+            budget.name = name.trim().to_string();
+        }
+        if let Some(amount) = request.amount {
+            budget.amount = amount;
+        }
+        if let Some(rollover) = request.rollover {
+            budget.rollover = rollover;
+        }
+        if let Some(end_date) = request.end_date {
+            budget.end_date = end_date;
+        }
+        budget.updated_at = Utc::now();
+
+        let mut v = Validator::new();
+        v.amount_scale("amount", budget.amount, &budget.currency);
+        check_budget_dates(&mut v, &budget);
+        v.finish()?;
+
+        self.state.storage.update_budget(&budget).await?;
+        Ok(budget)
+    }
+
+    pub async fn delete_budget(&self, user_id: Uuid, budget_id: Uuid) -> Result<(), ServiceError> {
+        self.get_budget(user_id, budget_id).await?;
+        self.state.storage.delete_budget(budget_id).await?;
+        Ok(())
+    }
+
+    // date 当天有效的全部预算的执行情况
+    pub async fn get_budget_statuses(
+        &self,
+        user_id: Uuid,
+        query: BudgetStatusQuery,
+    ) -> Result<Vec<BudgetStatus>, ServiceError> {
+        let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+        let mut statuses = Vec::new();
+        for budget in self.state.storage.list_budgets(user_id).await? {
+            if budget.start_date <= date && budget.end_date.is_none_or(|end| date <= end) {
+                statuses.push(self.status(&budget, date).await?);
+            }
+        }
+        Ok(statuses)
+    }
+
+    // 已结束的预算返回最后一个周期的情况
+    pub async fn get_budget_status(
+        &self,
+        user_id: Uuid,
+        budget_id: Uuid,
+        query: BudgetStatusQuery,
+    ) -> Result<BudgetStatus, ServiceError> {
+        let budget = self.get_budget(user_id, budget_id).await?;
+        let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+        let mut v = Validator::new();
+        v.check(date >= budget.start_date, "date", "before_start", "预算尚未开始");
+        v.finish()?;
+        let date = budget.end_date.map_or(date, |end| date.min(end));
+        self.status(&budget, date).await
+    }
+
+    async fn status(&self, budget: &Budget, date: NaiveDate) -> Result<BudgetStatus, ServiceError> {
+        let (period_start, period_end) = budget_period(budget, date);
+        // 结转需要从第一个周期开始累计
+        let first_start = if budget.rollover { budget.start_date } else { period_start };
+
+        let mut filter = TransactionFilter {
+            start_date: Some(first_start.and_time(NaiveTime::MIN).and_utc()),
+            end_date: Some(end_of_day(date)),
+            transaction_type: Some(TransactionType::Expense),
+            ..Default::default()
+        };
+        if let Some(category_id) = budget.category_id {
+            // 分类预算包括子分类
+            filter.category_ids = self
+                .state
+                .storage
+                .list_categories(budget.user_id)
+                .await?
+                .into_iter()
+                .filter(|c| c.id == category_id || c.parent_id == Some(category_id))
+                .map(|c| c.id)
+                .collect();
+        }
+        if let Some(tag) = &budget.tag {
+            filter.tags = vec![tag.clone()];
+        }
+
+        let mut rates = RateTable::new(ExchangeRateService::new(self.state.clone()), &budget.currency);
+        let mut spent_by_period: HashMap<NaiveDate, Decimal> = HashMap::new();
+        for total in self.state.storage.sum_transactions_by_day(budget.user_id, &filter).await? {
+            let converted = rates.convert(total.amount, &total.currency, total.date).await?;
+            let spent = spent_by_period.entry(budget_period(budget, total.date).0).or_default();
+            *spent = spent.checked_add(converted).ok_or_else(|| rates.overflow(&total.currency))?;
+        }
+
+        // 每个周期未用完的金额累计到下一周期，超支不结转
+        let mut rolled_over = Decimal::ZERO;
+        let mut start = first_start;
+        while start < period_start {
+            let (start_of, end_of) = budget_period(budget, start);
+            let spent = spent_by_period.get(&start_of).copied().unwrap_or_default();
+            rolled_over = (rolled_over + budget.amount - spent).max(Decimal::ZERO);
+            start = end_of + Duration::days(1);
+        }
+
+        let scale = currency_scale(&budget.currency);
+        let spent = spent_by_period.get(&period_start).copied().unwrap_or_default();
+        let elapsed_days = (date - period_start).num_days() + 1;
+        let period_days = (period_end - period_start).num_days() + 1;
+        let projected_spend = spent * Decimal::from(period_days) / Decimal::from(elapsed_days);
+
+        let rolled_over = rolled_over.round_dp(scale).normalize();
+        let spent = spent.round_dp(scale).normalize();
+        let projected_spend = projected_spend.round_dp(scale).normalize();
+        let available = budget.amount + rolled_over;
+        Ok(BudgetStatus {
+            budget_id: budget.id,
+            name: budget.name.clone(),
+            currency: budget.currency.clone(),
+            period_start,
+            period_end,
+            amount: budget.amount,
+            rolled_over,
+            available,
+            spent,
+            remaining: available - spent,
+            projected_spend,
+            is_overspent: spent > available,
+            is_projected_overspent: projected_spend > available,
+        })
+    }
+}
+
+// Custom 周期必须有结束日期，结束日期不能早于开始日期
+fn check_budget_dates(v: &mut Validator, budget: &Budget) {
+    match budget.end_date {
+        Some(end_date) => v.check(
+            end_date >= budget.start_date,
+            "end_date",
+            "invalid_range",
+            "结束日期不能早于开始日期",
+        ),
+        None => v.check(
+            budget.period != BudgetPeriod::Custom,
+            "end_date",
+            "required",
+            "自定义周期必须指定结束日期",
+        ),
+    }
+}
+
+// date 所在的预算周期（首尾两天都包含），第一个和最后一个周期截止到预算的起止日期
+fn budget_period(budget: &Budget, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let (start, end) = match budget.period {
+        BudgetPeriod::Weekly => {
+            let start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+            (start, start + Duration::days(6))
+        }
+        BudgetPeriod::Monthly => {
+            let start = date.with_day(1).unwrap();
+            (start, start + Months::new(1) - Duration::days(1))
+        }
+        BudgetPeriod::Yearly => (
+            NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(date.year(), 12, 31).unwrap(),
+        ),
+        BudgetPeriod::Custom => (budget.start_date, budget.end_date.unwrap_or(NaiveDate::MAX)),
+    };
+    (
+        start.max(budget.start_date),
+        budget.end_date.map_or(end, |end_date| end.min(end_date)),
+    )
+}
+
+// 某天（UTC）最后一刻，用作包含当天的结束时间
+fn end_of_day(date: NaiveDate) -> DateTime<Utc> {
+    (date + Duration::days(1)).and_time(NaiveTime::MIN).and_utc() - Duration::microseconds(1)
+}
+
+// 重复交易服务
+pub struct RecurringTransactionService {
+    state: Arc<AppState>,
+}
+
+impl RecurringTransactionService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    // 开始日期早于当天时立即补生成已到期的交易
+    pub async fn create_recurring(
+        &self,
+        user_id: Uuid,
+        request: CreateRecurringTransactionRequest,
+    ) -> Result<RecurringTransaction, ServiceError> {
+        let template = request.template;
+        let schedule = normalize_schedule(request.schedule);
+        let next_date = schedule.occurrences().next();
+        let now = Utc::now();
+        let recurring = RecurringTransaction {
+            id: Uuid::new_v4(),
+            user_id,
+            account_id: template.account_id,
+            category_id: template.category_id,
+            transaction_type: template.transaction_type,
+            amount: template.amount,
+            description: template.description.trim().to_string(),
+            notes: template.notes,
+            tags: trim_tags(template.tags.unwrap_or_default()),
+            next_date,
+            schedule,
+            occurrence_count: 0,
+            is_paused: false,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut v = Validator::new();
+        self.check_template(user_id, &mut v, &recurring).await?;
+        v.check(recurring.next_date.is_some(), "schedule", "no_occurrence", "重复规则没有任何发生日期");
+        v.finish()?;
+
+        self.state.storage.insert_recurring(&recurring).await?;
+        self.materialize(recurring, now.date_naive(), MAX_CATCH_UP_OCCURRENCES).await
+    }
+
+    pub async fn get_recurring_list(&self, user_id: Uuid) -> Result<Vec<RecurringTransaction>, ServiceError> {
+        Ok(self.state.storage.list_recurring(user_id).await?)
+    }
+
+    pub async fn get_recurring(&self, user_id: Uuid, recurring_id: Uuid) -> Result<RecurringTransaction, ServiceError> {
+        match self.state.storage.find_recurring(recurring_id).await? {
+            Some(recurring) if recurring.user_id == user_id => Ok(recurring),
+            Some(_) => Err(ServiceError::AuthorizationFailed),
+            None => Err(ServiceError::NotFound(format!("recurring transaction {}", recurring_id))),
+        }
+    }
+
+    // 修改这一次及之后的发生：之前还有发生时，原重复交易在新的开始日期前一天结束，
+    // 修改后的模板和规则作为新的重复交易从该日期开始，返回之后生效的重复交易
+    pub async fn update_recurring(
+        &self,
+        user_id: Uuid,
+        recurring_id: Uuid,
+        request: UpdateRecurringTransactionRequest,
+    ) -> Result<RecurringTransaction, ServiceError> {
+        let current = self.get_recurring(user_id, recurring_id).await?;
+        let mut v = Validator::new();
+        let Some(next_date) = current.next_date else {
+            v.add("from_date", "finished", "重复交易已结束");
+            return Err(v.finish().unwrap_err());
+        };
+
+        let mut edited = current.clone();
+        if let Some(account_id) = request.account_id {
+            edited.account_id = account_id;
+        }
+        if let Some(category_id) = request.category_id {
+            edited.category_id = category_id;
+        }
+        if let Some(amount) = request.amount {
+            edited.amount = amount;
+        }
+        if let Some(description) = request.description {
+            edited.description = description.trim().to_string();
+        }
+        if let Some(notes) = request.notes {
+            edited.notes = notes;
+        }
+        if let Some(tags) = request.tags {
+            edited.tags = trim_tags(tags);
+        }
+        self.check_template(user_id, &mut v, &edited).await?;
+
+        // 只修改模板时从原规则在 from_date 当天或之后的第一次发生开始，保持原有的间隔和对齐
+        let (field, schedule) = match request.schedule {
+            Some(schedule) => ("schedule.start_date", normalize_schedule(schedule)),
+            None => {
+                let from = request.from_date.unwrap_or(next_date);
+                let mut schedule = current.schedule.clone();
+                let start_date = schedule.occurrences().find(|date| *date >= from);
+                match start_date {
+                    Some(start_date) => {
+                        let before = schedule.occurrences().take_while(|date| *date < start_date).count();
+                        schedule.count = schedule.count.map(|count| count - before as u32);
+                        schedule.start_date = start_date;
+                    }
+                    None => v.add("from_date", "no_occurrence", "该日期之后没有发生"),
+                }
+                ("from_date", schedule)
+            }
+        };
+        v.check(
+            current.occurrence_count == 0 || schedule.start_date >= next_date,
+            field,
+            "already_posted",
+            "该日期之前的发生已生成，已生成的交易请单独修改",
+        );
+        edited.next_date = schedule.occurrences().next();
+        v.check(edited.next_date.is_some(), field, "no_occurrence", "重复规则没有任何发生日期");
+        v.finish()?;
+
+        let now = Utc::now();
+        edited.occurrence_count = 0;
+        edited.updated_at = now;
+        let updated = if current.occurrence_count == 0 && schedule.start_date <= next_date {
+            // 还没有任何发生，直接修改
+            edited.schedule = schedule;
+            self.state.storage.update_recurring(&edited, 0, None).await?
+        } else {
+            let mut previous = current.clone();
+            let end_date = schedule.start_date.pred_opt().unwrap_or(schedule.start_date);
+            previous.schedule.end_date = Some(previous.schedule.end_date.map_or(end_date, |end| end.min(end_date)));
+            previous.next_date = previous.schedule.occurrences().nth(previous.occurrence_count as usize);
+            previous.updated_at = now;
+            edited.id = Uuid::new_v4();
+            edited.schedule = schedule;
+            edited.created_at = now;
+            self.state
+                .storage
+                .update_recurring(&previous, current.occurrence_count, Some(&edited))
+                .await?
+        };
+        if !updated {
+            return Err(concurrent_update());
+        }
+        self.materialize(edited, now.date_naive(), MAX_CATCH_UP_OCCURRENCES).await
+    }
+
+    // 跳过下一次发生，跳过的也计入次数
+    pub async fn skip_next(&self, user_id: Uuid, recurring_id: Uuid) -> Result<RecurringTransaction, ServiceError> {
+        let mut recurring = self.get_recurring(user_id, recurring_id).await?;
+        let expected_count = recurring.occurrence_count;
+        let mut v = Validator::new();
+        v.check(recurring.next_date.is_some(), "next_date", "finished", "重复交易已结束");
+        v.finish()?;
+
+        advance(&mut recurring);
+        recurring.updated_at = Utc::now();
+        if !self.state.storage.update_recurring(&recurring, expected_count, None).await? {
+            return Err(concurrent_update());
+        }
+        Ok(recurring)
+    }
+
+    // 恢复时暂停期间的发生视为跳过
+    pub async fn set_paused(
+        &self,
+        user_id: Uuid,
+        recurring_id: Uuid,
+        paused: bool,
+    ) -> Result<RecurringTransaction, ServiceError> {
+        let mut recurring = self.get_recurring(user_id, recurring_id).await?;
+        if recurring.is_paused == paused {
+            return Ok(recurring);
+        }
+        let expected_count = recurring.occurrence_count;
+        let now = Utc::now();
+        recurring.is_paused = paused;
+        recurring.updated_at = now;
+        if !paused {
+            while recurring.next_date.is_some_and(|date| date < now.date_naive()) {
+                advance(&mut recurring);
+            }
+        }
+        if !self.state.storage.update_recurring(&recurring, expected_count, None).await? {
+            return Err(concurrent_update());
+        }
+        self.materialize(recurring, now.date_naive(), MAX_CATCH_UP_OCCURRENCES).await
+    }
+
+    // 已生成的交易保留
+    pub async fn delete_recurring(&self, user_id: Uuid, recurring_id: Uuid) -> Result<(), ServiceError> {
+        self.get_recurring(user_id, recurring_id).await?;
+        self.state.storage.delete_recurring(recurring_id).await?;
+        Ok(())
+    }
+
+    // 为所有到期的重复交易生成交易，返回生成的笔数；单个重复交易失败不影响其他的
+    pub async fn materialize_due(&self, today: NaiveDate) -> Result<usize, ServiceError> {
+        let mut posted = 0;
+        for recurring in self.state.storage.list_due_recurring(today).await? {
+            let (id, before) = (recurring.id, recurring.occurrence_count);
+            match self.materialize(recurring, today, usize::MAX).await {
+                Ok(recurring) => posted += recurring.occurrence_count.saturating_sub(before) as usize,
+                Err(err) => tracing::warn!("生成重复交易 {} 失败: {}", id, err),
+            }
+        }
+        Ok(posted)
+    }
+
+    // 依次生成 today 及之前到期的交易，最多 limit 笔；重复交易已被其他请求或实例修改时停止，以存储中的为准
+    async fn materialize(
+        &self,
+        mut recurring: RecurringTransaction,
+        today: NaiveDate,
+        limit: usize,
+    ) -> Result<RecurringTransaction, ServiceError> {
+        for _ in 0..limit {
+            let Some(date) = recurring.next_date.filter(|date| !recurring.is_paused && *date <= today) else {
+                break;
+            };
+            let Some(account) = self.state.storage.find_account(recurring.account_id).await? else {
+                break;
+            };
+            let now = Utc::now();
+            let transaction = Transaction {
+                id: Uuid::new_v4(),
+                user_id: recurring.user_id,
+                account_id: account.id,
+                category_id: recurring.category_id,
+                transaction_type: recurring.transaction_type,
+                amount: recurring.amount,
+                currency: account.currency,
+                description: recurring.description.clone(),
+                notes: recurring.notes.clone(),
+                tags: recurring.tags.clone(),
+                transaction_date: date.and_time(NaiveTime::MIN).and_utc(),
+                transfer_id: None,
+                transfer_direction: None,
+                recurring_id: Some(recurring.id),
+                created_at: now,
+                updated_at: now,
+            };
+
+            let expected_count = recurring.occurrence_count;
+            advance(&mut recurring);
+            recurring.updated_at = now;
+            if !self.state.storage.post_recurring(&recurring, expected_count, &transaction).await? {
+                return self
+                    .state
+                    .storage
+                    .find_recurring(recurring.id)
+                    .await?
+                    .ok_or_else(|| ServiceError::NotFound(format!("recurring transaction {}", recurring.id)));
+            }
+        }
+        Ok(recurring)
+    }
+
+    // 账户和分类的检查与创建交易相同
+    async fn check_template(
+        &self,
+        user_id: Uuid,
+        v: &mut Validator,
+        recurring: &RecurringTransaction,
+    ) -> Result<(), ServiceError> {
+        let account = AccountService::new(self.state.clone())
+            .get_referenced_account(user_id, recurring.account_id, "account_id")
+            .await?;
+        v.check(account.is_active, "account_id", "inactive", "账户已停用");
+        v.amount_scale("amount", recurring.amount, &account.currency);
+        if let Some(category_id) = recurring.category_id {
+            TransactionService::new(self.state.clone())
+                .check_category(user_id, v, category_id, recurring.transaction_type)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+// 创建、修改和恢复时在请求中最多补生成的笔数，其余由后台任务生成
+const MAX_CATCH_UP_OCCURRENCES: usize = 100;
+
+// 按月重复且未指定日期时固定为开始日期的日，拆分后的重复交易保持相同的日期
+fn normalize_schedule(mut schedule: RecurrenceSchedule) -> RecurrenceSchedule {
+    if schedule.frequency == RecurrenceFrequency::Monthly && !schedule.last_business_day {
+        schedule.day_of_month = Some(schedule.day_of_month.unwrap_or(schedule.start_date.day()));
+    }
+    schedule
+}
+
+// 前进到下一次发生
+fn advance(recurring: &mut RecurringTransaction) {
+    recurring.occurrence_count += 1;
+    recurring.next_date = recurring
+        .next_date
+        .and_then(|date| recurring.schedule.next_after(date, recurring.occurrence_count));
+}
+
+fn concurrent_update() -> ServiceError {
+    ServiceError::Conflict("recurring transaction was modified concurrently, please retry".to_string())
+}
+
+// 后台定时生成到期的重复交易，多个实例同时运行时由存储层的乐观锁保证不会重复生成
+pub async fn run_recurring_materializer(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(state.config.recurring_interval_secs));
+    loop {
+        interval.tick().await;
+        let today = Utc::now().date_naive();
+        match RecurringTransactionService::new(state.clone()).materialize_due(today).await {
+            Ok(0) => {}
+            Ok(posted) => tracing::info!("已生成 {} 笔重复交易", posted),
+            Err(err) => tracing::warn!("生成重复交易失败: {}", err),
+        }
+    }
+}
+
+// 信用卡分期服务
+pub struct InstallmentService {
+    state: Arc<AppState>,
+}
+
+impl InstallmentService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    // 把一笔信用卡消费分期，有手续费时创建按月记入手续费的重复交易，已到期的手续费立即补记
+    pub async fn create_installment(
+        &self,
+        user_id: Uuid,
+        transaction_id: Uuid,
+        request: CreateInstallmentPlanRequest,
+    ) -> Result<InstallmentPlanDetails, ServiceError> {
+        let transaction = TransactionService::new(self.state.clone())
+            .get_transaction(user_id, transaction_id)
+            .await?;
+        let account = AccountService::new(self.state.clone())
+            .get_account(user_id, transaction.account_id)
+            .await?;
+        let mut v = Validator::new();
+        v.check(
+            transaction.transaction_type == TransactionType::Expense && transaction.transfer_id.is_none(),
+            "transaction_id",
+            "not_expense",
+            "只有支出可以分期",
+        );
+        v.check(
+            account.account_type == AccountType::CreditCard,
+            "account_id",
+            "not_credit_card",
+            "只有信用卡消费可以分期",
+        );
+        v.finish()?;
+        if self.state.storage.find_transaction_installment(transaction.id).await?.is_some() {
+            return Err(ServiceError::Conflict(format!("transaction {} already has an installment plan", transaction.id)));
+        }
+
+        let mut v = Validator::new();
+        let scale = currency_scale(&account.currency);
+        if let Some(fee_per_period) = request.fee_per_period {
+            v.amount_scale("fee_per_period", fee_per_period, &account.currency);
+        }
+        let fee_per_period = match (request.fee_per_period, request.fee_rate) {
+            (Some(fee_per_period), _) => fee_per_period,
+            (None, Some(fee_rate)) => (transaction.amount * fee_rate).round_dp(scale),
+            (None, None) => Decimal::ZERO,
+        }
+        .normalize();
+        v.check(
+            !(transaction.amount / Decimal::from(request.periods)).round_dp(scale).is_zero(),
+            "periods",
+            "too_many",
+            "每期本金不能为 0",
+        );
+        let purchase_date = transaction.transaction_date.date_naive();
+        let first_period_date = request.first_period_date.unwrap_or(purchase_date);
+        v.check(
+            first_period_date >= purchase_date,
+            "first_period_date",
+            "before_purchase",
+            "第一期不能早于消费日期",
+        );
+        v.finish()?;
+
+        let now = Utc::now();
+        let fee_charges = (!fee_per_period.is_zero()).then(|| {
+            let schedule = normalize_schedule(RecurrenceSchedule {
+                frequency: RecurrenceFrequency::Monthly,
+                interval: 1,
+                day_of_month: None,
+                last_business_day: false,
+                start_date: first_period_date,
+                end_date: None,
+                count: Some(request.periods),
+            });
+            RecurringTransaction {
+                id: Uuid::new_v4(),
+                user_id,
+                account_id: account.id,
+                category_id: None,
+                transaction_type: TransactionType::Expense,
+                amount: fee_per_period,
+                description: format!("{} 分期手续费", transaction.description),
+                notes: None,
+                tags: transaction.tags.clone(),
+                next_date: Some(first_period_date),
+                schedule,
+                occurrence_count: 0,
+                is_paused: false,
+                created_at: now,
+                updated_at: now,
+            }
+        });
+        let plan = InstallmentPlan {
+            id: Uuid::new_v4(),
+            user_id,
+            account_id: account.id,
+            transaction_id: transaction.id,
+            principal: transaction.amount.normalize(),
+            periods: request.periods,
+            fee_per_period,
+            first_period_date,
+            recurring_id: fee_charges.as_ref().map(|r| r.id),
+            paid_off_date: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.state.storage.insert_installment(&plan, fee_charges.as_ref()).await?;
+        if let Some(fee_charges) = fee_charges {
+            RecurringTransactionService::new(self.state.clone())
+                .materialize(fee_charges, now.date_naive(), MAX_CATCH_UP_OCCURRENCES)
+                .await?;
+        }
+        Ok(installment_details(plan, account.currency, now.date_naive()))
+    }
+
+    pub async fn get_installments(&self, user_id: Uuid) -> Result<Vec<InstallmentPlanDetails>, ServiceError> {
+        let currencies: HashMap<Uuid, String> = self
+            .state
+            .storage
+            .list_accounts(user_id, i64::MAX, PageStart::Offset(0))
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account.currency))
+            .collect();
+        let today = Utc::now().date_naive();
+        Ok(self
+            .state
+            .storage
+            .list_installments(user_id)
+            .await?
+            .into_iter()
+            .map(|plan| {
+                let currency = currencies.get(&plan.account_id).cloned().unwrap_or_default();
+                installment_details(plan, currency, today)
+            })
+            .collect())
+    }
+
+    pub async fn get_installment(&self, user_id: Uuid, plan_id: Uuid) -> Result<InstallmentPlanDetails, ServiceError> {
+        let plan = self.find_installment(user_id, plan_id).await?;
+        let account = AccountService::new(self.state.clone())
+            .get_account(user_id, plan.account_id)
+            .await?;
+        Ok(installment_details(plan, account.currency, Utc::now().date_naive()))
+    }
+
+    // 提前还清：剩余本金全部计入还清当天，之后不再收手续费
+    // 还清日期不能晚于今天，也不能早于已入账的最后一期
+    pub async fn payoff_installment(
+        &self,
+        user_id: Uuid,
+        plan_id: Uuid,
+        request: PayoffInstallmentPlanRequest,
+    ) -> Result<InstallmentPlanDetails, ServiceError> {
+        let mut plan = self.find_installment(user_id, plan_id).await?;
+        let account = AccountService::new(self.state.clone())
+            .get_account(user_id, plan.account_id)
+            .await?;
+        let today = Utc::now().date_naive();
+        let date = request.date.unwrap_or(today);
+        let schedule = installment_schedule(&plan, currency_scale(&account.currency));
+
+        let mut v = Validator::new();
+        if plan.paid_off_date.is_some() || schedule.iter().all(|period| period.date <= today) {
+            v.add("date", "finished", "分期已结束");
+        } else {
+            v.check(date <= today, "date", "future", "还清日期不能晚于今天");
+            v.check(
+                schedule.iter().all(|period| period.date > today || period.date <= date),
+                "date",
+                "before_billed_period",
+                "还清日期不能早于已入账的最后一期",
+            );
+        }
+        v.finish()?;
+
+        if let Some(recurring_id) = plan.recurring_id.take() {
+            self.state.storage.delete_recurring(recurring_id).await?;
+        }
+        plan.paid_off_date = Some(date);
+        plan.updated_at = Utc::now();
+        self.state.storage.update_installment(&plan).await?;
+        Ok(installment_details(plan, account.currency, today))
+    }
+
+    async fn find_installment(&self, user_id: Uuid, plan_id: Uuid) -> Result<InstallmentPlan, ServiceError> {
+        match self.state.storage.find_installment(plan_id).await? {
+            Some(plan) if plan.user_id == user_id => Ok(plan),
+            Some(_) => Err(ServiceError::AuthorizationFailed),
+            None => Err(ServiceError::NotFound(format!("installment plan {}", plan_id))),
+        }
+    }
+}
+
+// 每月一期，从第一期日期起每月同一天（超过当月天数时为月末）；每期本金按货币精度取整，尾差计入最后一期
+// 提前还清后，还清日期之后的各期合并为还清当天的一期，不收手续费
+fn installment_schedule(plan: &InstallmentPlan, scale: u32) -> Vec<InstallmentPeriod> {
+    let per_period = (plan.principal / Decimal::from(plan.periods)).round_dp(scale);
+    let mut remaining = plan.principal;
+    let mut schedule = Vec::with_capacity(plan.periods as usize);
+    for number in 1..=plan.periods {
+        let date = plan.first_period_date + Months::new(number - 1);
+        if let Some(paid_off_date) = plan.paid_off_date.filter(|paid_off_date| date > *paid_off_date) {
+            schedule.push(InstallmentPeriod {
+                number,
+                date: paid_off_date,
+                principal: remaining.normalize(),
+                fee: Decimal::ZERO,
+                is_billed: false,
+            });
+            break;
+        }
+        let principal = if number == plan.periods { remaining } else { per_period };
+        remaining -= principal;
+        schedule.push(InstallmentPeriod {
+            number,
+            date,
+            principal: principal.normalize(),
+            fee: plan.fee_per_period,
+            is_billed: false,
+        });
+    }
+    schedule
+}
+
+// 入账日期不晚于 today 的期数为已入账
+fn installment_details(plan: InstallmentPlan, currency: String, today: NaiveDate) -> InstallmentPlanDetails {
+    let mut schedule = installment_schedule(&plan, currency_scale(&currency));
+    let mut details = InstallmentPlanDetails {
+        plan,
+        currency,
+        billed_periods: 0,
+        remaining_principal: Decimal::ZERO,
+        remaining_fees: Decimal::ZERO,
+        schedule: Vec::new(),
+    };
+    for period in &mut schedule {
+        period.is_billed = period.date <= today;
+        if period.is_billed {
+            details.billed_periods += 1;
+        } else {
+            details.remaining_principal += period.principal;
+            details.remaining_fees += period.fee;
+        }
+    }
+    details.remaining_principal = details.remaining_principal.normalize();
+    details.remaining_fees = details.remaining_fees.normalize();
+    details.schedule = schedule;
+    details
+}
+
+// 贷款和借出服务
+pub struct LoanService {
+    state: Arc<AppState>,
+}
+
+impl LoanService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    pub async fn get_amortization(&self, user_id: Uuid, account_id: Uuid) -> Result<AmortizationSchedule, ServiceError> {
+        let account = AccountService::new(self.state.clone())
+            .get_account(user_id, account_id)
+            .await?;
+        let terms = loan_terms(&account)?;
+        let outstanding = outstanding_principal(&account);
+        let paid_principal = (terms.principal - outstanding).max(Decimal::ZERO);
+        let rows = amortization_rows(&terms, paid_principal, currency_scale(&account.currency));
+
+        Ok(AmortizationSchedule {
+            account_id: account.id,
+            currency: account.currency,
+            total_payment: rows.iter().map(|row| row.payment).sum::<Decimal>().normalize(),
+            total_interest: rows.iter().map(|row| row.interest).sum::<Decimal>().normalize(),
+            paid_principal: paid_principal.normalize(),
+            outstanding_principal: outstanding.normalize(),
+            paid_periods: rows.iter().filter(|row| row.is_paid).count() as u32,
+            terms,
+            rows,
+        })
+    }
+
+    // 还款拆分为本金和利息：贷款从 account_id 转账到贷款账户并在 account_id 记一笔利息支出，
+    // 借出从借出账户转账到 account_id 并在 account_id 记一笔利息收入，各条交易在同一事务中写入
+    pub async fn create_payment(
+        &self,
+        user_id: Uuid,
+        loan_account_id: Uuid,
+        request: LoanPaymentRequest,
+    ) -> Result<LoanPayment, ServiceError> {
+        let accounts = AccountService::new(self.state.clone());
+        let loan_account = accounts.get_account(user_id, loan_account_id).await?;
+        let terms = loan_terms(&loan_account)?;
+        let account = accounts
+            .get_referenced_account(user_id, request.account_id, "account_id")
+            .await?;
+
+        let mut v = Validator::new();
+        v.check(account.is_active, "account_id", "inactive", "账户已停用");
+        v.check(account.id != loan_account.id, "account_id", "same_account", "不能使用借款账户本身");
+        v.check(
+            account.currency == loan_account.currency,
+            "account_id",
+            "currency_mismatch",
+            "账户货币必须与借款账户一致",
+        );
+        if let Some(amount) = request.amount {
+            v.amount_scale("amount", amount, &loan_account.currency);
+        }
+        if let Some(interest) = request.interest {
+            v.amount_scale("interest", interest, &loan_account.currency);
+        }
+        let scale = currency_scale(&loan_account.currency);
+        let outstanding = outstanding_principal(&loan_account);
+        v.check(!outstanding.is_zero(), "amount", "paid_off", "借款已还清");
+        v.finish()?;
+
+        let interest = request
+            .interest
+            .unwrap_or_else(|| (outstanding * terms.annual_interest_rate / Decimal::from(12)).round_dp(scale))
+            .normalize();
+        // 缺省还款本金为下一期计划中尚未还的本金，计划之外的剩余本金一次还清
+        let principal = match request.amount {
+            Some(amount) => amount - interest,
+            None => {
+                let paid_principal = (terms.principal - outstanding).max(Decimal::ZERO);
+                let mut scheduled = Decimal::ZERO;
+                amortization_rows(&terms, paid_principal, scale)
+                    .iter()
+                    .find_map(|row| {
+                        scheduled += row.principal;
+                        (scheduled > paid_principal).then(|| scheduled - paid_principal)
+                    })
+                    .unwrap_or(outstanding)
+                    .min(outstanding)
+            }
+        };
+        let mut v = Validator::new();
+        v.check(principal >= Decimal::ZERO, "amount", "less_than_interest", "还款金额不能少于利息");
+        v.check(principal <= outstanding, "amount", "exceeds_outstanding", "还款本金不能超过剩余本金");
+        v.finish()?;
+
+        let now = Utc::now();
+        let description = request.description.map(|description| description.trim().to_string());
+        let receivable = loan_account.account_type == AccountType::Receivable;
+        let entry = |account: &Account, transaction_type, amount, description: String| Transaction {
+            id: Uuid::new_v4(),
+            user_id,
+            account_id: account.id,
+            category_id: None,
+            transaction_type,
+            amount,
+            currency: account.currency.clone(),
+            description,
+            notes: None,
+            tags: Vec::new(),
+            transaction_date: request.payment_date.unwrap_or(now),
+            transfer_id: None,
+            transfer_direction: None,
+            recurring_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let principal_legs = (!principal.is_zero()).then(|| {
+            let (from, to) = if receivable { (&loan_account, &account) } else { (&account, &loan_account) };
+            let description = description
+                .clone()
+                .unwrap_or_else(|| format!("{} {}", loan_account.name, if receivable { "收回本金" } else { "还款本金" }));
+            let transfer_id = Uuid::new_v4();
+            let leg = |account: &Account, direction| Transaction {
+                transfer_id: Some(transfer_id),
+                transfer_direction: Some(direction),
+                ..entry(account, TransactionType::Transfer, principal, description.clone())
+            };
+            (
+                transfer_id,
+                TransferLegs {
+                    debit: leg(from, TransferDirection::Out),
+                    credit: leg(to, TransferDirection::In),
+                    fee: None,
+                },
+            )
+        });
+        let interest_transaction = (!interest.is_zero()).then(|| {
+            let (transaction_type, suffix) =
+                if receivable { (TransactionType::Income, "利息收入") } else { (TransactionType::Expense, "利息") };
+            let description = description.clone().unwrap_or_else(|| loan_account.name.clone());
+            entry(&account, transaction_type, interest, format!("{} {}", description, suffix))
+        });
+
+        let mut entries = Vec::new();
+        if let Some((_, legs)) = &principal_legs {
+            entries.extend(legs.to_vec());
+        }
+        entries.extend(interest_transaction.clone());
+        self.state.storage.insert_transfer(&entries).await?;
+
+        Ok(LoanPayment {
+            principal: principal.normalize(),
+            interest: interest.normalize(),
+            outstanding_principal: (outstanding - principal).normalize(),
+            principal_transfer: principal_legs.map(|(transfer_id, legs)| legs.into_transfer(transfer_id)),
+            interest_transaction,
+        })
+    }
+}
+
+// 贷款和借出账户的借款条件
+fn loan_terms(account: &Account) -> Result<LoanTerms, ServiceError> {
+    let error = match (account.account_type, &account.loan) {
+        (AccountType::Loan | AccountType::Receivable, Some(loan)) => return Ok(loan.clone()),
+        (AccountType::Loan | AccountType::Receivable, None) => {
+            FieldError::new("loan", "required", "账户未设置借款条件")
+        }
+        _ => FieldError::new("account_type", "not_loan", "只有贷款和借出账户有借款条件"),
+    };
+    Err(ServiceError::Validation(vec![error]))
+}
+
+// 剩余本金：贷款为欠款，借出为应收款
+fn outstanding_principal(account: &Account) -> Decimal {
+    match account.account_type {
+        AccountType::Receivable => account.balance.max(Decimal::ZERO),
+        _ => (-account.balance).max(Decimal::ZERO),
+    }
+}
+
+// 按借款条件计算每月还款，利息为上期剩余本金乘以月利率（年利率 / 12），
+// 各期金额按货币精度取整，尾差计入最后一期；按计划累计本金不超过 paid_principal 的各期为已还
+fn amortization_rows(terms: &LoanTerms, paid_principal: Decimal, scale: u32) -> Vec<AmortizationRow> {
+    let rate = terms.annual_interest_rate / Decimal::from(12);
+    let periods = Decimal::from(terms.term_months);
+    let per_period = match terms.repayment_method {
+        // 等额本息每期还款 P·r·(1+r)^n / ((1+r)^n − 1)，利率为 0 时为 P / n
+        RepaymentMethod::EqualInstallment if !rate.is_zero() => {
+            let growth = (0..terms.term_months).fold(Decimal::ONE, |growth, _| growth * (Decimal::ONE + rate));
+            terms.principal * (rate * growth / (growth - Decimal::ONE))
+        }
+        _ => terms.principal / periods,
+    }
+    .round_dp(scale);
+
+    let mut remaining = terms.principal;
+    let mut repaid = Decimal::ZERO;
+    let mut rows = Vec::with_capacity(terms.term_months as usize);
+    for number in 1..=terms.term_months {
+        let interest = (remaining * rate).round_dp(scale);
+        let principal = match terms.repayment_method {
+            _ if number == terms.term_months => remaining,
+            RepaymentMethod::EqualInstallment => (per_period - interest).clamp(Decimal::ZERO, remaining),
+            RepaymentMethod::EqualPrincipal => per_period.min(remaining),
+        };
+        remaining -= principal;
+        repaid += principal;
+        rows.push(AmortizationRow {
+            number,
+            date: terms.first_payment_date + Months::new(number - 1),
+            payment: (principal + interest).normalize(),
+            principal: principal.normalize(),
+            interest: interest.normalize(),
+            remaining_principal: remaining.normalize(),
+            is_paid: !paid_principal.is_zero() && repaid <= paid_principal,
+        });
+    }
+    rows
+}
+
+// 投资持仓服务
+pub struct InvestmentService {
+    state: Arc<AppState>,
+}
+
+impl InvestmentService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    pub async fn create_event(
+        &self,
+        user_id: Uuid,
+        account_id: Uuid,
+        request: CreateInvestmentEventRequest,
+    ) -> Result<InvestmentEvent, ServiceError> {
+        let account = self.get_investment_account(user_id, account_id).await?;
+        let mut events = self.state.storage.list_investment_events(account.id).await?;
+        let symbol = request.symbol.trim().to_uppercase();
+        // 未指定类别时沿用该品种最近一次事件的类别
+        let asset_class = request.asset_class.unwrap_or_else(|| {
+            events
+                .iter()
+                .rev()
+                .find(|event| event.symbol == symbol)
+                .map_or(AssetClass::Other, |event| event.asset_class)
+        });
+        let now = Utc::now();
+        let event = InvestmentEvent {
+            id: Uuid::new_v4(),
+            user_id,
+            account_id: account.id,
+            symbol,
+            asset_class,
+            event_type: request.event_type,
+            event_date: request.event_date,
+            quantity: request.quantity,
+            price: request.price,
+            amount: request.amount,
+            fee: request.fee.unwrap_or_default(),
+            notes: request.notes,
+            created_at: now,
+            updated_at: now,
+        };
+
+        events.push(event.clone());
+        check_investment_events(&account, &event, &mut events)?;
+        self.state.storage.insert_investment_event(&event).await?;
+        Ok(event)
+    }
+
+    pub async fn get_events(&self, user_id: Uuid, account_id: Uuid) -> Result<Vec<InvestmentEvent>, ServiceError> {
+        let account = self.get_investment_account(user_id, account_id).await?;
+        Ok(self.state.storage.list_investment_events(account.id).await?)
+    }
+
+    pub async fn get_event(&self, user_id: Uuid, event_id: Uuid) -> Result<InvestmentEvent, ServiceError> {
+        match self.state.storage.find_investment_event(event_id).await? {
+            Some(event) if event.user_id == user_id => Ok(event),
+            Some(_) => Err(ServiceError::AuthorizationFailed),
+            None => Err(ServiceError::NotFound(format!("investment event {}", event_id))),
+        }
+    }
+
+    pub async fn update_event(
+        &self,
+        user_id: Uuid,
+        event_id: Uuid,
+        request: UpdateInvestmentEventRequest,
+    ) -> Result<InvestmentEvent, ServiceError> {
+        let mut event = self.get_event(user_id, event_id).await?;
+        let account = self.get_investment_account(user_id, event.account_id).await?;
+
+        if let Some(asset_class) = request.asset_class {
+            event.asset_class = asset_class;
+        }
+        if let Some(event_date) = request.event_date {
+            event.event_date = event_date;
+        }
+        if let Some(quantity) = request.quantity {
+            event.quantity = Some(quantity);
+        }
+        if let Some(price) = request.price {
+            event.price = Some(price);
+        }
+        if let Some(amount) = request.amount {
+            event.amount = Some(amount);
+        }
+        if let Some(fee) = request.fee {
+            event.fee = fee;
+        }
+        if let Some(notes) = request.notes {
+            event.notes = notes;
+        }
+        event.updated_at = Utc::now();
+
+        let mut events = self.state.storage.list_investment_events(account.id).await?;
+        for existing in events.iter_mut().filter(|existing| existing.id == event.id) {
+            *existing = event.clone();
+        }
+        check_investment_events(&account, &event, &mut events)?;
+        self.state.storage.update_investment_event(&event).await?;
+        Ok(event)
+    }
+
+    // 删除后之后的卖出超过持仓时拒绝删除
+    pub async fn delete_event(&self, user_id: Uuid, event_id: Uuid) -> Result<(), ServiceError> {
+        let event = self.get_event(user_id, event_id).await?;
+        let account = self.get_investment_account(user_id, event.account_id).await?;
+        let mut events = self.state.storage.list_investment_events(account.id).await?;
+        events.retain(|existing| existing.id != event.id);
+        match replay_investments(&events, CostBasisMethod::Fifo, currency_scale(&account.currency)) {
+            Ok(_) => {}
+            Err(ReplayError::Oversold(sell)) => {
+                return Err(ServiceError::Conflict(format!(
+                    "deleting investment event {} would leave sell {} exceeding the holding",
+                    event.id, sell.id
+                )));
+            }
+            Err(ReplayError::Overflow(later)) => {
+                return Err(ServiceError::Conflict(format!(
+                    "deleting investment event {} would overflow the holding at event {}",
+                    event.id, later.id
+                )));
+            }
+        }
+        self.state.storage.delete_investment_event(event.id).await?;
+        Ok(())
+    }
+
+    pub async fn get_holdings(
+        &self,
+        user_id: Uuid,
+        account_id: Uuid,
+        query: HoldingsQuery,
+    ) -> Result<AccountHoldings, ServiceError> {
+        let account = self.get_investment_account(user_id, account_id).await?;
+        let method = query.method.unwrap_or_default();
+        let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+        let mut events = self.state.storage.list_investment_events(account.id).await?;
+        events.retain(|event| event.event_date <= end_of_day(date));
+        let (holdings, realized_gains) = replay_investments(&events, method, currency_scale(&account.currency))?;
+
+        Ok(AccountHoldings {
+            account_id: account.id,
+            currency: account.currency,
+            method,
+            date,
+            total_cost_basis: holdings.iter().map(|holding| holding.cost_basis).sum::<Decimal>().normalize(),
+            total_realized_gain: holdings.iter().map(|holding| holding.realized_gain).sum::<Decimal>().normalize(),
+            total_dividends: holdings.iter().map(|holding| holding.dividends).sum::<Decimal>().normalize(),
+            holdings,
+            realized_gains,
+        })
+    }
+
+    // 只有投资和加密货币账户可以记录持仓
+    async fn get_investment_account(&self, user_id: Uuid, account_id: Uuid) -> Result<Account, ServiceError> {
+        let account = AccountService::new(self.state.clone())
+            .get_account(user_id, account_id)
+            .await?;
+        if !matches!(account.account_type, AccountType::Investment | AccountType::Crypto) {
+            return Err(ServiceError::Validation(vec![FieldError::new(
+                "account_type",
+                "not_investment",
+                "只有投资和加密货币账户可以记录持仓",
+            )]));
+        }
+        Ok(account)
+    }
+}
+
+// 校验新增或修改后的事件，并按时间先后重放 events 确认任何时候卖出都不超过持仓
+fn check_investment_events(
+    account: &Account,
+    event: &InvestmentEvent,
+    events: &mut [InvestmentEvent],
+) -> Result<(), ServiceError> {
+    let mut v = Validator::new();
+    v.investment_event(event);
+    if let Some(amount) = event.amount {
+        v.currency_places("amount", amount, &account.currency);
+    }
+    v.currency_places("fee", event.fee, &account.currency);
+    v.finish()?;
+
+    events.sort_by_key(|event| (event.event_date, event.created_at, event.id));
+    match replay_investments(events, CostBasisMethod::Fifo, currency_scale(&account.currency)) {
+        Ok(_) => Ok(()),
+        Err(ReplayError::Oversold(_)) => Err(ServiceError::Validation(vec![FieldError::new(
+            "quantity",
+            "insufficient_quantity",
+            "卖出数量超过持仓",
+        )])),
+        Err(ReplayError::Overflow(_)) => Err(ServiceError::Validation(vec![FieldError::new(
+            "quantity",
+            "too_large",
+            "持仓数量或金额过大",
+        )])),
+    }
+}
+
+// 重放投资事件失败的原因及对应的事件
+#[derive(Debug)]
+enum ReplayError<'a> {
+    Oversold(&'a InvestmentEvent), // 卖出超过当时的持仓
+    Overflow(&'a InvestmentEvent), // 数量或金额超出 Decimal 的范围
+}
+
+impl From<ReplayError<'_>> for ServiceError {
+    fn from(err: ReplayError<'_>) -> Self {
+        match err {
+            ReplayError::Oversold(event) => {
+                ServiceError::Internal(format!("investment event {} exceeds the holding", event.id))
+            }
+            ReplayError::Overflow(event) => {
+                ServiceError::Internal(format!("investment event {} overflows the holding", event.id))
+            }
+        }
+    }
+}
+
+// 按顺序重放投资事件，返回各品种的持仓（按首次出现的顺序）和每次卖出的已实现收益；
+// 某次卖出超过当时的持仓或计算溢出时返回该事件
+fn replay_investments(
+    events: &[InvestmentEvent],
+    method: CostBasisMethod,
+    scale: u32,
+) -> Result<(Vec<Holding>, Vec<RealizedGain>), ReplayError<'_>> {
+    let mut replay = InvestmentReplay::new(method, scale);
+    for event in events {
+        replay.apply(event)?;
+    }
+    Ok((replay.holdings(), replay.realized_gains))
+}
+
+// 逐个应用按时间排序的投资事件，可以随时取出当前的持仓。批次始终按先进先出减少数量，
+// 平均成本法下持仓和卖出成本按移动加权平均计算，批次的单位成本显示为平均成本；金额按货币精度取整，单位成本保留 8 位小数
+struct InvestmentReplay {
+    method: CostBasisMethod,
+    scale: u32,
+    holdings: Vec<Holding>,
+    // 与 holdings 一一对应，估值时使用
+    trades: Vec<TradeHistory>,
+    realized_gains: Vec<RealizedGain>,
+}
+
+// 品种最近一次买入或卖出的日期和成交价（已按之后的拆股调整），以及每次拆股的日期和比例
+#[derive(Default)]
+struct TradeHistory {
+    last_trade: Option<(NaiveDate, Decimal)>,
+    splits: Vec<(NaiveDate, Decimal)>,
+}
+
+impl InvestmentReplay {
+    fn new(method: CostBasisMethod, scale: u32) -> Self {
+        Self {
+            method,
+            scale,
+            holdings: Vec::new(),
+            trades: Vec::new(),
+            realized_gains: Vec::new(),
+        }
+    }
+
+    fn apply<'a>(&mut self, event: &'a InvestmentEvent) -> Result<(), ReplayError<'a>> {
+        let scale = self.scale;
+        let index = match self.holdings.iter().position(|holding| holding.symbol == event.symbol) {
+            Some(index) => index,
+            None => {
+                self.holdings.push(Holding {
+                    symbol: event.symbol.clone(),
+                    asset_class: event.asset_class,
+                    quantity: Decimal::ZERO,
+                    cost_basis: Decimal::ZERO,
+                    average_cost: Decimal::ZERO,
+                    realized_gain: Decimal::ZERO,
+                    dividends: Decimal::ZERO,
+                    lots: Vec::new(),
+                });
+                self.trades.push(TradeHistory::default());
+                self.holdings.len() - 1
+            }
+        };
+        let holding = &mut self.holdings[index];
+        let trades = &mut self.trades[index];
+        holding.asset_class = event.asset_class;
+        let quantity = event.quantity.unwrap_or_default();
+        let price = event.price.unwrap_or_default();
+        let overflow = || ReplayError::Overflow(event);
+
+        match event.event_type {
+            InvestmentEventType::Buy => {
+                let cost = quantity
+                    .checked_mul(price)
+                    .and_then(|trade| trade.checked_add(event.fee))
+                    .ok_or_else(overflow)?
+                    .round_dp(scale);
+                holding.lots.push(Lot {
+                    event_id: event.id,
+                    acquired_at: event.event_date,
+                    quantity,
+                    unit_cost: (cost / quantity).round_dp(UNIT_COST_SCALE),
+                    cost_basis: cost,
+                });
+                holding.quantity = holding.quantity.checked_add(quantity).ok_or_else(overflow)?;
+                holding.cost_basis = holding.cost_basis.checked_add(cost).ok_or_else(overflow)?;
+                trades.last_trade = Some((event.event_date.date_naive(), price));
+            }
+            InvestmentEventType::Sell => {
+                if quantity > holding.quantity {
+                    return Err(ReplayError::Oversold(event));
+                }
+                // 批次按先进先出减少，部分卖出的批次按比例分摊成本
+                let mut lots_cost = Decimal::ZERO;
+                let mut remaining = quantity;
+                for lot in holding.lots.iter_mut() {
+                    let sold = remaining.min(lot.quantity);
+                    let cost = if sold == lot.quantity {
+                        lot.cost_basis
+                    } else {
+                        prorate(lot.cost_basis, sold, lot.quantity).ok_or_else(overflow)?.round_dp(scale)
+                    };
+                    lot.quantity -= sold;
+                    lot.cost_basis -= cost;
+                    lots_cost += cost;
+                    remaining -= sold;
+                    if remaining.is_zero() {
+                        break;
+                    }
+                }
+                holding.lots.retain(|lot| !lot.quantity.is_zero());
+
+                let cost = match self.method {
+                    CostBasisMethod::Fifo => lots_cost,
+                    CostBasisMethod::AverageCost if quantity == holding.quantity => holding.cost_basis,
+                    CostBasisMethod::AverageCost => prorate(holding.cost_basis, quantity, holding.quantity)
+                        .ok_or_else(overflow)?
+                        .round_dp(scale),
+                };
+                let proceeds = quantity
+                    .checked_mul(price)
+                    .and_then(|trade| trade.checked_sub(event.fee))
+                    .ok_or_else(overflow)?
+                    .round_dp(scale);
+                let gain = proceeds.checked_sub(cost).ok_or_else(overflow)?;
+                holding.quantity -= quantity;
+                holding.cost_basis -= cost;
+                holding.realized_gain = holding.realized_gain.checked_add(gain).ok_or_else(overflow)?;
+                trades.last_trade = Some((event.event_date.date_naive(), price));
+                self.realized_gains.push(RealizedGain {
+                    event_id: event.id,
+                    symbol: event.symbol.clone(),
+                    sold_at: event.event_date,
+                    quantity: quantity.normalize(),
+                    proceeds: proceeds.normalize(),
+                    cost_basis: cost.normalize(),
+                    gain: gain.normalize(),
+                });
+            }
+            InvestmentEventType::Dividend => {
+                holding.dividends = event
+                    .amount
+                    .unwrap_or_default()
+                    .checked_sub(event.fee)
+                    .and_then(|dividend| holding.dividends.checked_add(dividend))
+                    .ok_or_else(overflow)?;
+            }
+            InvestmentEventType::Split => {
+                holding.quantity = holding.quantity.checked_mul(quantity).ok_or_else(overflow)?;
+                for lot in holding.lots.iter_mut() {
+                    lot.quantity = lot.quantity.checked_mul(quantity).ok_or_else(overflow)?;
+                    lot.unit_cost = (lot.unit_cost / quantity).round_dp(UNIT_COST_SCALE);
+                }
+                if let Some((_, price)) = &mut trades.last_trade {
+                    *price = (*price / quantity).round_dp(UNIT_COST_SCALE);
+                }
+                trades.splits.push((event.event_date.date_naive(), quantity));
+            }
+        }
+        Ok(())
+    }
+
+    // 当前各品种的持仓
+    fn holdings(&self) -> Vec<Holding> {
+        let mut holdings = self.holdings.clone();
+        for holding in holdings.iter_mut() {
+            if !holding.quantity.is_zero() {
+                holding.average_cost = (holding.cost_basis / holding.quantity).round_dp(UNIT_COST_SCALE);
+            }
+            for lot in holding.lots.iter_mut() {
+                if self.method == CostBasisMethod::AverageCost {
+                    lot.unit_cost = holding.average_cost;
+                    lot.cost_basis = (lot.quantity * holding.average_cost).round_dp(self.scale);
+                }
+                lot.quantity = lot.quantity.normalize();
+                lot.unit_cost = lot.unit_cost.normalize();
+                lot.cost_basis = lot.cost_basis.normalize();
+            }
+            holding.quantity = holding.quantity.normalize();
+            holding.cost_basis = holding.cost_basis.normalize();
+            holding.average_cost = holding.average_cost.normalize();
+            holding.realized_gain = holding.realized_gain.normalize();
+            holding.dividends = holding.dividends.normalize();
+        }
+        holdings
+    }
+}
+
+// cost 中 part / whole 对应的部分，先乘后除以保留精度
+fn prorate(cost: Decimal, part: Decimal, whole: Decimal) -> Option<Decimal> {
+    cost.checked_mul(part)?.checked_div(whole)
+}
+
+// 单位成本保留的小数位数
+const UNIT_COST_SCALE: u32 = 8;
+
+// 收益率保留的小数位数
+const RETURN_SCALE: u32 = 6;
+// 求解年化内部收益率时搜索的上限
+const MAX_ANNUAL_RETURN: f64 = 1e6;
+
+// 投资组合服务
+pub struct PortfolioService {
+    state: Arc<AppState>,
+}
+
+impl PortfolioService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    pub async fn record_price(&self, user_id: Uuid, request: SecurityPriceRequest) -> Result<SecurityPrice, ServiceError> {
+        let price = SecurityPrice {
+            user_id,
+            symbol: request.symbol.trim().to_uppercase(),
+            price_date: request.price_date,
+            price: request.price.normalize(),
+            currency: request.currency.trim().to_ascii_uppercase(),
+            updated_at: Utc::now(),
+        };
+        self.state.storage.upsert_security_price(&price).await?;
+        Ok(price)
+    }
+
+    pub async fn get_prices(&self, user_id: Uuid, query: SecurityPriceQuery) -> Result<Vec<SecurityPrice>, ServiceError> {
+        let symbol = query.symbol.map(|symbol| symbol.trim().to_uppercase());
+        Ok(self.state.storage.list_security_prices(user_id, symbol.as_deref()).await?)
+    }
+
+    // 各投资账户按最新价格估值，并统计期间的已实现和未实现收益、时间加权和资金加权收益率
+    pub async fn get_performance(
+        &self,
+        user_id: Uuid,
+        query: PerformanceQuery,
+    ) -> Result<PortfolioPerformance, ServiceError> {
+        let end_date = query.end_date.unwrap_or_else(|| Utc::now().date_naive());
+        let mut v = Validator::new();
+        if let Some(start) = query.start_date {
+            v.check(start <= end_date, "end_date", "invalid_range", "结束日期不能早于开始日期");
+        }
+        v.finish()?;
+
+        let user = UserService::new(self.state.clone()).get_user(user_id).await?;
+        let base_currency = user.base_currency;
+        let base_scale = currency_scale(&base_currency);
+        let method = query.method.unwrap_or_default();
+        let mut prices = self.state.storage.list_security_prices(user_id, None).await?;
+        prices.sort_by(|a, b| (&a.symbol, a.price_date).cmp(&(&b.symbol, b.price_date)));
+
+        let mut accounts = Vec::new();
+        for account in self
+            .state
+            .storage
+            .list_accounts(user_id, i64::MAX, PageStart::Offset(0))
+            .await?
+        {
+            if !matches!(account.account_type, AccountType::Investment | AccountType::Crypto) {
+                continue;
+            }
+            let mut events = self.state.storage.list_investment_events(account.id).await?;
+            events.retain(|event| event.event_date <= end_of_day(end_date));
+            if !events.is_empty() {
+                accounts.push((account, events));
+            }
+        }
+        let start_date = query
+            .start_date
+            .or_else(|| {
+                accounts
+                    .iter()
+                    .filter_map(|(_, events)| events.first())
+                    .map(|event| event.event_date.date_naive())
+                    .min()
+            })
+            .unwrap_or(end_date);
+
+        // 期初市值取开始日期前一天的收盘估值；期间有投资事件的日期都作为估值日期，
+        // 所有账户使用相同的估值日期，便于按日期合计
+        let opening_date = start_date.pred_opt().unwrap_or(start_date);
+        let mut dates = vec![opening_date, end_date];
+        for (_, events) in &accounts {
+            dates.extend(
+                events
+                    .iter()
+                    .map(|event| event.event_date.date_naive())
+                    .filter(|date| *date >= start_date),
+            );
+        }
+        dates.sort();
+        dates.dedup();
+
+        let mut base_rates = RateTable::new(ExchangeRateService::new(self.state.clone()), &base_currency);
+        let mut total_values = vec![Decimal::ZERO; dates.len()];
+        let mut total_flows = vec![Decimal::ZERO; dates.len()];
+        let mut total_cost_basis = Decimal::ZERO;
+        let mut total_realized_gain = Decimal::ZERO;
+        let mut total_dividends = Decimal::ZERO;
+        let mut account_performances = Vec::with_capacity(accounts.len());
+        for (account, events) in accounts {
+            let scale = currency_scale(&account.currency);
+            let mut rates = RateTable::new(ExchangeRateService::new(self.state.clone()), &account.currency);
+            let mut values = Vec::with_capacity(dates.len());
+            let mut flows = Vec::with_capacity(dates.len());
+            let mut holdings = Vec::new();
+            // 事件只重放一次，每个估值日期应用到当天为止的新事件后估值；除期初外，
+            // 每个估值日期之前新应用的事件都发生在当天，即当天的资金流动
+            let mut replay = InvestmentReplay::new(method, scale);
+            let mut applied = 0;
+            for (index, date) in dates.iter().enumerate() {
+                let count = events.partition_point(|event| event.event_date <= end_of_day(*date));
+                for event in &events[applied..count] {
+                    replay.apply(event)?;
+                }
+                let valuations = value_holdings(&replay, &prices, *date, &mut rates).await?;
+                let value = valuations
+                    .iter()
+                    .try_fold(Decimal::ZERO, |total, valuation| total.checked_add(valuation.market_value))
+                    .ok_or_else(|| ServiceError::Internal(format!("market value of account {} overflows", account.id)))?;
+                values.push(value);
+                flows.push(if index == 0 { Decimal::ZERO } else { net_flow(&events[applied..count], scale)? });
+                applied = count;
+                holdings = valuations;
+            }
+
+            let mut realized_gain = Decimal::ZERO;
+            for gain in replay.realized_gains.iter().filter(|gain| gain.sold_at.date_naive() >= start_date) {
+                realized_gain += gain.gain;
+                base_rates
+                    .accumulate(&mut total_realized_gain, gain.gain, &account.currency, gain.sold_at.date_naive(), base_scale)
+                    .await?;
+            }
+            let mut dividends = Decimal::ZERO;
+            for event in events.iter().filter(|event| {
+                event.event_type == InvestmentEventType::Dividend && event.event_date.date_naive() >= start_date
+            }) {
+                let amount = event.amount.unwrap_or_default() - event.fee;
+                dividends += amount;
+                base_rates
+                    .accumulate(&mut total_dividends, amount, &account.currency, event.event_date.date_naive(), base_scale)
+                    .await?;
+            }
+            let cost_basis = holdings.iter().map(|holding| holding.cost_basis).sum::<Decimal>();
+            base_rates
+                .accumulate(&mut total_cost_basis, cost_basis, &account.currency, end_date, base_scale)
+                .await?;
+            for (index, date) in dates.iter().enumerate() {
+                base_rates
+                    .accumulate(&mut total_values[index], values[index], &account.currency, *date, base_scale)
+                    .await?;
+                base_rates
+                    .accumulate(&mut total_flows[index], flows[index], &account.currency, *date, base_scale)
+                    .await?;
+            }
+
+            account_performances.push(AccountPerformance {
+                account_id: account.id,
+                account_name: account.name,
+                summary: performance_summary(
+                    account.currency,
+                    &dates,
+                    &values,
+                    &flows,
+                    cost_basis,
+                    realized_gain,
+                    dividends,
+                ),
+                holdings,
+            });
+        }
+
+        Ok(PortfolioPerformance {
+            start_date,
+            end_date,
+            method,
+            total: performance_summary(
+                base_currency,
+                &dates,
+                &total_values,
+                &total_flows,
+                total_cost_basis,
+                total_realized_gain,
+                total_dividends,
+            ),
+            accounts: account_performances,
+        })
+    }
+}
+
+// 按 date 收盘时的持仓和价格估值，replay 只应用了 date 当天及之前的事件；没有价格的品种按平均成本估值。
+// prices 按品种和日期排序
+async fn value_holdings(
+    replay: &InvestmentReplay,
+    prices: &[SecurityPrice],
+    date: NaiveDate,
+    rates: &mut RateTable,
+) -> Result<Vec<HoldingValuation>, ServiceError> {
+    let mut valuations = Vec::new();
+    for (holding, trades) in replay.holdings().into_iter().zip(&replay.trades) {
+        if holding.quantity.is_zero() {
+            continue;
+        }
+        let overflow = || ServiceError::Internal(format!("market value of {} overflows", holding.symbol));
+        let (price, price_date, market_value) = match latest_price(&holding.symbol, trades, prices, date) {
+            Some((price_date, price, currency)) => {
+                let price = match currency {
+                    Some(currency) => price
+                        .checked_mul(rates.rate(&currency, date).await?)
+                        .ok_or_else(overflow)?
+                        .round_dp(UNIT_COST_SCALE),
+                    None => price,
+                };
+                let market_value = holding.quantity.checked_mul(price).ok_or_else(overflow)?;
+                (price, Some(price_date), market_value.round_dp(replay.scale))
+            }
+            None => (holding.average_cost, None, holding.cost_basis),
+        };
+        valuations.push(HoldingValuation {
+            symbol: holding.symbol,
+            asset_class: holding.asset_class,
+            quantity: holding.quantity,
+            price: price.normalize(),
+            price_date,
+            market_value: market_value.normalize(),
+            cost_basis: holding.cost_basis,
+            unrealized_gain: (market_value - holding.cost_basis).normalize(),
+        });
+    }
+    Ok(valuations)
+}
+
+// date 当天或之前最近的价格：手动记录的价格和最近一次买入或卖出的成交价取日期较晚的一个，同一天优先手动记录的价格；
+// 价格日期之后的拆股按比例调整价格。手动记录的价格返回其货币，成交价使用账户货币，返回 None
+fn latest_price(
+    symbol: &str,
+    trades: &TradeHistory,
+    prices: &[SecurityPrice],
+    date: NaiveDate,
+) -> Option<(NaiveDate, Decimal, Option<String>)> {
+    let first = prices.partition_point(|price| price.symbol.as_str() < symbol);
+    let count = prices[first..].partition_point(|price| price.symbol == symbol && price.price_date <= date);
+    let recorded = prices[first..first + count].last();
+
+    match trades.last_trade {
+        Some((trade_date, price)) if recorded.is_none_or(|recorded| recorded.price_date < trade_date) => {
+            Some((trade_date, price, None))
+        }
+        _ => {
+            let recorded = recorded?;
+            let mut price = recorded.price;
+            for (_, ratio) in trades.splits.iter().filter(|(split_date, _)| *split_date > recorded.price_date) {
+                price = (price / ratio).round_dp(UNIT_COST_SCALE);
+            }
+            Some((recorded.price_date, price, Some(recorded.currency.clone())))
+        }
+    }
+}
+
+// events 带来的资金净流入：买入成本为流入，卖出所得和分红为流出
+fn net_flow(events: &[InvestmentEvent], scale: u32) -> Result<Decimal, ReplayError<'_>> {
+    events.iter().try_fold(Decimal::ZERO, |total, event| {
+        let trade = event.quantity.unwrap_or_default().checked_mul(event.price.unwrap_or_default());
+        let flow = match event.event_type {
+            InvestmentEventType::Buy => trade.and_then(|trade| trade.checked_add(event.fee)),
+            InvestmentEventType::Sell => trade.and_then(|trade| event.fee.checked_sub(trade)),
+            InvestmentEventType::Dividend => event.fee.checked_sub(event.amount.unwrap_or_default()),
+            InvestmentEventType::Split => Some(Decimal::ZERO),
+        };
+        flow.and_then(|flow| total.checked_add(flow.round_dp(scale)))
+            .ok_or(ReplayError::Overflow(event))
+    })
+}
+
+// values 和 flows 与 dates 一一对应，第一个日期为期初
+fn performance_summary(
+    currency: String,
+    dates: &[NaiveDate],
+    values: &[Decimal],
+    flows: &[Decimal],
+    cost_basis: Decimal,
+    realized_gain: Decimal,
+    dividends: Decimal,
+) -> PerformanceSummary {
+    let end_value = values.last().copied().unwrap_or_default();
+    PerformanceSummary {
+        currency,
+        start_value: values.first().copied().unwrap_or_default().normalize(),
+        end_value: end_value.normalize(),
+        net_contributions: flows.iter().sum::<Decimal>().normalize(),
+        cost_basis: cost_basis.normalize(),
+        unrealized_gain: (end_value - cost_basis).normalize(),
+        realized_gain: realized_gain.normalize(),
+        dividends: dividends.normalize(),
+        time_weighted_return: time_weighted_return(values, flows),
+        money_weighted_return: money_weighted_return(dates, values, flows),
+    }
+}
+
+// 在每个估值日期切分期间，子期间的收益为 (当日市值 - 当日净流入) / 前一估值日市值，
+// 前一估值日没有持仓的子期间不计入；整个期间都没有持仓时返回 None
+fn time_weighted_return(values: &[Decimal], flows: &[Decimal]) -> Option<Decimal> {
+    let mut growth = Decimal::ONE;
+    let mut measured = false;
+    for index in 1..values.len() {
+        if values[index - 1] > Decimal::ZERO {
+            growth = growth
+                .checked_mul(values[index] - flows[index])?
+                .checked_div(values[index - 1])?;
+            measured = true;
+        }
+    }
+    measured.then(|| (growth - Decimal::ONE).round_dp(RETURN_SCALE).normalize())
+}
+
+// 期初市值和期间净流入视为投入，期末市值视为收回，用二分法求年化的内部收益率（XIRR）；
+// 没有投入或没有收回、或收益率超出搜索范围时返回 None
+fn money_weighted_return(dates: &[NaiveDate], values: &[Decimal], flows: &[Decimal]) -> Option<Decimal> {
+    let (first, last) = (*dates.first()?, *dates.last()?);
+    let years = |date: NaiveDate| (date - first).num_days() as f64 / 365.0;
+    let mut cash_flows = vec![(0.0, -f64::try_from(values[0]).ok()?)];
+    for (date, flow) in dates.iter().zip(flows).skip(1).filter(|(_, flow)| !flow.is_zero()) {
+        cash_flows.push((years(*date), -f64::try_from(*flow).ok()?));
+    }
+    cash_flows.push((years(last), f64::try_from(*values.last()?).ok()?));
+    if !cash_flows.iter().any(|(_, amount)| *amount < 0.0) || !cash_flows.iter().any(|(_, amount)| *amount > 0.0) {
+        return None;
+    }
+
+    let npv = |rate: f64| -> f64 {
+        cash_flows
+            .iter()
+            .map(|(years, amount)| amount / (1.0 + rate).powf(*years))
+            .sum()
+    };
+    let mut low: f64 = -0.999_999;
+    let mut high: f64 = 1.0;
+    let low_value = npv(low);
+    if !low_value.is_finite() {
+        return None;
+    }
+    while npv(high).signum() == low_value.signum() {
+        high *= 2.0;
+        if high > MAX_ANNUAL_RETURN {
+            return None;
+        }
+    }
+    for _ in 0..200 {
+        let middle = (low + high) / 2.0;
+        if npv(middle).signum() == low_value.signum() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Decimal::try_from((low + high) / 2.0)
+        .ok()
+        .map(|rate| rate.round_dp(RETURN_SCALE).normalize())
+}
+
+// 即将到期款项默认和最多列出的天数
+const DEFAULT_UPCOMING_DAYS: u32 = 30;
+const MAX_UPCOMING_DAYS: u32 = 366;
+
+// 即将到期款项服务
+pub struct UpcomingService {
+    state: Arc<AppState>,
+}
+
+impl UpcomingService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    // 未暂停的重复交易的每次发生和信用卡的下一个还款日，按到期日排序
+    pub async fn get_upcoming(&self, user_id: Uuid, query: UpcomingQuery) -> Result<Vec<UpcomingPayment>, ServiceError> {
+        let days = query.days.unwrap_or(DEFAULT_UPCOMING_DAYS);
+        let mut v = Validator::new();
+        v.check(
+            (1..=MAX_UPCOMING_DAYS).contains(&days),
+            "days",
+            "out_of_range",
+            format!("天数必须在 1 到 {} 之间", MAX_UPCOMING_DAYS),
+        );
+        v.finish()?;
+
+        let start = Utc::now().date_naive();
+        let end = start + Duration::days(days as i64 - 1);
+        let accounts: HashMap<Uuid, Account> = self
+            .state
+            .storage
+            .list_accounts(user_id, i64::MAX, PageStart::Offset(0))
+            .await?
+            .into_iter()
+            .map(|account| (account.id, account))
+            .collect();
+
+        let mut payments = Vec::new();
+        for recurring in self.state.storage.list_recurring(user_id).await? {
+            if let Some(account) = accounts.get(&recurring.account_id) {
+                payments.extend(recurring_payments(&recurring, account, start, end));
+            }
+        }
+        let account_service = AccountService::new(self.state.clone());
+        for account in accounts.values() {
+            payments.extend(account_service.upcoming_card_payment(account, start, end).await?);
+        }
+        payments.sort_by(|a, b| {
+            (a.due_date, a.kind.as_str(), &a.description).cmp(&(b.due_date, b.kind.as_str(), &b.description))
+        });
+        Ok(payments)
+    }
+}
+
+// 重复交易在 start 到 end 之间尚未生成的发生，暂停时没有
+fn recurring_payments<'a>(
+    recurring: &'a RecurringTransaction,
+    account: &'a Account,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> impl Iterator<Item = UpcomingPayment> + 'a {
+    recurring
+        .schedule
+        .occurrences()
+        .skip(recurring.occurrence_count as usize)
+        .take_while(move |date| !recurring.is_paused && *date <= end)
+        .filter(move |date| *date >= start)
+        .map(|due_date| UpcomingPayment {
+            kind: UpcomingKind::Recurring,
+            source_id: recurring.id,
+            account_id: account.id,
+            due_date,
+            description: recurring.description.clone(),
+            transaction_type: recurring.transaction_type,
+            amount: recurring.amount,
+            currency: account.currency.clone(),
+        })
+}
+
+// 到期提醒服务
+pub struct NotificationService {
+    state: Arc<AppState>,
+}
+
+impl NotificationService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    pub async fn get_notifications(
+        &self,
+        user_id: Uuid,
+        query: NotificationQuery,
+    ) -> Result<Vec<Notification>, ServiceError> {
+        Ok(self
+            .state
+            .storage
+            .list_notifications(user_id, query.unread_only.unwrap_or(false))
+            .await?)
+    }
+
+    pub async fn mark_read(&self, user_id: Uuid, notification_id: Uuid) -> Result<Notification, ServiceError> {
+        let notification = match self.state.storage.find_notification(notification_id).await? {
+            Some(notification) if notification.user_id == user_id => notification,
+            Some(_) => return Err(ServiceError::AuthorizationFailed),
+            None => return Err(ServiceError::NotFound(format!("notification {}", notification_id))),
+        };
+        self.state.storage.mark_notification_read(notification.id, Utc::now()).await?;
+        self.state
+            .storage
+            .find_notification(notification.id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("notification {}", notification_id)))
+    }
+
+    // 为 today 起 reminder_days 天内到期的支出和信用卡还款生成提醒，返回新生成的条数
+    // 已提醒过的款项由存储层的唯一约束去重，多次运行或多个实例同时运行不会重复提醒
+    pub async fn send_due_reminders(&self, today: NaiveDate) -> Result<usize, ServiceError> {
+        let end = today + Duration::days(self.state.config.reminder_days as i64);
+        let mut due = Vec::new();
+        for recurring in self.state.storage.list_due_recurring(end).await? {
+            if recurring.transaction_type != TransactionType::Expense {
+                continue;
+            }
+            if let Some(account) = self.state.storage.find_account(recurring.account_id).await? {
+                due.extend(recurring_payments(&recurring, &account, today, end).map(|p| (recurring.user_id, p)));
+            }
+        }
+        for account in self.state.storage.list_payment_due_accounts().await? {
+            let payment = AccountService::new(self.state.clone())
+                .upcoming_card_payment(&account, today, end)
+                .await?;
+            due.extend(payment.map(|p| (account.user_id, p)));
+        }
+
+        let mut sent = 0;
+        for (user_id, payment) in due {
+            let notification = Notification {
+                id: Uuid::new_v4(),
+                user_id,
+                kind: payment.kind,
+                source_id: payment.source_id,
+                due_date: payment.due_date,
+                title: payment.description,
+                amount: payment.amount,
+                currency: payment.currency,
+                created_at: Utc::now(),
+                read_at: None,
+            };
+            if self.state.storage.insert_notification(&notification).await? {
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+}
+
+// 后台定时生成到期提醒
+pub async fn run_bill_reminders(state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(state.config.reminder_interval_secs));
+    loop {
+        interval.tick().await;
+        let today = Utc::now().date_naive();
+        match NotificationService::new(state.clone()).send_due_reminders(today).await {
+            Ok(0) => {}
+            Ok(sent) => tracing::info!("已生成 {} 条到期提醒", sent),
+            Err(err) => tracing::warn!("生成到期提醒失败: {}", err),
+        }
+    }
+}
+
+// 历史汇率不再变化，可以长时间缓存；当天的汇率可能还会更新
+const HISTORICAL_RATE_TTL: i64 = 7 * 24 * 60 * 60;
+const CURRENT_RATE_TTL: i64 = 60 * 60;
+
+// 汇率换算时保留的小数位数
+const RATE_SCALE: u32 = 12;
+
+// 交叉汇率的中间货币
+const PIVOT_CURRENCY: &str = "USD";
+
+// 外部来源的汇率必须在 [1 / MAX_EXCHANGE_RATE, MAX_EXCHANGE_RATE] 之间，保证正反向换算都不会溢出
+fn is_plausible_rate(rate: Decimal) -> bool {
+    let max = Decimal::from(MAX_EXCHANGE_RATE);
+    rate <= max && rate * max >= Decimal::ONE
+}
+
+// 汇率服务
+pub struct ExchangeRateService {
+    state: Arc<AppState>,
+}
+
+impl ExchangeRateService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    // base 兑 quote 在 date 当天有效的汇率：依次查缓存、数据库（含反向汇率）和外部来源，
+    // 当天没有发布汇率时（周末、节假日）使用之前最近一天的汇率
+    pub async fn get_rate(&self, base: &str, quote: &str, date: NaiveDate) -> Result<ExchangeRate, ServiceError> {
+        let today = Utc::now().date_naive();
+        let date = date.min(today);
+        if base == quote {
+            return Ok(ExchangeRate {
+                base_currency: base.to_string(),
+                quote_currency: quote.to_string(),
+                rate_date: date,
+                rate: Decimal::ONE,
+                source: "identity".to_string(),
+            });
+        }
+        if let Some(rate) = self.state.rate_cache.get_rate(base, quote, date).await? {
+            return Ok(rate);
+        }
+
+        let mut rate = self.find_stored_rate(base, quote, date).await?;
+        if rate.as_ref().is_none_or(|rate| rate.rate_date < date) {
+            if let Some(provider) = &self.state.rate_provider {
+                // 先以 base 为基准获取，来源不支持该基准货币时再反向获取
+                for currency in [base, quote] {
+                    match provider.fetch_rates(currency, date).await {
+                        Ok(mut rates) if !rates.is_empty() => {
+                            rates.retain(|rate| {
+                                let plausible = is_plausible_rate(rate.rate);
+                                if !plausible {
+                                    tracing::warn!(
+                                        "忽略超出范围的汇率 {}/{} = {}",
+                                        rate.base_currency, rate.quote_currency, rate.rate
+                                    );
+                                }
+                                plausible
+                            });
+                            self.state.storage.upsert_exchange_rates(&rates).await?;
+                        }
+                        Ok(_) => continue,
+                        Err(err) => {
+                            tracing::warn!("获取 {} 在 {} 的汇率失败: {}", currency, date, err);
+                            continue;
+                        }
+                    }
+                    let fetched = self.find_stored_rate(base, quote, date).await?;
+                    if fetched.is_some() {
+                        rate = fetched;
+                        break;
+                    }
+                }
+            }
+        }
+
+        let rate = match rate {
+            Some(rate) => rate,
+            None => self.cross_rate(base, quote, date).await?,
+        };
+        let ttl = if date < today { HISTORICAL_RATE_TTL } else { CURRENT_RATE_TTL };
+        self.state.rate_cache.put_rate(date, &rate, ttl).await?;
+        Ok(rate)
+    }
+
+    // 汇率查询接口，货币代码不区分大小写
+    pub async fn lookup(&self, query: ExchangeRateQuery) -> Result<ExchangeRate, ServiceError> {
+        let base = query.base.trim().to_ascii_uppercase();
+        let quote = query.quote.trim().to_ascii_uppercase();
+        let mut v = Validator::new();
+        v.currency("base", &base);
+        v.currency("quote", &quote);
+        v.finish()?;
+
+        let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+        self.get_rate(&base, &quote, date).await
+    }
+
+    // 没有直接汇率时通过中间货币换算
+    async fn cross_rate(&self, base: &str, quote: &str, date: NaiveDate) -> Result<ExchangeRate, ServiceError> {
+        let unavailable = || ServiceError::ExchangeRateUnavailable(format!("{}/{} on {}", base, quote, date));
+        if base == PIVOT_CURRENCY || quote == PIVOT_CURRENCY {
+            return Err(unavailable());
+        }
+        let first = Box::pin(self.get_rate(base, PIVOT_CURRENCY, date)).await;
+        let second = Box::pin(self.get_rate(PIVOT_CURRENCY, quote, date)).await;
+        match (first, second) {
+            (Ok(first), Ok(second)) => Ok(ExchangeRate {
+                base_currency: base.to_string(),
+                quote_currency: quote.to_string(),
+                rate_date: first.rate_date.min(second.rate_date),
+                rate: first.rate.checked_mul(second.rate).ok_or_else(unavailable)?.round_dp(RATE_SCALE).normalize(),
+                source: "cross".to_string(),
+            }),
+            (Err(ServiceError::ExchangeRateUnavailable(_)), _) | (_, Err(ServiceError::ExchangeRateUnavailable(_))) => {
+                Err(unavailable())
+            }
+            (Err(err), _) | (_, Err(err)) => Err(err),
+        }
+    }
+
+    // 数据库中的直接汇率或反向汇率，取日期较近的一个
+    async fn find_stored_rate(&self, base: &str, quote: &str, date: NaiveDate) -> Result<Option<ExchangeRate>, ServiceError> {
+        let direct = self.state.storage.find_exchange_rate(base, quote, date).await?;
+        let inverse = self
+            .state
+            .storage
+            .find_exchange_rate(quote, base, date)
+            .await?
+            .filter(|inverse| !inverse.rate.is_zero())
+            .map(|inverse| ExchangeRate {
+                base_currency: base.to_string(),
+                quote_currency: quote.to_string(),
+                rate_date: inverse.rate_date,
+                rate: (Decimal::ONE / inverse.rate).round_dp(RATE_SCALE).normalize(),
+                source: inverse.source,
+            });
+        Ok(match (direct, inverse) {
+            (Some(direct), Some(inverse)) if inverse.rate_date > direct.rate_date => Some(inverse),
+            (Some(direct), _) => Some(direct),
+            (None, inverse) => inverse,
+        })
+    }
+}
+
+// 一次统计中用到的汇率，同一货币同一天只查询一次
+struct RateTable {
+    service: ExchangeRateService,
+    quote: String,
+    rates: HashMap<(String, NaiveDate), Decimal>,
+}
+
+impl RateTable {
+    fn new(service: ExchangeRateService, quote: &str) -> Self {
+        Self {
+            service,
+            quote: quote.to_string(),
+            rates: HashMap::new(),
+        }
+    }
+
+    async fn rate(&mut self, currency: &str, date: NaiveDate) -> Result<Decimal, ServiceError> {
+        if let Some(rate) = self.rates.get(&(currency.to_string(), date)) {
+            return Ok(*rate);
+        }
+        let rate = self.service.get_rate(currency, &self.quote, date).await?.rate;
+        self.rates.insert((currency.to_string(), date), rate);
+        Ok(rate)
+    }
+
+    // 无法获取汇率时返回 None，其他错误照常返回
+    async fn try_rate(&mut self, currency: &str, date: NaiveDate) -> Result<Option<Decimal>, ServiceError> {
+        match self.rate(currency, date).await {
+            Ok(rate) => Ok(Some(rate)),
+            Err(ServiceError::ExchangeRateUnavailable(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    // 按 date 的汇率折算 amount，不舍入；数据库中异常的汇率使乘积溢出时返回错误
+    async fn convert(&mut self, amount: Decimal, currency: &str, date: NaiveDate) -> Result<Decimal, ServiceError> {
+        let rate = self.rate(currency, date).await?;
+        amount.checked_mul(rate).ok_or_else(|| self.overflow(currency))
+    }
+
+    // 无法获取汇率或乘积溢出时返回 None
+    async fn try_convert(&mut self, amount: Decimal, currency: &str, date: NaiveDate) -> Result<Option<Decimal>, ServiceError> {
+        let rate = self.try_rate(currency, date).await?;
+        Ok(rate.and_then(|rate| amount.checked_mul(rate)))
+    }
+
+    // 把折算后按 scale 舍入的 amount 累加到 total
+    async fn accumulate(
+        &mut self,
+        total: &mut Decimal,
+        amount: Decimal,
+        currency: &str,
+        date: NaiveDate,
+        scale: u32,
+    ) -> Result<(), ServiceError> {
+        let converted = self.convert(amount, currency, date).await?.round_dp(scale);
+        *total = total.checked_add(converted).ok_or_else(|| self.overflow(currency))?;
+        Ok(())
+    }
+
+    fn overflow(&self, currency: &str) -> ServiceError {
+        ServiceError::Internal(format!("amount in {} overflows when converted to {}", currency, self.quote))
+    }
+}
+
+// 统计服务
+pub struct StatisticsService {
+    state: Arc<AppState>,
+}
+
+impl StatisticsService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    pub async fn get_financial_summary(&self, user_id: Uuid) -> Result<FinancialSummary, ServiceError> {
+        let user = UserService::new(self.state.clone()).get_user(user_id).await?;
+        let base_currency = user.base_currency;
+        let scale = currency_scale(&base_currency);
+        let mut rates = RateTable::new(ExchangeRateService::new(self.state.clone()), &base_currency);
+
+        // 某种货币没有汇率时跳过该货币的金额，其余货币照常合计
+        let mut unconverted_currencies = Vec::new();
+        let mut total_income = Decimal::ZERO;
+        let mut total_expense = Decimal::ZERO;
+        for total in self
+            .state
+            .storage
+            .sum_transactions_by_day(user_id, &TransactionFilter::default())
+            .await? {
+            let target = match total.transaction_type {
+                TransactionType::Income => &mut total_income,
+                TransactionType::Expense => &mut total_expense,
+                // 转账只是在自己的账户之间移动资金，不计入收支
+                TransactionType::Transfer | TransactionType::Investment => continue,
+            };
+            let converted = rates.try_convert(total.amount, &total.currency, total.date).await?;
+            match converted.and_then(|converted| target.checked_add(converted)) {
+                Some(sum) => *target = sum,
+                None => unconverted_currencies.push(total.currency),
+            }
+        }
+        let total_income = total_income.round_dp(scale).normalize();
+        let total_expense = total_expense.round_dp(scale).normalize();
+
+        // 账户余额按当天汇率折算
+        let today = Utc::now().date_naive();
+        let accounts = self.state.storage.list_accounts(user_id, i64::MAX, PageStart::Offset(0)).await?;
+        let mut account_balances = Vec::with_capacity(accounts.len());
+        let mut total_balance = Decimal::ZERO;
+        for account in accounts {
+            let mut converted = rates
+                .try_convert(account.balance, &account.currency, today)
+                .await?
+                .map(|converted| converted.round_dp(scale).normalize());
+            match converted.and_then(|converted| total_balance.checked_add(converted)) {
+                Some(sum) => total_balance = sum,
+                None => {
+                    converted = None;
+                    unconverted_currencies.push(account.currency.clone());
+                }
+            }
+            account_balances.push(AccountBalance {
+                account_id: account.id,
+                account_name: account.name,
+                converted_balance: converted,
+                balance: account.balance,
+                currency: account.currency,
+            });
+        }
+        unconverted_currencies.sort();
+        unconverted_currencies.dedup();
+
+        Ok(FinancialSummary {
+            base_currency,
+            total_income,
+            total_expense,
+            net_income: total_income - total_expense,
+            total_balance,
+            account_balances,
+            unconverted_currencies,
+        })
+    }
+
+    // TODO: 实现统计相关业务逻辑
+    // pub async fn get_monthly_report(&self, user_id: Uuid, year: i32, month: u32) -> Result<MonthlyReport, ServiceError>
+    // pub async fn get_category_analysis(&self, user_id: Uuid, start_date: DateTime<Utc>, end_date: DateTime<Utc>) -> Result<CategoryAnalysis, ServiceError>
+}
+
+// 服务错误类型
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error("Database error: {0}")]
+    Database(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("Validation failed")]
+    Validation(Vec<FieldError>),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Authentication failed")]
+    AuthenticationFailed,
+
+    #[error("Authorization failed")]
+    AuthorizationFailed,
+
+    #[error("Exchange rate unavailable: {0}")]
+    ExchangeRateUnavailable(String),
+
+    #[error("Internal server error: {0}")]
+    Internal(String),
+}
+
+impl ServiceError {
+    // 稳定的机器可读错误码，客户端依赖这些值，不要随意修改
+    pub fn code(&self) -> &'static str {
+        match self {
+            ServiceError::Database(_) | ServiceError::Internal(_) => "internal_error",
+            ServiceError::NotFound(_) => "not_found",
+            ServiceError::InvalidInput(_) => "invalid_input",
+            ServiceError::Validation(_) => "validation_failed",
+            ServiceError::Conflict(_) => "conflict",
+            ServiceError::AuthenticationFailed => "authentication_failed",
+            ServiceError::AuthorizationFailed => "authorization_failed",
+            ServiceError::ExchangeRateUnavailable(_) => "exchange_rate_unavailable",
+        }
+    }
+}
+
+impl From<StorageError> for ServiceError {
+    fn from(err: StorageError) -> Self {
+        match err {
+            StorageError::Conflict(_) => ServiceError::Conflict(err.to_string()),
+            StorageError::Sqlx(sqlx::Error::RowNotFound) => ServiceError::NotFound(err.to_string()),
+            StorageError::Sqlx(sqlx::Error::Database(ref db_err)) if db_err.is_unique_violation() => {
+                ServiceError::Conflict(db_err.message().to_string())
+            }
+            _ => ServiceError::Database(err.to_string()),
+        }
+    }
+}
+
+impl From<SessionError> for ServiceError {
+    fn from(err: SessionError) -> Self {
+        ServiceError::Internal(err.to_string())
+    }
+}
+
+impl From<RateError> for ServiceError {
+    fn from(err: RateError) -> Self {
+        ServiceError::Internal(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{MemoryStorage, SqliteStorage};
+    use crate::rates::MemoryRateCache;
+    use crate::session::MemorySessionStore;
+    use crate::validation::Validate;
+
+    fn test_state() -> Arc<AppState> {
+        test_state_with(Arc::new(MemoryStorage::new()))
+    }
+
+    fn test_state_with(storage: Arc<dyn Storage>) -> Arc<AppState> {
+        Arc::new(AppState::new(
+            AppConfig::default(),
+            storage,
+            Arc::new(MemorySessionStore::new()),
+            Arc::new(MemoryRateCache::new()),
+            None,
+        ))
+    }
+
+    // 直接写入存储，跳过注册时较慢的密码哈希
+    async fn test_user(state: &Arc<AppState>) -> Uuid {
+        let now = Utc::now();
+        let id = Uuid::new_v4();
+        let user = User {
+            id,
+            username: format!("user-{}", id.simple()),
+            email: format!("{}@example.com", id.simple()),
+            display_name: "User".to_string(),
+            avatar_url: None,
+            base_currency: "CNY".to_string(),
+            password_hash: String::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        state.storage.insert_user(&user).await.unwrap();
+        user.id
+    }
+
+    async fn test_account(state: &Arc<AppState>, user_id: Uuid, currency: &str, balance: &str) -> Account {
+        AccountService::new(state.clone())
+            .create_account(
+                user_id,
+                CreateAccountRequest {
+                    name: format!("{} account", currency),
+                    account_type: AccountType::BankCard,
+                    currency: currency.to_string(),
+                    initial_balance: Some(balance.parse().unwrap()),
+                    credit_limit: None,
+                    statement_closing_day: None,
+                    payment_due_day: None,
+                    loan: None,
+                },
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn test_card(state: &Arc<AppState>, user_id: Uuid, closing_day: u32, due_day: u32) -> Account {
+        AccountService::new(state.clone())
+            .create_account(
+                user_id,
+                CreateAccountRequest {
+                    name: "信用卡".to_string(),
+                    account_type: AccountType::CreditCard,
+                    currency: "CNY".to_string(),
+                    initial_balance: None,
+                    credit_limit: Some(dec("10000")),
+                    statement_closing_day: Some(closing_day),
+                    payment_due_day: Some(due_day),
+                    loan: None,
+                },
+            )
+            .await
+            .unwrap()
+    }
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    async fn test_transaction(state: &Arc<AppState>, user_id: Uuid, request: serde_json::Value) -> Transaction {
+        TransactionService::new(state.clone())
+            .create_transaction(user_id, serde_json::from_value(request).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn test_transfer(
+        state: &Arc<AppState>,
+        user_id: Uuid,
+        from: &Account,
+        to: &Account,
+        amount: &str,
+        to_amount: Option<&str>,
+    ) -> Transfer {
+        TransferService::new(state.clone())
+            .create_transfer(
+                user_id,
+                CreateTransferRequest {
+                    from_account_id: from.id,
+                    to_account_id: to.id,
+                    amount: dec(amount),
+                    to_amount: to_amount.map(dec),
+                    exchange_rate: None,
+                    fee: None,
+                    description: "转账".to_string(),
+                    notes: None,
+                    tags: None,
+                    transfer_date: None,
+                },
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn transactions_update_account_balance() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let account = test_account(&state, user_id, "CNY", "100").await;
+        let transactions = TransactionService::new(state.clone());
+        let expense = transactions
+            .create_transaction(
+                user_id,
+                CreateTransactionRequest {
+                    account_id: account.id,
+                    category_id: None,
+                    transaction_type: TransactionType::Expense,
+                    amount: dec("30.5"),
+                    description: "午饭".to_string(),
+                    notes: None,
+                    tags: None,
+                    transaction_date: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let accounts = AccountService::new(state.clone());
+        assert_eq!(accounts.get_account(user_id, account.id).await.unwrap().balance, dec("69.5"));
+        transactions.delete_transaction(user_id, expense.id).await.unwrap();
+        assert_eq!(accounts.get_account(user_id, account.id).await.unwrap().balance, dec("100"));
+    }
+
+    #[tokio::test]
+    async fn login_rejects_unknown_user() {
+        let state = test_state();
+        let result = AuthService::new(state)
+            .login(LoginRequest {
+                username: "nobody".to_string(),
+                password: "secret123".to_string(),
+            })
+            .await;
+        assert!(matches!(result, Err(ServiceError::AuthenticationFailed)));
+    }
+
+    #[tokio::test]
+    async fn deleting_an_account_removes_its_transfers() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let from = test_account(&state, user_id, "CNY", "100").await;
+        let to = test_account(&state, user_id, "CNY", "50").await;
+        let transfer = test_transfer(&state, user_id, &from, &to, "30", None).await;
+
+        let accounts = AccountService::new(state.clone());
+        assert_eq!(accounts.get_account(user_id, to.id).await.unwrap().balance, dec("80"));
+        accounts.delete_account(user_id, from.id).await.unwrap();
+        assert_eq!(accounts.get_account(user_id, to.id).await.unwrap().balance, dec("50"));
+        let result = TransferService::new(state.clone()).get_transfer(user_id, transfer.id).await;
+        assert!(matches!(result, Err(ServiceError::NotFound(_))));
+        let remaining = state.storage.list_transfer_legs(transfer.id).await.unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[tokio::test]
+    async fn same_currency_transfer_update_resets_to_amount() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let usd = test_account(&state, user_id, "USD", "100").await;
+        let cny = test_account(&state, user_id, "CNY", "0").await;
+        let usd_savings = test_account(&state, user_id, "USD", "0").await;
+        let transfer = test_transfer(&state, user_id, &usd, &cny, "10", Some("71.5")).await;
+
+        let transfers = TransferService::new(state.clone());
+        let update = |value| serde_json::from_value::<UpdateTransferRequest>(value).unwrap();
+        let updated = transfers
+            .update_transfer(user_id, transfer.id, update(serde_json::json!({ "to_account_id": usd_savings.id })))
+            .await
+            .unwrap();
+        assert_eq!(updated.to_amount, dec("10"));
+        let accounts = AccountService::new(state.clone());
+        assert_eq!(accounts.get_account(user_id, usd_savings.id).await.unwrap().balance, dec("10"));
+        assert_eq!(accounts.get_account(user_id, cny.id).await.unwrap().balance, dec("0"));
+
+        let result = transfers
+            .update_transfer(user_id, transfer.id, update(serde_json::json!({ "to_amount": "12" })))
+            .await;
+        assert!(matches!(result, Err(ServiceError::Validation(errors)) if errors[0].code == "amount_mismatch"));
+        let updated = transfers
+            .update_transfer(user_id, transfer.id, update(serde_json::json!({ "amount": "12", "to_amount": "12" })))
+            .await
+            .unwrap();
+        assert_eq!((updated.amount, updated.to_amount), (dec("12"), dec("12")));
+    }
+
+    #[tokio::test]
+    async fn changing_the_from_currency_requires_a_new_to_amount() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let cny = test_account(&state, user_id, "CNY", "1000").await;
+        let cny_savings = test_account(&state, user_id, "CNY", "0").await;
+        let eur = test_account(&state, user_id, "EUR", "500").await;
+        let transfer = test_transfer(&state, user_id, &cny, &cny_savings, "100", None).await;
+
+        let transfers = TransferService::new(state.clone());
+        let update = |value| serde_json::from_value::<UpdateTransferRequest>(value).unwrap();
+        let result = transfers
+            .update_transfer(user_id, transfer.id, update(serde_json::json!({ "from_account_id": eur.id })))
+            .await;
+        assert!(matches!(
+            result,
+            Err(ServiceError::Validation(errors)) if errors[0].field == "to_amount" && errors[0].code == "required"
+        ));
+
+        let updated = transfers
+            .update_transfer(
+                user_id,
+                transfer.id,
+                update(serde_json::json!({ "from_account_id": eur.id, "exchange_rate": "7.8" })),
+            )
+            .await
+            .unwrap();
+        assert_eq!((updated.currency.as_str(), updated.amount), ("EUR", dec("100")));
+        assert_eq!((updated.to_amount, updated.exchange_rate), (dec("780"), dec("7.8")));
+        let accounts = AccountService::new(state.clone());
+        assert_eq!(accounts.get_account(user_id, cny.id).await.unwrap().balance, dec("1000"));
+        assert_eq!(accounts.get_account(user_id, eur.id).await.unwrap().balance, dec("400"));
+        assert_eq!(accounts.get_account(user_id, cny_savings.id).await.unwrap().balance, dec("780"));
+    }
+
+    #[tokio::test]
+    async fn oversized_exchange_rate_is_a_validation_error() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let usd = test_account(&state, user_id, "USD", "100").await;
+        let cny = test_account(&state, user_id, "CNY", "0").await;
+        let request = serde_json::from_value::<CreateTransferRequest>(serde_json::json!({
+            "from_account_id": usd.id,
+            "to_account_id": cny.id,
+            "amount": "10",
+            "exchange_rate": Decimal::MAX.to_string(),
+            "description": "转账",
+        }))
+        .unwrap();
+
+        let mut v = Validator::new();
+        request.validate(&mut v);
+        assert!(matches!(v.finish(), Err(ServiceError::Validation(errors)) if errors[0].code == "too_large"));
+        // 服务层不依赖接口校验，乘法溢出时同样返回字段错误
+        let result = TransferService::new(state.clone()).create_transfer(user_id, request).await;
+        assert!(matches!(
+            result,
+            Err(ServiceError::Validation(errors)) if errors[0].field == "exchange_rate" && errors[0].code == "too_large"
+        ));
+    }
+
+    #[tokio::test]
+    async fn summary_skips_currencies_without_rates() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let cny = test_account(&state, user_id, "CNY", "100").await;
+        let usd = test_account(&state, user_id, "USD", "50").await;
+        let transactions = TransactionService::new(state.clone());
+        for (account, amount) in [(&cny, "20"), (&usd, "5")] {
+            transactions
+                .create_transaction(
+                    user_id,
+                    CreateTransactionRequest {
+                        account_id: account.id,
+                        category_id: None,
+                        transaction_type: TransactionType::Income,
+                        amount: dec(amount),
+                        description: "收入".to_string(),
+                        notes: None,
+                        tags: None,
+                        transaction_date: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let summary = StatisticsService::new(state.clone()).get_financial_summary(user_id).await.unwrap();
+        assert_eq!(summary.total_income, dec("20"));
+        assert_eq!(summary.total_balance, dec("120"));
+        assert_eq!(summary.unconverted_currencies, vec!["USD".to_string()]);
+        let balance = |id| summary.account_balances.iter().find(|b| b.account_id == id).unwrap();
+        assert_eq!(balance(cny.id).converted_balance, Some(dec("120")));
+        assert_eq!(balance(usd.id).converted_balance, None);
+    }
+
+    #[tokio::test]
+    async fn summary_flags_rates_that_overflow() {
+        assert!(is_plausible_rate(dec("7.1")) && is_plausible_rate(dec("0.000000001")));
+        assert!(!is_plausible_rate(dec("1000000001")) && !is_plausible_rate(dec("0.0000000009")));
+
+        // 数据库中已有的异常汇率不会使统计 panic，对应货币列为无法折算
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let cny = test_account(&state, user_id, "CNY", "100").await;
+        let usd = test_account(&state, user_id, "USD", "50").await;
+        state
+            .storage
+            .upsert_exchange_rates(&[ExchangeRate {
+                base_currency: "USD".to_string(),
+                quote_currency: "CNY".to_string(),
+                rate_date: Utc::now().date_naive(),
+                rate: dec("1e28"),
+                source: "manual".to_string(),
+            }])
+            .await
+            .unwrap();
+
+        let summary = StatisticsService::new(state.clone()).get_financial_summary(user_id).await.unwrap();
+        assert_eq!(summary.total_balance, dec("100"));
+        assert_eq!(summary.unconverted_currencies, vec!["USD".to_string()]);
+        let balance = |id| summary.account_balances.iter().find(|b| b.account_id == id).unwrap();
+        assert_eq!(balance(cny.id).converted_balance, Some(dec("100")));
+        assert_eq!(balance(usd.id).converted_balance, None);
+    }
+
+    #[test]
+    fn cursors_round_trip_within_their_scope() {
+        let id = Uuid::new_v4();
+        let time = Cursor {
+            value: SortValue::Time("2026-05-01T08:30:00.123456Z".parse().unwrap()),
+            id,
+        };
+        let amount = Cursor { value: SortValue::Amount(dec("-12.50")), id };
+        let scope = "transactions:TransactionDate:Desc";
+        assert_eq!(decode_cursor(&encode_cursor(scope, time), scope, true), Some(time));
+        assert_eq!(decode_cursor(&encode_cursor("amount", amount), "amount", false), Some(amount));
+
+        // 其他列表或排序方式的游标、排序值类型不符、被篡改的游标都无效
+        let token = encode_cursor(scope, time);
+        assert_eq!(decode_cursor(&token, "transactions:TransactionDate:Asc", true), None);
+        assert_eq!(decode_cursor(&token, scope, false), None);
+        assert_eq!(decode_cursor(&format!("{}!", token), scope, true), None);
+        let forged = URL_SAFE_NO_PAD.encode(format!(r#"{{"scope":"{}","value":"yesterday","id":"{}"}}"#, scope, id));
+        assert_eq!(decode_cursor(&forged, scope, true), None);
+        assert_eq!(decode_cursor(&URL_SAFE_NO_PAD.encode("{}"), scope, true), None);
+
+        let pagination = |page, cursor: &str| PaginationQuery {
+            page,
+            limit: None,
+            cursor: Some(cursor.to_string()),
+        };
+        let error_of = |result: Result<PageRequest, ServiceError>| match result {
+            Err(ServiceError::Validation(errors)) => (errors[0].field.clone(), errors[0].code.clone()),
+            _ => panic!("expected a validation error"),
+        };
+        assert_eq!(
+            error_of(PageRequest::parse(&pagination(None, "garbage"), scope.to_string(), true)),
+            ("cursor".to_string(), "invalid".to_string())
+        );
+        assert_eq!(
+            error_of(PageRequest::parse(&pagination(Some(2), &token), scope.to_string(), true)),
+            ("page".to_string(), "not_allowed".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn cursor_pages_break_ties_by_id() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let account = test_account(&state, user_id, "CNY", "0").await;
+        for _ in 0..7 {
+            test_transaction(
+                &state,
+                user_id,
+                serde_json::json!({
+                    "account_id": account.id,
+                    "transaction_type": "Expense",
+                    "amount": "9.9",
+                    "description": "早餐",
+                    "transaction_date": "2026-05-01T08:00:00Z",
+                }),
+            )
+            .await;
+        }
+
+        let transactions = TransactionService::new(state.clone());
+        for sort in [r#""sort_by":"transaction_date""#, r#""sort_by":"amount","sort_order":"asc""#] {
+            let query = |extra: &str| {
+                let json = format!("{{{},{}}}", sort, extra);
+                serde_json::from_str::<TransactionQuery>(&json).unwrap()
+            };
+            let all = transactions.get_transactions(user_id, query(r#""limit":100"#)).await.unwrap();
+            let mut ids: Vec<Uuid> = all.data.iter().map(|t| t.id).collect();
+            assert_eq!(ids.len(), 7);
+
+            let mut paged = Vec::new();
+            let mut page = transactions.get_transactions(user_id, query(r#""limit":3,"page":1"#)).await.unwrap();
+            loop {
+                paged.extend(page.data.iter().map(|t| t.id));
+                let Some(cursor) = page.next_cursor else { break };
+                page = transactions
+                    .get_transactions(user_id, query(&format!(r#""limit":3,"cursor":"{}""#, cursor)))
+                    .await
+                    .unwrap();
+            }
+            // 排序值全部相同时按 id 继续，不重复也不遗漏
+            assert_eq!(paged, ids);
+            ids.sort();
+            ids.dedup();
+            assert_eq!(ids.len(), 7);
+        }
+    }
+
+    #[tokio::test]
+    async fn budget_rollover_carries_underspend_only() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let account = test_account(&state, user_id, "CNY", "10000").await;
+        for (date, amount) in [("2026-01-20", "600"), ("2026-02-10", "1500"), ("2026-03-10", "300")] {
+            test_transaction(
+                &state,
+                user_id,
+                serde_json::json!({
+                    "account_id": account.id,
+                    "transaction_type": "Expense",
+                    "amount": amount,
+                    "description": "支出",
+                    "transaction_date": format!("{}T12:00:00Z", date),
+                }),
+            )
+            .await;
+        }
+
+        let budgets = BudgetService::new(state.clone());
+        let create = |rollover: bool| {
+            serde_json::from_value::<CreateBudgetRequest>(serde_json::json!({
+                "name": "日常",
+                "period": "Monthly",
+                "amount": "1000",
+                "rollover": rollover,
+                "start_date": "2026-01-15",
+            }))
+            .unwrap()
+        };
+        let budget = budgets.create_budget(user_id, create(true)).await.unwrap();
+        let status = |budget_id, date: &str| {
+            let budgets = &budgets;
+            let query = BudgetStatusQuery { date: Some(date.parse().unwrap()) };
+            async move { budgets.get_budget_status(user_id, budget_id, query).await.unwrap() }
+        };
+
+        // 第一个周期从开始日期算起，期中按已过天数推算整个周期的支出
+        let january = status(budget.id, "2026-01-20").await;
+        assert_eq!(
+            (january.period_start.to_string(), january.period_end.to_string()),
+            ("2026-01-15".to_string(), "2026-01-31".to_string())
+        );
+        assert_eq!((january.spent, january.projected_spend), (dec("600"), dec("1700")));
+        assert!(!january.is_overspent && january.is_projected_overspent);
+
+        // 一月结余 400 结转到二月
+        let february = status(budget.id, "2026-02-14").await;
+        assert_eq!(
+            (february.rolled_over, february.available, february.spent),
+            (dec("400"), dec("1400"), dec("1500"))
+        );
+        assert_eq!(february.remaining, dec("-100"));
+        assert!(february.is_overspent);
+
+        // 二月超支不结转到三月
+        let march = status(budget.id, "2026-03-16").await;
+        assert_eq!(
+            (march.period_start.to_string(), march.period_end.to_string()),
+            ("2026-03-01".to_string(), "2026-03-31".to_string())
+        );
+        assert_eq!((march.rolled_over, march.available, march.spent), (dec("0"), dec("1000"), dec("300")));
+        assert_eq!(march.projected_spend, dec("581.25"));
+        assert!(!march.is_projected_overspent);
+
+        let plain = budgets.create_budget(user_id, create(false)).await.unwrap();
+        assert_eq!(status(plain.id, "2026-02-14").await.rolled_over, dec("0"));
+    }
+
+    #[test]
+    fn budget_periods_are_clipped_to_the_budget_dates() {
+        let budget = |period, start: &str, end: Option<&str>| Budget {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            name: "预算".to_string(),
+            category_id: None,
+            tag: None,
+            period,
+            amount: dec("100"),
+            currency: "CNY".to_string(),
+            rollover: false,
+            start_date: start.parse().unwrap(),
+            end_date: end.map(|end| end.parse().unwrap()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let period = |budget: &Budget, date: &str| {
+            let (start, end) = budget_period(budget, date.parse().unwrap());
+            (start.to_string(), end.to_string())
+        };
+        let pair = |start: &str, end: &str| (start.to_string(), end.to_string());
+
+        // 2026-05-06 是星期三，周预算从星期一开始
+        let weekly = budget(BudgetPeriod::Weekly, "2026-01-01", Some("2026-05-08"));
+        assert_eq!(period(&weekly, "2026-05-06"), pair("2026-05-04", "2026-05-08"));
+        assert_eq!(period(&weekly, "2026-01-01"), pair("2026-01-01", "2026-01-04"));
+        let monthly = budget(BudgetPeriod::Monthly, "2028-01-31", None);
+        assert_eq!(period(&monthly, "2028-02-29"), pair("2028-02-01", "2028-02-29"));
+        assert_eq!(period(&monthly, "2028-12-31"), pair("2028-12-01", "2028-12-31"));
+        let yearly = budget(BudgetPeriod::Yearly, "2026-07-01", None);
+        assert_eq!(period(&yearly, "2027-03-01"), pair("2027-01-01", "2027-12-31"));
+        let custom = budget(BudgetPeriod::Custom, "2026-03-10", Some("2026-04-20"));
+        assert_eq!(period(&custom, "2026-04-01"), pair("2026-03-10", "2026-04-20"));
+    }
+
+    #[tokio::test]
+    async fn statements_split_payments_at_the_closing_date() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let debit = test_account(&state, user_id, "CNY", "10000").await;
+        // 账单日为 31 日，二月的账单日为月末 28 日
+        let card = test_card(&state, user_id, 31, 20).await;
+        for (date, amount) in [("2026-01-25", "1000"), ("2026-02-05", "300")] {
+            test_transaction(
+                &state,
+                user_id,
+                serde_json::json!({
+                    "account_id": card.id,
+                    "transaction_type": "Expense",
+                    "amount": amount,
+                    "description": "刷卡",
+                    "transaction_date": format!("{}T12:00:00Z", date),
+                }),
+            )
+            .await;
+        }
+        let transfers = TransferService::new(state.clone());
+        let pay = |date: &str, amount: &str| {
+            serde_json::from_value::<CreateTransferRequest>(serde_json::json!({
+                "from_account_id": debit.id,
+                "to_account_id": card.id,
+                "amount": amount,
+                "description": "还款",
+                "transfer_date": format!("{}T12:00:00Z", date),
+            }))
+            .unwrap()
+        };
+        // 账单日之前的还款计入本期账单，之后的计入已还金额
+        transfers.create_transfer(user_id, pay("2026-01-28", "200")).await.unwrap();
+
+        let accounts = AccountService::new(state.clone());
+        let statement = |date: &str| {
+            let accounts = &accounts;
+            let query = StatementQuery { date: Some(date.parse().unwrap()) };
+            async move { accounts.get_statement(user_id, card.id, query).await.unwrap() }
+        };
+        let january = statement("2026-02-05").await;
+        assert_eq!(
+            (january.period_start.to_string(), january.closing_date.to_string(), january.due_date.unwrap().to_string()),
+            ("2026-01-01".to_string(), "2026-01-31".to_string(), "2026-02-20".to_string())
+        );
+        assert_eq!((january.statement_balance, january.paid_amount), (dec("800"), dec("0")));
+        assert_eq!((january.minimum_payment, january.unbilled_balance), (dec("80"), dec("300")));
+        assert_eq!(january.status, StatementStatus::Unpaid);
+
+        transfers.create_transfer(user_id, pay("2026-02-10", "500")).await.unwrap();
+        let january = statement("2026-02-15").await;
+        assert_eq!((january.paid_amount, january.remaining_balance), (dec("500"), dec("300")));
+        assert_eq!((january.minimum_payment, january.current_balance), (dec("0"), dec("600")));
+        assert_eq!(january.available_credit, Some(dec("9400")));
+        assert_eq!(statement("2026-02-25").await.status, StatementStatus::Overdue);
+
+        // 二月账单跨过月末：2 月 1 日到 28 日
+        let february = statement("2026-03-01").await;
+        assert_eq!(
+            (february.period_start.to_string(), february.closing_date.to_string()),
+            ("2026-02-01".to_string(), "2026-02-28".to_string())
+        );
+        assert_eq!((february.statement_balance, february.minimum_payment), (dec("600"), dec("60")));
+        transfers.create_transfer(user_id, pay("2026-03-03", "600")).await.unwrap();
+        let february = statement("2026-03-05").await;
+        assert_eq!((february.remaining_balance, february.minimum_payment), (dec("0"), dec("0")));
+        assert_eq!(february.status, StatementStatus::Paid);
+    }
+
+    #[test]
+    fn installment_remainder_goes_to_the_last_period() {
+        let mut plan = InstallmentPlan {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            account_id: Uuid::nil(),
+            transaction_id: Uuid::nil(),
+            principal: dec("1000"),
+            periods: 3,
+            fee_per_period: dec("6"),
+            first_period_date: "2026-01-31".parse().unwrap(),
+            recurring_id: None,
+            paid_off_date: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let periods = |plan: &InstallmentPlan| {
+            installment_schedule(plan, 2)
+                .into_iter()
+                .map(|period| (period.date.to_string(), period.principal, period.fee))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            periods(&plan),
+            vec![
+                ("2026-01-31".to_string(), dec("333.33"), dec("6")),
+                ("2026-02-28".to_string(), dec("333.33"), dec("6")),
+                ("2026-03-31".to_string(), dec("333.34"), dec("6")),
+            ]
+        );
+
+        // 提前还清后剩余本金合并到还清当天，不再收手续费
+        plan.paid_off_date = Some("2026-02-10".parse().unwrap());
+        assert_eq!(
+            periods(&plan),
+            vec![
+                ("2026-01-31".to_string(), dec("333.33"), dec("6")),
+                ("2026-02-10".to_string(), dec("666.67"), dec("0")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn installment_fees_are_charged_until_payoff() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let card = test_card(&state, user_id, 5, 25).await;
+        let today = Utc::now().date_naive();
+        let purchase_date = today - Months::new(2);
+        let purchase = test_transaction(
+            &state,
+            user_id,
+            serde_json::json!({
+                "account_id": card.id,
+                "transaction_type": "Expense",
+                "amount": "1000",
+                "description": "手机",
+                "transaction_date": purchase_date.and_time(NaiveTime::MIN).and_utc(),
+            }),
+        )
+        .await;
+
+        let installments = InstallmentService::new(state.clone());
+        let request = CreateInstallmentPlanRequest {
+            periods: 6,
+            fee_per_period: None,
+            fee_rate: Some(dec("0.006")),
+            first_period_date: None,
+        };
+        let details = installments.create_installment(user_id, purchase.id, request).await.unwrap();
+        let recurring_id = details.plan.recurring_id.unwrap();
+        // 第一期为消费当天，到今天已入账三期，已到期的手续费立即补记
+        assert_eq!(details.plan.fee_per_period, dec("6"));
+        assert_eq!(details.billed_periods, 3);
+        assert_eq!((details.remaining_principal, details.remaining_fees), (dec("499.99"), dec("18")));
+        assert_eq!(details.schedule.last().unwrap().principal, dec("166.65"));
+        let fees = |state: &Arc<AppState>| {
+            let filter = TransactionFilter {
+                account_ids: vec![card.id],
+                ..Default::default()
+            };
+            let state = state.clone();
+            async move {
+                state
+                    .storage
+                    .list_transactions(user_id, &filter, TransactionSort::default(), i64::MAX, PageStart::Offset(0))
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .filter(|transaction| transaction.description == "手机 分期手续费")
+                    .map(|transaction| transaction.amount)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(fees(&state).await, vec![dec("6"); 3]);
+
+        let payoff = |date: NaiveDate| PayoffInstallmentPlanRequest { date: Some(date) };
+        let error_code = |result: Result<InstallmentPlanDetails, ServiceError>| match result {
+            Err(ServiceError::Validation(errors)) => errors[0].code.clone(),
+            _ => panic!("expected a validation error"),
+        };
+        let plan_id = details.plan.id;
+        assert_eq!(
+            error_code(installments.payoff_installment(user_id, plan_id, payoff(today + Duration::days(1))).await),
+            "future"
+        );
+        assert_eq!(
+            error_code(installments.payoff_installment(user_id, plan_id, payoff(purchase_date)).await),
+            "before_billed_period"
+        );
+
+        let paid_off = installments.payoff_installment(user_id, plan_id, payoff(today)).await.unwrap();
+        assert_eq!(paid_off.plan.recurring_id, None);
+        assert_eq!((paid_off.remaining_principal, paid_off.remaining_fees), (dec("0"), dec("0")));
+        let last = paid_off.schedule.last().unwrap();
+        assert_eq!((last.number, last.date, last.principal, last.fee), (4, today, dec("499.99"), dec("0")));
+        // 手续费的重复规则已删除，已记入的手续费保留，之后不再生成
+        assert!(state.storage.find_recurring(recurring_id).await.unwrap().is_none());
+        RecurringTransactionService::new(state.clone())
+            .materialize_due(today + Duration::days(120))
+            .await
+            .unwrap();
+        assert_eq!(fees(&state).await.len(), 3);
+        assert_eq!(
+            error_code(installments.payoff_installment(user_id, plan_id, payoff(today)).await),
+            "finished"
+        );
+    }
+
+    // 临时文件中的 SQLite 数据库，测试结束后由调用方删除
+    async fn sqlite_state() -> (Arc<AppState>, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("your-wallet-test-{}.db", Uuid::new_v4()));
+        let storage = SqliteStorage::connect(&format!("sqlite://{}", path.display())).await.unwrap();
+        storage.run_migrations().await.unwrap();
+        (test_state_with(Arc::new(storage)), path)
+    }
+
+    // 两个后端写入相同的交易，返回用户、第二个账户和分类的 id
+    async fn seed_filter_transactions(state: &Arc<AppState>) -> (Uuid, Uuid, Uuid) {
+        let user_id = test_user(state).await;
+        let first = test_account(state, user_id, "CNY", "10000").await;
+        let second = test_account(state, user_id, "CNY", "10000").await;
+        let category = CategoryService::new(state.clone())
+            .create_category(
+                user_id,
+                CreateCategoryRequest {
+                    name: "咖啡".to_string(),
+                    icon: None,
+                    color: None,
+                    transaction_type: TransactionType::Expense,
+                    parent_id: None,
+                },
+            )
+            .await
+            .unwrap();
+        let rows = [
+            (&first, Some(category.id), "Expense", "10", "Coffee 100% arabica", None, vec!["food"], 1),
+            (&first, None, "Expense", "25.5", "lunch_box", Some(r"team\lunch"), vec!["food", "work"], 2),
+            (&second, None, "Income", "1000", "Salary", Some("May"), vec!["work"], 3),
+            (&second, None, "Expense", "99.99", "COFFEE beans 咖啡豆", Some("50% off"), vec![], 4),
+            (&first, None, "Expense", "5", "lunchXbox", None, vec![], 5),
+        ];
+        for (account, category_id, transaction_type, amount, description, notes, tags, day) in rows {
+            test_transaction(
+                state,
+                user_id,
+                serde_json::json!({
+                    "account_id": account.id,
+                    "category_id": category_id,
+                    "transaction_type": transaction_type,
+                    "amount": amount,
+                    "description": description,
+                    "notes": notes,
+                    "tags": tags,
+                    "transaction_date": format!("2026-05-0{}T12:00:00Z", day),
+                }),
+            )
+            .await;
+        }
+        (user_id, second.id, category.id)
+    }
+
+    #[tokio::test]
+    async fn transaction_filters_agree_across_backends() {
+        let (sqlite, path) = sqlite_state().await;
+        let backends = [test_state(), sqlite];
+
+        let mut seeded = Vec::new();
+        for state in &backends {
+            seeded.push(seed_filter_transactions(state).await);
+        }
+        // {second} 和 {category} 替换为各后端中对应的 id
+        let cases = [
+            (r#"{"q":"%"}"#, vec!["COFFEE beans 咖啡豆", "Coffee 100% arabica"]),
+            (r#"{"q":"_"}"#, vec!["lunch_box"]),
+            (r#"{"q":"\\"}"#, vec!["lunch_box"]),
+            (r#"{"q":"coffee"}"#, vec!["COFFEE beans 咖啡豆", "Coffee 100% arabica"]),
+            (r#"{"q":"coffee 咖啡"}"#, vec!["COFFEE beans 咖啡豆"]),
+            (r#"{"q":"LUNCH"}"#, vec!["lunchXbox", "lunch_box"]),
+            (r#"{"tags":"food,work"}"#, vec!["Salary", "lunch_box", "Coffee 100% arabica"]),
+            (r#"{"tags":"food,work","tag_match":"all"}"#, vec!["lunch_box"]),
+            (r#"{"account_ids":"{second}"}"#, vec!["COFFEE beans 咖啡豆", "Salary"]),
+            (r#"{"category_ids":"{category}"}"#, vec!["Coffee 100% arabica"]),
+            (r#"{"transaction_type":"Income"}"#, vec!["Salary"]),
+            (
+                r#"{"min_amount":"10","max_amount":"99.99","sort_by":"amount"}"#,
+                vec!["COFFEE beans 咖啡豆", "lunch_box", "Coffee 100% arabica"],
+            ),
+            (
+                r#"{"start_date":"2026-05-02T00:00:00Z","end_date":"2026-05-04T00:00:00Z"}"#,
+                vec!["Salary", "lunch_box"],
+            ),
+        ];
+        for (query, expected) in cases {
+            for (state, (user_id, second, category)) in backends.iter().zip(&seeded) {
+                let query = query.replace("{second}", &second.to_string()).replace("{category}", &category.to_string());
+                let page = TransactionService::new(state.clone())
+                    .get_transactions(*user_id, serde_json::from_str(&query).unwrap())
+                    .await
+                    .unwrap();
+                let descriptions: Vec<&str> = page.data.iter().map(|t| t.description.as_str()).collect();
+                assert_eq!(descriptions, expected, "{}", query);
+                assert_eq!(page.total, Some(expected.len() as u64), "{}", query);
+            }
+        }
+        let _ = std::fs::remove_file(path);
+    }
+
+    async fn expense_category(
+        state: &Arc<AppState>,
+        user_id: Uuid,
+        name: &str,
+        parent_id: Option<Uuid>,
+    ) -> Result<Category, ServiceError> {
+        CategoryService::new(state.clone())
+            .create_category(
+                user_id,
+                CreateCategoryRequest {
+                    name: name.to_string(),
+                    icon: None,
+                    color: None,
+                    transaction_type: TransactionType::Expense,
+                    parent_id,
+                },
+            )
+            .await
+    }
+
+    fn validation_code<T: std::fmt::Debug>(result: Result<T, ServiceError>) -> String {
+        match result {
+            Err(ServiceError::Validation(errors)) => errors[0].code.clone(),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn categories_are_at_most_two_levels_deep() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let service = CategoryService::new(state.clone());
+        let parent = expense_category(&state, user_id, "日常", None).await.unwrap();
+        let child = expense_category(&state, user_id, "早餐", Some(parent.id)).await.unwrap();
+        let other = expense_category(&state, user_id, "出行", None).await.unwrap();
+
+        let grandchild = expense_category(&state, user_id, "豆浆", Some(child.id)).await;
+        assert_eq!(validation_code(grandchild), "too_deep");
+
+        let move_to = |parent_id: Uuid| UpdateCategoryRequest {
+            name: None,
+            icon: None,
+            color: None,
+            parent_id: Some(Some(parent_id)),
+            is_archived: None,
+        };
+        // 上级分类挂到自己的子分类下会形成环
+        let cycle = service.update_category(user_id, parent.id, move_to(child.id)).await;
+        assert_eq!(validation_code(cycle), "too_deep");
+        let itself = service.update_category(user_id, parent.id, move_to(parent.id)).await;
+        assert_eq!(validation_code(itself), "invalid");
+        let with_children = service.update_category(user_id, parent.id, move_to(other.id)).await;
+        assert_eq!(validation_code(with_children), "too_deep");
+
+        let moved = service.update_category(user_id, child.id, move_to(other.id)).await.unwrap();
+        assert_eq!(moved.parent_id, Some(other.id));
+        // 子分类沿用上级分类的图标和颜色
+        assert_eq!(child.icon, parent.icon);
+    }
+
+    #[tokio::test]
+    async fn archiving_a_category_archives_its_children() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let service = CategoryService::new(state.clone());
+        let parent = expense_category(&state, user_id, "日常", None).await.unwrap();
+        let child = expense_category(&state, user_id, "早餐", Some(parent.id)).await.unwrap();
+
+        service.archive_category(user_id, parent.id).await.unwrap();
+        let visible = service.get_categories(user_id, CategoryQuery::default(), Locale::ZhCn).await.unwrap();
+        assert!(visible.iter().all(|c| c.id != parent.id && c.id != child.id));
+        let all = CategoryQuery {
+            include_archived: Some(true),
+            ..Default::default()
+        };
+        let archived = service.get_categories(user_id, all, Locale::ZhCn).await.unwrap();
+        assert!(archived.iter().filter(|c| c.id == parent.id || c.id == child.id).all(|c| c.is_archived));
+
+        // 归档后可以重新使用同名分类，但不能再作为上级分类
+        expense_category(&state, user_id, "日常", None).await.unwrap();
+        let under_archived = expense_category(&state, user_id, "午餐", Some(parent.id)).await;
+        assert_eq!(validation_code(under_archived), "archived");
+        // 系统分类不能归档
+        let system = Uuid::from_u128(0x101);
+        assert!(matches!(
+            service.archive_category(user_id, system).await,
+            Err(ServiceError::AuthorizationFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn merging_a_category_moves_its_transactions_and_children() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let account = test_account(&state, user_id, "CNY", "1000").await;
+        let service = CategoryService::new(state.clone());
+        let source = expense_category(&state, user_id, "外卖", None).await.unwrap();
+        let source_child = expense_category(&state, user_id, "夜宵", Some(source.id)).await.unwrap();
+        let target = expense_category(&state, user_id, "吃饭", None).await.unwrap();
+        let target_child = expense_category(&state, user_id, "午餐", Some(target.id)).await.unwrap();
+        let mut moved = Vec::new();
+        for amount in ["12", "30"] {
+            let transaction = test_transaction(
+                &state,
+                user_id,
+                serde_json::json!({
+                    "account_id": account.id,
+                    "category_id": source.id,
+                    "transaction_type": "Expense",
+                    "amount": amount,
+                    "description": "外卖",
+                    "transaction_date": "2026-05-01T12:00:00Z",
+                }),
+            )
+            .await;
+            moved.push(transaction.id);
+        }
+        let untouched = test_transaction(
+            &state,
+            user_id,
+            serde_json::json!({
+                "account_id": account.id,
+                "category_id": source_child.id,
+                "transaction_type": "Expense",
+                "amount": "8",
+                "description": "夜宵",
+                "transaction_date": "2026-05-01T23:00:00Z",
+            }),
+        )
+        .await;
+
+        let merge = |target_id| MergeCategoryRequest { target_id };
+        let into_self = service.merge_category(user_id, source.id, merge(source.id), Locale::ZhCn).await;
+        assert_eq!(validation_code(into_self), "same_category");
+        // 来源分类有子分类时只能合并到一级分类
+        let into_child = service
+            .merge_category(user_id, source.id, merge(target_child.id), Locale::ZhCn)
+            .await;
+        assert_eq!(validation_code(into_child), "too_deep");
+
+        let response = service
+            .merge_category(user_id, source.id, merge(target.id), Locale::ZhCn)
+            .await
+            .unwrap();
+        assert_eq!(response.moved_transactions, 2);
+        for id in moved {
+            let transaction = state.storage.find_transaction(id).await.unwrap().unwrap();
+            assert_eq!(transaction.category_id, Some(target.id));
+        }
+        let untouched = state.storage.find_transaction(untouched.id).await.unwrap().unwrap();
+        assert_eq!(untouched.category_id, Some(source_child.id));
+        let source = state.storage.find_category(source.id).await.unwrap().unwrap();
+        assert!(source.is_archived);
+        let source_child = state.storage.find_category(source_child.id).await.unwrap().unwrap();
+        assert_eq!(source_child.parent_id, Some(target.id));
+        assert!(!source_child.is_archived);
+    }
+
+    #[tokio::test]
+    async fn seeded_catalog_ids_are_stable_across_locales_and_backends() {
+        let (sqlite, path) = sqlite_state().await;
+        let mut catalogs = Vec::new();
+        for state in [test_state(), sqlite] {
+            let user_id = test_user(&state).await;
+            let service = CategoryService::new(state.clone());
+            for locale in [Locale::ZhCn, Locale::En] {
+                let catalog = service.get_catalog(user_id, locale).await.unwrap();
+                let mut entries: Vec<(Uuid, String)> = catalog.categories.into_iter().map(|c| (c.id, c.name)).collect();
+                entries.sort();
+                catalogs.push((locale, catalog.version, entries));
+            }
+        }
+        let _ = std::fs::remove_file(path);
+
+        let (_, version, zh_cn) = &catalogs[0];
+        let (_, _, en) = &catalogs[1];
+        assert_eq!(*version, 1);
+        assert_eq!(zh_cn.len(), 22);
+        assert!(zh_cn.contains(&(Uuid::from_u128(0x101), "餐饮".to_string())));
+        assert!(en.contains(&(Uuid::from_u128(0x101), "Food & Dining".to_string())));
+        // 各语言的分类 id 相同，内存后端与迁移脚本中的目录一致
+        let ids = |entries: &[(Uuid, String)]| entries.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        assert_eq!(ids(zh_cn), ids(en));
+        assert_eq!(catalogs[2], catalogs[0]);
+        assert_eq!(catalogs[3], catalogs[1]);
+    }
+
+    #[tokio::test]
+    async fn merging_tags_deduplicates_transactions_with_both_tags() {
+        let (sqlite, path) = sqlite_state().await;
+        for state in [test_state(), sqlite] {
+            let user_id = test_user(&state).await;
+            let account = test_account(&state, user_id, "CNY", "1000").await;
+            let mut transactions = Vec::new();
+            for (transaction_type, amount, tags) in [
+                ("Expense", "10", vec!["咖啡", "早餐"]),
+                ("Expense", "20", vec!["coffee", "咖啡"]),
+                ("Expense", "5", vec!["coffee"]),
+                ("Income", "100", vec!["coffee"]),
+            ] {
+                let transaction = test_transaction(
+                    &state,
+                    user_id,
+                    serde_json::json!({
+                        "account_id": account.id,
+                        "transaction_type": transaction_type,
+                        "amount": amount,
+                        "description": "标签",
+                        "tags": tags,
+                        "transaction_date": "2026-05-01T12:00:00Z",
+                    }),
+                )
+                .await;
+                transactions.push(transaction.id);
+            }
+            let service = TagService::new(state.clone());
+
+            let rename = |name: &str| RenameTagRequest { name: name.to_string() };
+            let renamed = service.rename_tag(user_id, "早餐", rename(" 早饭 ")).await.unwrap();
+            assert_eq!((renamed.tag.name.as_str(), renamed.updated_transactions), ("早饭", 1));
+            // 重命名为已有标签需要使用合并
+            assert_eq!(validation_code(service.rename_tag(user_id, "coffee", rename("咖啡")).await), "already_exists");
+            assert!(matches!(
+                service.rename_tag(user_id, "早餐", rename("午餐")).await,
+                Err(ServiceError::NotFound(_))
+            ));
+
+            let merge = |target: &str| MergeTagRequest { target: target.to_string() };
+            assert_eq!(validation_code(service.merge_tag(user_id, "coffee", merge("coffee")).await), "same_tag");
+            assert_eq!(validation_code(service.merge_tag(user_id, "coffee", merge("茶")).await), "not_found");
+            let merged = service.merge_tag(user_id, "coffee", merge("咖啡")).await.unwrap();
+            assert_eq!(merged.updated_transactions, 3);
+            assert_eq!(merged.tag.usage_count, 4);
+            let both = state.storage.find_transaction(transactions[1]).await.unwrap().unwrap();
+            assert_eq!(both.tags, vec!["咖啡".to_string()]);
+
+            let tags: Vec<(String, u64)> = service
+                .get_tags(user_id, TagQuery::default())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|tag| (tag.name, tag.usage_count))
+                .collect();
+            assert_eq!(tags, vec![("咖啡".to_string(), 4), ("早饭".to_string(), 1)]);
+
+            // 默认只统计支出，一笔交易计入它的每个标签
+            let spending = service.get_tag_spending(user_id, TagSpendingQuery::default()).await.unwrap();
+            let totals: Vec<(&str, Decimal, u64)> = spending
+                .tags
+                .iter()
+                .map(|t| (t.tag.as_str(), t.total, t.transaction_count))
+                .collect();
+            assert_eq!(totals, vec![("咖啡", dec("35"), 3), ("早饭", dec("10"), 1)]);
+        }
+        let _ = std::fs::remove_file(path);
+    }
+
+    fn schedule(frequency: RecurrenceFrequency, interval: u32, start_date: &str) -> RecurrenceSchedule {
+        normalize_schedule(RecurrenceSchedule {
+            frequency,
+            interval,
+            day_of_month: None,
+            last_business_day: false,
+            start_date: start_date.parse().unwrap(),
+            end_date: None,
+            count: None,
+        })
+    }
+
+    #[test]
+    fn next_after_matches_occurrences() {
+        let mut schedules = vec![
+            schedule(RecurrenceFrequency::Daily, 3, "2026-01-30"),
+            schedule(RecurrenceFrequency::Weekly, 2, "2026-02-27"),
+            schedule(RecurrenceFrequency::Monthly, 1, "2026-01-31"),
+            schedule(RecurrenceFrequency::Monthly, 5, "2024-02-29"),
+        ];
+        let mut business = schedule(RecurrenceFrequency::Monthly, 1, "2026-01-15");
+        business.day_of_month = None;
+        business.last_business_day = true;
+        schedules.push(business);
+        let mut limited = schedule(RecurrenceFrequency::Monthly, 1, "2026-01-10");
+        limited.day_of_month = Some(5);
+        limited.count = Some(7);
+        schedules.push(limited);
+        let mut ended = schedule(RecurrenceFrequency::Weekly, 1, "2026-01-01");
+        ended.end_date = Some("2026-03-01".parse().unwrap());
+        schedules.push(ended);
+
+        for schedule in schedules {
+            let expected: Vec<NaiveDate> = schedule.occurrences().take(60).collect();
+            let mut actual = Vec::new();
+            let mut next = schedule.occurrences().next();
+            while let Some(date) = next.filter(|_| actual.len() < 60) {
+                actual.push(date);
+                next = schedule.next_after(date, actual.len() as u32);
+            }
+            assert_eq!(actual, expected, "{:?}", schedule);
+        }
+    }
+
+    #[tokio::test]
+    async fn recurring_catch_up_is_capped_per_request() {
+        let state = test_state();
+        let user_id = test_user(&state).await;
+        let account = test_account(&state, user_id, "CNY", "0").await;
+        let today = Utc::now().date_naive();
+        let start_date = today - Duration::days(MAX_CATCH_UP_OCCURRENCES as i64 + 49);
+        let recurring = RecurringTransactionService::new(state.clone())
+            .create_recurring(
+                user_id,
+                CreateRecurringTransactionRequest {
+                    template: CreateTransactionRequest {
+                        account_id: account.id,
+                        category_id: None,
+                        transaction_type: TransactionType::Expense,
+                        amount: dec("1"),
+                        description: "每日".to_string(),
+                        notes: None,
+                        tags: None,
+                        transaction_date: None,
+                    },
+                    schedule: schedule(RecurrenceFrequency::Daily, 1, &start_date.to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(recurring.occurrence_count as usize, MAX_CATCH_UP_OCCURRENCES);
+        assert_eq!(recurring.next_date, Some(start_date + Duration::days(MAX_CATCH_UP_OCCURRENCES as i64)));
+
+        let posted = RecurringTransactionService::new(state.clone()).materialize_due(today).await.unwrap();
+        assert_eq!(posted, 50);
+        let balance = AccountService::new(state.clone()).get_account(user_id, account.id).await.unwrap().balance;
+        assert_eq!(balance, Decimal::from(-(MAX_CATCH_UP_OCCURRENCES as i64 + 50)));
+    }
+
+    #[tokio::test]
+    async fn due_reminders_are_sent_once() {
+        let (sqlite, path) = sqlite_state().await;
+        for state in [test_state(), sqlite] {
+            let user_id = test_user(&state).await;
+            let account = test_account(&state, user_id, "CNY", "0").await;
+            let today = Utc::now().date_naive();
+            let due_date = today + Duration::days(2);
+            for (transaction_type, description) in [(TransactionType::Expense, "房租"), (TransactionType::Income, "工资")] {
+                RecurringTransactionService::new(state.clone())
+                    .create_recurring(
+                        user_id,
+                        CreateRecurringTransactionRequest {
+                            template: CreateTransactionRequest {
+                                account_id: account.id,
+                                category_id: None,
+                                transaction_type,
+                                amount: dec("3000"),
+                                description: description.to_string(),
+                                notes: None,
+                                tags: None,
+                                transaction_date: None,
+                            },
+                            schedule: schedule(RecurrenceFrequency::Monthly, 1, &due_date.to_string()),
+                        },
+                    )
+                    .await
+                    .unwrap();
+            }
+
+            // 后台任务重复运行，或第二天再次运行时同一款项仍在提醒范围内
+            let service = NotificationService::new(state.clone());
+            assert_eq!(service.send_due_reminders(today).await.unwrap(), 1);
+            assert_eq!(service.send_due_reminders(today).await.unwrap(), 0);
+            assert_eq!(service.send_due_reminders(today + Duration::days(1)).await.unwrap(), 0);
+
+            let notifications = service.get_notifications(user_id, NotificationQuery { unread_only: None }).await.unwrap();
+            assert_eq!(notifications.len(), 1);
+            assert_eq!((notifications[0].title.as_str(), notifications[0].due_date), ("房租", due_date));
+        }
+        let _ = std::fs::remove_file(path);
+    }
+
+    fn loan(principal: &str, annual_interest_rate: &str, term_months: u32, method: RepaymentMethod) -> LoanTerms {
+        LoanTerms {
+            principal: dec(principal),
+            annual_interest_rate: dec(annual_interest_rate),
+            term_months,
+            first_payment_date: "2026-01-31".parse().unwrap(),
+            repayment_method: method,
+        }
+    }
+
+    #[test]
+    fn amortization_rows_repay_the_principal() {
+        let terms = loan("12000", "0.12", 12, RepaymentMethod::EqualInstallment);
+        let rows = amortization_rows(&terms, Decimal::ZERO, 2);
+        assert_eq!(rows.len(), 12);
+        assert_eq!(rows.iter().map(|row| row.principal).sum::<Decimal>(), dec("12000"));
+        assert_eq!(rows[11].remaining_principal, Decimal::ZERO);
+        // 每期 12000·0.01·1.01¹² / (1.01¹² − 1) ≈ 1066.19，第一期利息为 12000 × 1%
+        assert_eq!((rows[0].payment, rows[0].interest), (dec("1066.19"), dec("120")));
+        assert!(rows[..11].iter().all(|row| row.payment == dec("1066.19")));
+        assert_eq!(rows[11].date, "2026-12-31".parse::<NaiveDate>().unwrap());
+        assert!(rows.iter().all(|row| !row.is_paid));
+    }
+
+    #[test]
+    fn amortization_rounding_goes_to_the_last_row() {
+        for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+            let rows = amortization_rows(&loan("1000", "0", 3, method), Decimal::ZERO, 2);
+            let principals: Vec<Decimal> = rows.iter().map(|row| row.principal).collect();
+            assert_eq!(principals, vec![dec("333.33"), dec("333.33"), dec("333.34")]);
+            assert!(rows.iter().all(|row| row.interest.is_zero() && row.payment == row.principal));
+        }
+
+        let rows = amortization_rows(&loan("1000", "0.06", 3, RepaymentMethod::EqualPrincipal), Decimal::ZERO, 2);
+        let interests: Vec<Decimal> = rows.iter().map(|row| row.interest).collect();
+        assert_eq!(interests, vec![dec("5"), dec("3.33"), dec("1.67")]);
+        assert_eq!(rows.iter().map(|row| row.principal).sum::<Decimal>(), dec("1000"));
+    }
+
+    #[test]
+    fn prepaid_principal_marks_rows_paid() {
+        let terms = loan("1200", "0", 12, RepaymentMethod::EqualPrincipal);
+        let unpaid = |paid: &str| {
+            amortization_rows(&terms, dec(paid), 2)
+                .iter()
+                .filter(|row| !row.is_paid)
+                .count()
+        };
+        assert_eq!(unpaid("0"), 12);
+        assert_eq!(unpaid("300"), 9);
+        // 多还的本金不足一期时不计为已还
+        assert_eq!(unpaid("350"), 9);
+        assert_eq!(unpaid("700"), 5);
+        assert_eq!(unpaid("1200"), 0);
+    }
+
+    fn investment_event(
+        day: u32,
+        event_type: InvestmentEventType,
+        quantity: Option<&str>,
+        price: Option<&str>,
+        amount: Option<&str>,
+        fee: &str,
+    ) -> InvestmentEvent {
+        let event_date = NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_time(NaiveTime::MIN).and_utc();
+        InvestmentEvent {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            account_id: Uuid::nil(),
+            symbol: "AAPL".to_string(),
+            asset_class: AssetClass::Stock,
+            event_type,
+            event_date,
+            quantity: quantity.map(dec),
+            price: price.map(dec),
+            amount: amount.map(dec),
+            fee: dec(fee),
+            notes: None,
+            created_at: event_date,
+            updated_at: event_date,
+        }
+    }
+
+    // 10 股 @100，10 股 @120（手续费 2），卖出 15 股 @130（手续费 1）
+    fn partial_lot_events() -> Vec<InvestmentEvent> {
+        vec![
+            investment_event(1, InvestmentEventType::Buy, Some("10"), Some("100"), None, "0"),
+            investment_event(2, InvestmentEventType::Buy, Some("10"), Some("120"), None, "2"),
+            investment_event(3, InvestmentEventType::Sell, Some("15"), Some("130"), None, "1"),
+        ]
+    }
+
+    #[test]
+    fn fifo_sell_splits_the_second_lot() {
+        let mut events = partial_lot_events();
+        events.push(investment_event(4, InvestmentEventType::Split, Some("2"), None, None, "0"));
+        events.push(investment_event(5, InvestmentEventType::Dividend, None, None, Some("10"), "1"));
+        let (holdings, gains) = replay_investments(&events, CostBasisMethod::Fifo, 2).unwrap();
+
+        // 卖出成本为第一批 1000 加第二批 1202 的一半
+        assert_eq!(gains.len(), 1);
+        let gain = &gains[0];
+        assert_eq!(
+            (gain.quantity, gain.proceeds, gain.cost_basis, gain.gain),
+            (dec("15"), dec("1949"), dec("1601"), dec("348"))
+        );
+
+        let holding = &holdings[0];
+        assert_eq!((holding.quantity, holding.cost_basis), (dec("10"), dec("601")));
+        assert_eq!((holding.realized_gain, holding.dividends), (dec("348"), dec("9")));
+        assert_eq!(holding.lots.len(), 1);
+        let lot = &holding.lots[0];
+        assert_eq!(
+            (lot.event_id, lot.quantity, lot.unit_cost, lot.cost_basis),
+            (events[1].id, dec("10"), dec("60.1"), dec("601"))
+        );
+    }
+
+    #[test]
+    fn average_cost_sell_uses_the_moving_average() {
+        let events = partial_lot_events();
+        let (holdings, gains) = replay_investments(&events, CostBasisMethod::AverageCost, 2).unwrap();
+
+        // 平均成本 2202 / 20 = 110.1
+        assert_eq!((gains[0].cost_basis, gains[0].gain), (dec("1651.5"), dec("297.5")));
+        let holding = &holdings[0];
+        assert_eq!(
+            (holding.quantity, holding.cost_basis, holding.average_cost),
+            (dec("5"), dec("550.5"), dec("110.1"))
+        );
+        assert_eq!((holding.lots[0].unit_cost, holding.lots[0].cost_basis), (dec("110.1"), dec("550.5")));
+    }
+
+    #[test]
+    fn overselling_returns_the_sell_event() {
+        let events = vec![
+            investment_event(1, InvestmentEventType::Buy, Some("5"), Some("100"), None, "0"),
+            investment_event(2, InvestmentEventType::Sell, Some("3"), Some("110"), None, "0"),
+            investment_event(3, InvestmentEventType::Sell, Some("2.5"), Some("120"), None, "0"),
+        ];
+        for method in [CostBasisMethod::Fifo, CostBasisMethod::AverageCost] {
+            let result = replay_investments(&events, method, 2);
+            assert!(matches!(result, Err(ReplayError::Oversold(sell)) if sell.id == events[2].id));
+        }
+    }
+
+    #[test]
+    fn oversized_investment_events_are_rejected() {
+        let mut v = Validator::new();
+        v.investment_event(&investment_event(1, InvestmentEventType::Buy, Some("1e12"), Some("1e12"), None, "1e15"));
+        let codes = match v.finish() {
+            Err(ServiceError::Validation(errors)) => errors
+                .into_iter()
+                .map(|error| (error.field, error.code))
+                .collect::<Vec<_>>(),
+            other => panic!("unexpected {:?}", other),
+        };
+        for field in ["quantity", "price", "fee"] {
+            assert!(codes.contains(&(field.to_string(), "too_large".to_string())), "{}", field);
+        }
+
+        // 已保存的事件连续拆股溢出时返回该事件而不是 panic
+        let mut events = vec![investment_event(1, InvestmentEventType::Buy, Some("100000000000"), Some("1"), None, "0")];
+        for day in 2..=3 {
+            events.push(investment_event(day, InvestmentEventType::Split, Some("100000000000"), None, None, "0"));
+        }
+        let result = replay_investments(&events, CostBasisMethod::Fifo, 2);
+        assert!(matches!(result, Err(ReplayError::Overflow(split)) if split.id == events[2].id));
+    }
+
+    #[tokio::test]
+    async fn oversized_prices_do_not_panic_the_valuation() {
+        let request = SecurityPriceRequest {
+            symbol: "AAPL".to_string(),
+            price_date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            price: dec("1000000000000"),
+            currency: "USD".to_string(),
+        };
+        let mut v = Validator::new();
+        request.validate(&mut v);
+        assert!(matches!(v.finish(), Err(ServiceError::Validation(errors)) if errors[0].code == "too_large"));
+
+        // 校验之前保存的异常价格使估值返回错误而不是 panic
+        let state = test_state();
+        let buy = investment_event(1, InvestmentEventType::Buy, Some("100000000000"), Some("1"), None, "0");
+        let prices = vec![SecurityPrice {
+            user_id: Uuid::nil(),
+            symbol: request.symbol,
+            price_date: request.price_date,
+            price: dec("1e20"),
+            currency: request.currency,
+            updated_at: Utc::now(),
+        }];
+        let mut replay = InvestmentReplay::new(CostBasisMethod::Fifo, 2);
+        replay.apply(&buy).unwrap();
+        let mut rates = RateTable::new(ExchangeRateService::new(state.clone()), "USD");
+        let result = value_holdings(&replay, &prices, request.price_date, &mut rates).await;
+        assert!(matches!(result, Err(ServiceError::Internal(_))));
+    }
+
+    fn decimals(values: &[&str]) -> Vec<Decimal> {
+        values.iter().copied().map(dec).collect()
+    }
+
+    #[test]
+    fn time_weighted_return_chains_periods() {
+        // 无现金流时等于区间涨幅
+        assert_eq!(
+            time_weighted_return(&decimals(&["100", "120", "150"]), &decimals(&["0", "0", "0"])),
+            Some(dec("0.5"))
+        );
+        // 第二期流入 50 不计入收益：(150 - 50) / 100 × 165 / 150 - 1
+        assert_eq!(
+            time_weighted_return(&decimals(&["100", "150", "165"]), &decimals(&["0", "50", "0"])),
+            Some(dec("0.1"))
+        );
+        // 期初市值始终为零时无法衡量
+        assert_eq!(time_weighted_return(&decimals(&["0", "50"]), &decimals(&["0", "50"])), None);
+    }
+
+    #[test]
+    fn money_weighted_return_matches_reference_values() {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let one_year = start + Duration::days(365);
+        let two_years = start + Duration::days(730);
+
+        // 一年期单笔投入的 XIRR 等于简单收益率
+        assert_eq!(
+            money_weighted_return(&[start, one_year], &decimals(&["100", "110"]), &decimals(&["0", "0"])),
+            Some(dec("0.1"))
+        );
+        // 两年复利 10%
+        assert_eq!(
+            money_weighted_return(&[start, two_years], &decimals(&["100", "121"]), &decimals(&["0", "0"])),
+            Some(dec("0.1"))
+        );
+        // 一年后追加 100，期末 231 = 100 × 1.1² + 100 × 1.1
+        assert_eq!(
+            money_weighted_return(
+                &[start, one_year, two_years],
+                &decimals(&["100", "210", "231"]),
+                &decimals(&["0", "100", "0"])
+            ),
+            Some(dec("0.1"))
+        );
+    }
+
+    #[test]
+    fn money_weighted_return_without_a_root_is_none() {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let end = start + Duration::days(365);
+        let flows = decimals(&["0", "0"]);
+
+        // 没有投入，或全部亏光没有收回，现金流不变号
+        assert_eq!(money_weighted_return(&[start, end], &decimals(&["0", "0"]), &flows), None);
+        assert_eq!(money_weighted_return(&[start, end], &decimals(&["100", "0"]), &flows), None);
+        // 收益率超出搜索范围
+        assert_eq!(money_weighted_return(&[start, end], &decimals(&["1", "10000000"]), &flows), None);
+    }
+
+    #[tokio::test]
+    async fn accounts_are_scoped_to_their_owner() {
+        let state = test_state();
+        let owner = test_user(&state).await;
+        let other = test_user(&state).await;
+        let account = test_account(&state, owner, "CNY", "0").await;
+        let result = AccountService::new(state.clone()).get_account(other, account.id).await;
+        assert!(matches!(result, Err(ServiceError::AuthorizationFailed)));
+    }
+}
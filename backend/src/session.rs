@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+mod memory;
+mod redis;
+
+pub use self::memory::MemorySessionStore;
+pub use self::redis::RedisSessionStore;
+
+// 会话存储后端类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionBackend {
+    Redis,  // 生产部署，多实例共享会话
+    Memory, // 单元测试，重启后所有会话失效
+}
+
+impl std::str::FromStr for SessionBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "redis" => Ok(SessionBackend::Redis),
+            "memory" => Ok(SessionBackend::Memory),
+            other => Err(format!("unknown session backend: {}", other)),
+        }
+    }
+}
+
+// 根据配置创建会话存储
+pub async fn connect(
+    backend: SessionBackend,
+    redis_url: &str,
+) -> Result<Arc<dyn SessionStore>, SessionError> {
+    let store: Arc<dyn SessionStore> = match backend {
+        SessionBackend::Redis => Arc::new(RedisSessionStore::connect(redis_url).await?),
+        SessionBackend::Memory => Arc::new(MemorySessionStore::new()),
+    };
+    Ok(store)
+}
+
+// 会话存储错误
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error(transparent)]
+    Redis(#[from] ::redis::RedisError),
+}
+
+pub type SessionResult<T> = Result<T, SessionError>;
+
+// 刷新令牌轮换结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotateOutcome {
+    Rotated, // 令牌有效，已替换为新令牌
+    Reused,  // 旧令牌被重复使用，整个会话已被注销
+    Revoked, // 会话不存在、已过期或已注销
+}
+
+// 登录会话存储
+// 每次登录创建一个会话（令牌族），会话中只保存当前有效的刷新令牌 jti
+#[async_trait]
+pub trait SessionStore: Send + Sync + std::fmt::Debug {
+    async fn create_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        refresh_jti: Uuid,
+        ttl_secs: i64,
+    ) -> SessionResult<()>;
+    // 校验并轮换刷新令牌，检测到重复使用时注销整个会话；会话不属于 user_id 时视为不存在
+    async fn rotate_refresh_token(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        presented_jti: Uuid,
+        new_jti: Uuid,
+        ttl_secs: i64,
+    ) -> SessionResult<RotateOutcome>;
+    async fn is_session_active(&self, session_id: Uuid) -> SessionResult<bool>;
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> SessionResult<()>;
+    // 注销用户的所有会话，返回被注销的会话数
+    async fn revoke_all_sessions(&self, user_id: Uuid) -> SessionResult<u64>;
+}
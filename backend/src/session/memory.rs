@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use super::{RotateOutcome, SessionResult, SessionStore};
+
+#[derive(Debug, Clone)]
+struct MemorySession {
+    user_id: Uuid,
+    refresh_jti: Uuid,
+    expires_at: DateTime<Utc>,
+}
+
+// 内存会话存储，用于单元测试和本地演示
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    sessions: RwLock<HashMap<Uuid, MemorySession>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    async fn create_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        refresh_jti: Uuid,
+        ttl_secs: i64,
+    ) -> SessionResult<()> {
+        let mut sessions = self.sessions.write().unwrap();
+        // 顺便清理过期会话
+        let now = Utc::now();
+        sessions.retain(|_, s| s.expires_at > now);
+        sessions.insert(
+            session_id,
+            MemorySession {
+                user_id,
+                refresh_jti,
+                expires_at: now + Duration::seconds(ttl_secs),
+            },
+        );
+        Ok(())
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        presented_jti: Uuid,
+        new_jti: Uuid,
+        ttl_secs: i64,
+    ) -> SessionResult<RotateOutcome> {
+        let mut sessions = self.sessions.write().unwrap();
+        let now = Utc::now();
+        let session = match sessions.get_mut(&session_id) {
+            Some(session) if session.user_id == user_id && session.expires_at > now => session,
+            _ => return Ok(RotateOutcome::Revoked),
+        };
+        if session.refresh_jti != presented_jti {
+            sessions.remove(&session_id);
+            return Ok(RotateOutcome::Reused);
+        }
+        session.refresh_jti = new_jti;
+        session.expires_at = now + Duration::seconds(ttl_secs);
+        Ok(RotateOutcome::Rotated)
+    }
+
+    async fn is_session_active(&self, session_id: Uuid) -> SessionResult<bool> {
+        let sessions = self.sessions.read().unwrap();
+        Ok(sessions
+            .get(&session_id)
+            .is_some_and(|s| s.expires_at > Utc::now()))
+    }
+
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> SessionResult<()> {
+        let mut sessions = self.sessions.write().unwrap();
+        if sessions.get(&session_id).is_some_and(|s| s.user_id == user_id) {
+            sessions.remove(&session_id);
+        }
+        Ok(())
+    }
+
+    async fn revoke_all_sessions(&self, user_id: Uuid) -> SessionResult<u64> {
+        let mut sessions = self.sessions.write().unwrap();
+        let before = sessions.len();
+        sessions.retain(|_, s| s.user_id != user_id);
+        Ok((before - sessions.len()) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reused_refresh_token_revokes_session() {
+        let store = MemorySessionStore::new();
+        let (user_id, session_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        store.create_session(user_id, session_id, first, 60).await.unwrap();
+
+        let outcome = store.rotate_refresh_token(Uuid::new_v4(), session_id, first, second, 60).await.unwrap();
+        assert_eq!(outcome, RotateOutcome::Revoked);
+        let outcome = store.rotate_refresh_token(user_id, session_id, first, second, 60).await.unwrap();
+        assert_eq!(outcome, RotateOutcome::Rotated);
+        let outcome = store.rotate_refresh_token(user_id, session_id, first, third, 60).await.unwrap();
+        assert_eq!(outcome, RotateOutcome::Reused);
+        assert!(!store.is_session_active(session_id).await.unwrap());
+        let outcome = store.rotate_refresh_token(user_id, session_id, second, third, 60).await.unwrap();
+        assert_eq!(outcome, RotateOutcome::Revoked);
+    }
+
+    #[tokio::test]
+    async fn revoke_all_sessions_only_touches_the_user() {
+        let store = MemorySessionStore::new();
+        let (user_id, other_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let other_session = Uuid::new_v4();
+        for _ in 0..2 {
+            store.create_session(user_id, Uuid::new_v4(), Uuid::new_v4(), 60).await.unwrap();
+        }
+        store.create_session(other_id, other_session, Uuid::new_v4(), 60).await.unwrap();
+
+        assert_eq!(store.revoke_all_sessions(user_id).await.unwrap(), 2);
+        assert!(store.is_session_active(other_session).await.unwrap());
+    }
+}
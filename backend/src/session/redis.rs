@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, Script};
+use uuid::Uuid;
+
+use super::{RotateOutcome, SessionResult, SessionStore};
+
+// 会话键: session:{sid} -> hash { user, jti }
+// 用户会话索引: user_sessions:{uid} -> set { sid }
+fn session_key(session_id: Uuid) -> String {
+    format!("session:{}", session_id)
+}
+
+fn user_sessions_key(user_id: Uuid) -> String {
+    format!("user_sessions:{}", user_id)
+}
+
+// 原子地比较并替换刷新令牌 jti，KEYS[1] 为会话键，KEYS[2] 为用户会话索引
+// ARGV: 出示的 jti、新 jti、有效期（秒）、会话 id、用户 id
+// 返回 1 轮换成功，-1 检测到重复使用并已删除会话，0 会话不存在或不属于该用户
+// 轮换后会话的有效期延长，索引的有效期随之延长，否则索引先于会话过期，注销所有会话时找不到该会话
+const ROTATE_SCRIPT: &str = r#"
+local session = redis.call('HMGET', KEYS[1], 'user', 'jti')
+if not session[2] or session[1] ~= ARGV[5] then
+    return 0
+end
+if session[2] ~= ARGV[1] then
+    redis.call('DEL', KEYS[1])
+    redis.call('SREM', KEYS[2], ARGV[4])
+    return -1
+end
+redis.call('HSET', KEYS[1], 'jti', ARGV[2])
+redis.call('EXPIRE', KEYS[1], ARGV[3])
+redis.call('SADD', KEYS[2], ARGV[4])
+if redis.call('TTL', KEYS[2]) < tonumber(ARGV[3]) then
+    redis.call('EXPIRE', KEYS[2], ARGV[3])
+end
+return 1
+"#;
+
+// Redis 会话存储
+#[derive(Clone)]
+pub struct RedisSessionStore {
+    conn: MultiplexedConnection,
+    rotate_script: Script,
+}
+
+impl std::fmt::Debug for RedisSessionStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisSessionStore").finish_non_exhaustive()
+    }
+}
+
+impl RedisSessionStore {
+    pub async fn connect(redis_url: &str) -> SessionResult<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let conn = client.get_multiplexed_async_connection().await?;
+        Ok(Self {
+            conn,
+            rotate_script: Script::new(ROTATE_SCRIPT),
+        })
+    }
+}
+
+#[async_trait]
+impl SessionStore for RedisSessionStore {
+    async fn create_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        refresh_jti: Uuid,
+        ttl_secs: i64,
+    ) -> SessionResult<()> {
+        let mut conn = self.conn.clone();
+        let key = session_key(session_id);
+        let index_key = user_sessions_key(user_id);
+        redis::pipe()
+            .atomic()
+            .hset_multiple(&key, &[("user", user_id.to_string()), ("jti", refresh_jti.to_string())])
+            .ignore()
+            .expire(&key, ttl_secs)
+            .ignore()
+            .sadd(&index_key, session_id.to_string())
+            .ignore()
+            .expire(&index_key, ttl_secs)
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        presented_jti: Uuid,
+        new_jti: Uuid,
+        ttl_secs: i64,
+    ) -> SessionResult<RotateOutcome> {
+        let mut conn = self.conn.clone();
+        let result: i64 = self
+            .rotate_script
+            .key(session_key(session_id))
+            .key(user_sessions_key(user_id))
+            .arg(presented_jti.to_string())
+            .arg(new_jti.to_string())
+            .arg(ttl_secs)
+            .arg(session_id.to_string())
+            .arg(user_id.to_string())
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(match result {
+            1 => RotateOutcome::Rotated,
+            -1 => RotateOutcome::Reused,
+            _ => RotateOutcome::Revoked,
+        })
+    }
+
+    async fn is_session_active(&self, session_id: Uuid) -> SessionResult<bool> {
+        let mut conn = self.conn.clone();
+        Ok(conn.exists(session_key(session_id)).await?)
+    }
+
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> SessionResult<()> {
+        let mut conn = self.conn.clone();
+        let key = session_key(session_id);
+        let owner: Option<String> = conn.hget(&key, "user").await?;
+        if owner.as_deref() != Some(user_id.to_string().as_str()) {
+            return Ok(());
+        }
+        redis::pipe()
+            .atomic()
+            .del(&key)
+            .ignore()
+            .srem(user_sessions_key(user_id), session_id.to_string())
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn revoke_all_sessions(&self, user_id: Uuid) -> SessionResult<u64> {
+        let mut conn = self.conn.clone();
+        let index_key = user_sessions_key(user_id);
+        let session_ids: Vec<String> = conn.smembers(&index_key).await?;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for session_id in &session_ids {
+            pipe.del(format!("session:{}", session_id));
+        }
+        pipe.del(&index_key).ignore();
+        // 每个 DEL 返回实际删除的键数量，已过期的会话不计入
+        let deleted: Vec<u64> = pipe.query_async(&mut conn).await?;
+        Ok(deleted.iter().sum())
+    }
+}
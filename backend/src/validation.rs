@@ -0,0 +1,723 @@
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+
+use crate::models::*;
+use crate::services::ServiceError;
+
+// 名称、标签等字段的长度限制（按字符计）
+pub const MAX_NAME_LEN: usize = 50;
+pub const MAX_DESCRIPTION_LEN: usize = 200;
+pub const MAX_NOTES_LEN: usize = 1000;
+pub const MAX_TAG_LEN: usize = 30;
+pub const MAX_TAGS: usize = 20;
+pub const MAX_ICON_LEN: usize = 64;
+pub const MAX_SYMBOL_LEN: usize = 20;
+
+// 投资数量和价格的小数位数，与数据库 NUMERIC(28,8) 对应
+const QUANTITY_SCALE: u32 = 8;
+// 投资数量和价格整数部分上限，保证二者的乘积不会溢出
+const MAX_QUANTITY_DIGITS: u32 = 12;
+
+// 交易日期最多允许提前录入一年（预约的账单等）
+const MAX_FUTURE_DAYS: i64 = 366;
+
+// 重复规则的间隔上限
+const MAX_RECURRENCE_INTERVAL: u32 = 366;
+
+// 分期期数上限
+const MAX_INSTALLMENT_PERIODS: u32 = 60;
+
+// 贷款期限上限（月）
+const MAX_LOAN_TERM_MONTHS: u32 = 600;
+
+// 金额整数部分上限，与数据库 NUMERIC(28,8) 对应并留有余量
+const MAX_AMOUNT_DIGITS: u32 = 15;
+
+// 汇率上限，现行货币之间的汇率远小于此值，同时保证金额乘以汇率不会溢出
+pub const MAX_EXCHANGE_RATE: u64 = 1_000_000_000;
+
+// 现行 ISO 4217 货币代码
+const ISO_4217_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XCD", "XCG", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+];
+
+pub fn is_valid_currency(code: &str) -> bool {
+    ISO_4217_CODES.contains(&code)
+}
+
+// 货币的小数位数（ISO 4217 minor unit），默认 2 位
+pub fn currency_scale(code: &str) -> u32 {
+    match code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
+
+// 校验错误收集器，一次返回所有字段的错误
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, field: &str, code: &str, message: impl Into<String>) {
+        self.errors.push(FieldError::new(field, code, message));
+    }
+
+    pub fn check(&mut self, ok: bool, field: &str, code: &str, message: impl Into<String>) {
+        if !ok {
+            self.add(field, code, message);
+        }
+    }
+
+    // 去除首尾空白后的长度必须在 [min, max] 之间
+    pub fn length(&mut self, field: &str, value: &str, min: usize, max: usize) {
+        let len = value.trim().chars().count();
+        if len < min {
+            if min == 1 {
+                self.add(field, "required", "不能为空");
+            } else {
+                self.add(field, "too_short", format!("长度不能少于 {} 个字符", min));
+            }
+        } else if len > max {
+            self.add(field, "too_long", format!("长度不能超过 {} 个字符", max));
+        }
+    }
+
+    pub fn currency(&mut self, field: &str, code: &str) {
+        self.check(
+            is_valid_currency(code),
+            field,
+            "invalid_currency",
+            "必须是有效的 ISO 4217 货币代码",
+        );
+    }
+
+    // 金额小数位数不能超过货币允许的精度，末尾的 0 不计入
+    pub fn amount_scale(&mut self, field: &str, amount: Decimal, currency: &str) {
+        self.currency_places(field, amount, currency);
+        self.amount_size(field, amount);
+    }
+
+    pub fn currency_places(&mut self, field: &str, amount: Decimal, currency: &str) {
+        let scale = currency_scale(currency);
+        if amount.normalize().scale() > scale {
+            self.add(
+                field,
+                "invalid_scale",
+                format!("{} 金额最多 {} 位小数", currency, scale),
+            );
+        }
+    }
+
+    pub fn amount_size(&mut self, field: &str, amount: Decimal) {
+        if amount.abs() >= Decimal::from(10u64.pow(MAX_AMOUNT_DIGITS)) {
+            self.add(field, "too_large", "金额过大");
+        }
+    }
+
+    // 必须为正数
+    pub fn positive_amount(&mut self, field: &str, amount: Decimal) {
+        self.check(amount > Decimal::ZERO, field, "not_positive", "金额必须大于 0");
+    }
+
+    pub fn date(&mut self, field: &str, date: DateTime<Utc>) {
+        let earliest = Utc.with_ymd_and_hms(1900, 1, 1, 0, 0, 0).unwrap();
+        if date < earliest {
+            self.add(field, "too_early", "日期不能早于 1900 年");
+        } else if date > Utc::now() + Duration::days(MAX_FUTURE_DAYS) {
+            self.add(field, "too_far_in_future", "日期不能晚于一年以后");
+        }
+    }
+
+    // #RRGGBB 格式的颜色
+    pub fn color(&mut self, field: &str, color: &str) {
+        let valid = color.len() == 7
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+        self.check(valid, field, "invalid_format", "颜色格式应为 #RRGGBB");
+    }
+
+    pub fn tags(&mut self, field: &str, tags: &[String]) {
+        if tags.len() > MAX_TAGS {
+            self.add(field, "too_many", format!("标签不能超过 {} 个", MAX_TAGS));
+        }
+        for (i, tag) in tags.iter().enumerate() {
+            self.length(&format!("{}[{}]", field, i), tag, 1, MAX_TAG_LEN);
+        }
+    }
+
+    // 每月的几号，超过当月天数时按月末处理
+    pub fn day_of_month(&mut self, field: &str, day: u32) {
+        self.check((1..=31).contains(&day), field, "out_of_range", "日期必须在 1 到 31 之间");
+    }
+
+    // 本金精度依赖账户货币，在服务层校验
+    pub fn loan_terms(&mut self, field: &str, terms: &LoanTerms) {
+        let field = |name: &str| format!("{}.{}", field, name);
+        self.positive_amount(&field("principal"), terms.principal);
+        self.check(
+            terms.annual_interest_rate >= Decimal::ZERO && terms.annual_interest_rate < Decimal::ONE,
+            &field("annual_interest_rate"),
+            "out_of_range",
+            "年利率必须在 0 到 1 之间",
+        );
+        self.check(
+            (1..=MAX_LOAN_TERM_MONTHS).contains(&terms.term_months),
+            &field("term_months"),
+            "out_of_range",
+            format!("期限必须在 1 到 {} 个月之间", MAX_LOAN_TERM_MONTHS),
+        );
+        self.date(&field("first_payment_date"), terms.first_payment_date.and_time(NaiveTime::MIN).and_utc());
+    }
+
+    // 各类投资事件必填和不允许的字段及数值范围；金额和手续费的精度依赖账户货币，在服务层校验
+    pub fn investment_event(&mut self, event: &InvestmentEvent) {
+        let (quantity, price, amount) = match event.event_type {
+            InvestmentEventType::Buy | InvestmentEventType::Sell => (true, true, false),
+            InvestmentEventType::Dividend => (false, false, true),
+            InvestmentEventType::Split => (true, false, false),
+        };
+        for (field, value, allowed) in [
+            ("quantity", event.quantity, quantity),
+            ("price", event.price, price),
+            ("amount", event.amount, amount),
+        ] {
+            match value {
+                None if allowed => self.add(field, "required", "该类事件必须指定"),
+                Some(_) if !allowed => self.add(field, "not_allowed", "该类事件不能指定"),
+                _ => {}
+            }
+        }
+        let max_quantity = Decimal::from(10u64.pow(MAX_QUANTITY_DIGITS));
+        if let Some(quantity) = event.quantity {
+            self.check(quantity > Decimal::ZERO, "quantity", "not_positive", "数量必须大于 0");
+            self.check(
+                quantity.normalize().scale() <= QUANTITY_SCALE,
+                "quantity",
+                "invalid_scale",
+                format!("数量最多 {} 位小数", QUANTITY_SCALE),
+            );
+            self.check(quantity < max_quantity, "quantity", "too_large", "数量过大");
+        }
+        if let Some(price) = event.price {
+            self.check(price >= Decimal::ZERO, "price", "negative", "价格不能为负");
+            self.check(
+                price.normalize().scale() <= QUANTITY_SCALE,
+                "price",
+                "invalid_scale",
+                format!("价格最多 {} 位小数", QUANTITY_SCALE),
+            );
+            self.check(price < max_quantity, "price", "too_large", "价格过大");
+        }
+        if let Some(amount) = event.amount {
+            self.positive_amount("amount", amount);
+            self.amount_size("amount", amount);
+        }
+        self.check(event.fee >= Decimal::ZERO, "fee", "negative", "手续费不能为负");
+        self.amount_size("fee", event.fee);
+        self.check(
+            event.event_type != InvestmentEventType::Split || event.fee.is_zero(),
+            "fee",
+            "not_allowed",
+            "拆股不能指定手续费",
+        );
+    }
+
+    // 每月的日期和最后一个工作日只适用于 Monthly，二者不能同时指定
+    pub fn schedule(&mut self, field: &str, schedule: &RecurrenceSchedule) {
+        let field = |name: &str| format!("{}.{}", field, name);
+        self.check(
+            (1..=MAX_RECURRENCE_INTERVAL).contains(&schedule.interval),
+            &field("interval"),
+            "out_of_range",
+            format!("间隔必须在 1 到 {} 之间", MAX_RECURRENCE_INTERVAL),
+        );
+        let monthly = schedule.frequency == RecurrenceFrequency::Monthly;
+        if let Some(day) = schedule.day_of_month {
+            if !monthly {
+                self.add(&field("day_of_month"), "not_allowed", "只有按月重复时可以指定日期");
+            } else if schedule.last_business_day {
+                self.add(&field("day_of_month"), "conflict", "不能与最后一个工作日同时指定");
+            } else {
+                self.day_of_month(&field("day_of_month"), day);
+            }
+        }
+        if schedule.last_business_day && !monthly {
+            self.add(&field("last_business_day"), "not_allowed", "只有按月重复时可以指定最后一个工作日");
+        }
+        if let Some(end_date) = schedule.end_date {
+            self.check(
+                end_date >= schedule.start_date,
+                &field("end_date"),
+                "invalid_range",
+                "结束日期不能早于开始日期",
+            );
+        }
+        if let Some(count) = schedule.count {
+            self.check(count > 0, &field("count"), "out_of_range", "次数必须大于 0");
+        }
+        self.date(&field("start_date"), schedule.start_date.and_time(NaiveTime::MIN).and_utc());
+    }
+
+    pub fn finish(self) -> Result<(), ServiceError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ServiceError::Validation(self.errors))
+        }
+    }
+}
+
+// 请求模型的格式校验，不访问存储；账户是否存在等由服务层检查
+pub trait Validate {
+    fn validate(&self, v: &mut Validator);
+}
+
+impl Validate for CreateUserRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.length("username", &self.username, 3, 32);
+        v.check(
+            self.username
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')),
+            "username",
+            "invalid_format",
+            "只能包含字母、数字、下划线、连字符和点",
+        );
+        v.length("email", &self.email, 3, 254);
+        v.check(
+            self.email
+                .split_once('@')
+                .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.')),
+            "email",
+            "invalid_format",
+            "邮箱格式不正确",
+        );
+        v.length("display_name", &self.display_name, 1, MAX_NAME_LEN);
+        if let Some(base_currency) = &self.base_currency {
+            v.currency("base_currency", &base_currency.trim().to_ascii_uppercase());
+        }
+        // 密码不去除空白，直接按字符计
+        let password_len = self.password.chars().count();
+        if password_len < 8 {
+            v.add("password", "too_short", "密码长度不能少于 8 个字符");
+        } else if password_len > 128 {
+            v.add("password", "too_long", "密码长度不能超过 128 个字符");
+        }
+    }
+}
+
+impl Validate for UpdateUserRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(display_name) = &self.display_name {
+            v.length("display_name", display_name, 1, MAX_NAME_LEN);
+        }
+        if let Some(avatar_url) = &self.avatar_url {
+            v.length("avatar_url", avatar_url, 0, 500);
+        }
+        if let Some(base_currency) = &self.base_currency {
+            v.currency("base_currency", &base_currency.trim().to_ascii_uppercase());
+        }
+    }
+}
+
+impl Validate for LoginRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(!self.username.is_empty(), "username", "required", "不能为空");
+        v.check(!self.password.is_empty(), "password", "required", "不能为空");
+    }
+}
+
+impl Validate for RefreshTokenRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(!self.refresh_token.is_empty(), "refresh_token", "required", "不能为空");
+    }
+}
+
+impl Validate for CreateAccountRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.length("name", &self.name, 1, MAX_NAME_LEN);
+        let currency = self.currency.trim().to_ascii_uppercase();
+        v.currency("currency", &currency);
+        if let Some(initial_balance) = self.initial_balance {
+            // 信用卡等账户的初始余额可以为负
+            if is_valid_currency(&currency) {
+                v.amount_scale("initial_balance", initial_balance, &currency);
+            }
+        }
+        if let Some(credit_limit) = self.credit_limit {
+            v.positive_amount("credit_limit", credit_limit);
+        }
+        if let Some(day) = self.statement_closing_day {
+            v.day_of_month("statement_closing_day", day);
+        }
+        if let Some(day) = self.payment_due_day {
+            v.day_of_month("payment_due_day", day);
+        }
+        if let Some(loan) = &self.loan {
+            v.loan_terms("loan", loan);
+        }
+    }
+}
+
+impl Validate for CreateBudgetRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.length("name", &self.name, 1, MAX_NAME_LEN);
+        v.positive_amount("amount", self.amount);
+        if let Some(currency) = &self.currency {
+            v.currency("currency", &currency.trim().to_ascii_uppercase());
+        }
+        if let Some(tag) = &self.tag {
+            v.length("tag", tag, 1, MAX_TAG_LEN);
+        }
+        v.check(
+            self.category_id.is_some() != self.tag.is_some(),
+            "category_id",
+            "invalid_scope",
+            "分类和标签必须且只能指定一个",
+        );
+    }
+}
+
+impl Validate for UpdateBudgetRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(name) = &self.name {
+            v.length("name", name, 1, MAX_NAME_LEN);
+        }
+        if let Some(amount) = self.amount {
+            v.positive_amount("amount", amount);
+        }
+    }
+}
+
+impl Validate for CreateCategoryRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.length("name", &self.name, 1, MAX_NAME_LEN);
+        if let Some(icon) = &self.icon {
+            v.length("icon", icon, 1, MAX_ICON_LEN);
+        }
+        if let Some(color) = &self.color {
+            v.color("color", color);
+        }
+        v.check(
+            self.transaction_type != TransactionType::Transfer,
+            "transaction_type",
+            "not_allowed",
+            "转账不使用分类",
+        );
+    }
+}
+
+impl Validate for UpdateCategoryRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(name) = &self.name {
+            v.length("name", name, 1, MAX_NAME_LEN);
+        }
+        if let Some(icon) = &self.icon {
+            v.length("icon", icon, 1, MAX_ICON_LEN);
+        }
+        if let Some(color) = &self.color {
+            v.color("color", color);
+        }
+    }
+}
+
+// 目标分类是否存在、能否合并在服务层校验
+impl Validate for MergeCategoryRequest {
+    fn validate(&self, _v: &mut Validator) {}
+}
+
+impl Validate for RenameTagRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.length("name", &self.name, 1, MAX_TAG_LEN);
+    }
+}
+
+impl Validate for MergeTagRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.length("target", &self.target, 1, MAX_TAG_LEN);
+    }
+}
+
+impl Validate for UpdateAccountRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(name) = &self.name {
+            v.length("name", name, 1, MAX_NAME_LEN);
+        }
+        if let Some(Some(credit_limit)) = self.credit_limit {
+            v.positive_amount("credit_limit", credit_limit);
+        }
+        if let Some(Some(day)) = self.statement_closing_day {
+            v.day_of_month("statement_closing_day", day);
+        }
+        if let Some(Some(day)) = self.payment_due_day {
+            v.day_of_month("payment_due_day", day);
+        }
+        if let Some(Some(loan)) = &self.loan {
+            v.loan_terms("loan", loan);
+        }
+    }
+}
+
+// 金额精度依赖账户货币，在服务层校验
+impl Validate for CreateTransactionRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            self.transaction_type != TransactionType::Transfer,
+            "transaction_type",
+            "use_transfer_endpoint",
+            "转账请通过 /api/transfers 创建",
+        );
+        v.positive_amount("amount", self.amount);
+        v.length("description", &self.description, 1, MAX_DESCRIPTION_LEN);
+        if let Some(notes) = &self.notes {
+            v.length("notes", notes, 0, MAX_NOTES_LEN);
+        }
+        if let Some(tags) = &self.tags {
+            v.tags("tags", tags);
+        }
+        if let Some(transaction_date) = self.transaction_date {
+            v.date("transaction_date", transaction_date);
+        }
+    }
+}
+
+// 交易日期由重复规则决定
+impl Validate for CreateRecurringTransactionRequest {
+    fn validate(&self, v: &mut Validator) {
+        self.template.validate(v);
+        v.check(
+            self.template.transaction_date.is_none(),
+            "transaction_date",
+            "not_allowed",
+            "重复交易的日期由重复规则决定",
+        );
+        v.schedule("schedule", &self.schedule);
+    }
+}
+
+impl Validate for UpdateRecurringTransactionRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(amount) = self.amount {
+            v.positive_amount("amount", amount);
+        }
+        if let Some(description) = &self.description {
+            v.length("description", description, 1, MAX_DESCRIPTION_LEN);
+        }
+        if let Some(Some(notes)) = &self.notes {
+            v.length("notes", notes, 0, MAX_NOTES_LEN);
+        }
+        if let Some(tags) = &self.tags {
+            v.tags("tags", tags);
+        }
+        if let Some(schedule) = &self.schedule {
+            v.schedule("schedule", schedule);
+        }
+    }
+}
+
+// 手续费金额的精度依赖账户货币，在服务层校验
+impl Validate for CreateInstallmentPlanRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            (2..=MAX_INSTALLMENT_PERIODS).contains(&self.periods),
+            "periods",
+            "out_of_range",
+            format!("期数必须在 2 到 {} 之间", MAX_INSTALLMENT_PERIODS),
+        );
+        if let Some(fee_per_period) = self.fee_per_period {
+            v.check(fee_per_period >= Decimal::ZERO, "fee_per_period", "negative", "手续费不能为负");
+        }
+        if let Some(fee_rate) = self.fee_rate {
+            v.check(
+                fee_rate >= Decimal::ZERO && fee_rate < Decimal::ONE,
+                "fee_rate",
+                "out_of_range",
+                "费率必须在 0 到 1 之间",
+            );
+        }
+        v.check(
+            self.fee_per_period.is_none() || self.fee_rate.is_none(),
+            "fee_rate",
+            "conflict",
+            "不能与 fee_per_period 同时指定",
+        );
+        if let Some(first_period_date) = self.first_period_date {
+            v.date("first_period_date", first_period_date.and_time(NaiveTime::MIN).and_utc());
+        }
+    }
+}
+
+impl Validate for PayoffInstallmentPlanRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(date) = self.date {
+            v.date("date", date.and_time(NaiveTime::MIN).and_utc());
+        }
+    }
+}
+
+impl Validate for LoanPaymentRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(amount) = self.amount {
+            v.positive_amount("amount", amount);
+        }
+        if let Some(interest) = self.interest {
+            v.check(interest >= Decimal::ZERO, "interest", "negative", "利息不能为负");
+        }
+        if let (Some(amount), Some(interest)) = (self.amount, self.interest) {
+            v.check(interest <= amount, "interest", "exceeds_amount", "利息不能超过还款金额");
+        }
+        if let Some(description) = &self.description {
+            v.length("description", description, 1, MAX_DESCRIPTION_LEN);
+        }
+        if let Some(payment_date) = self.payment_date {
+            v.date("payment_date", payment_date);
+        }
+    }
+}
+
+// 事件的数量、价格和金额在服务层与原事件合并后校验
+impl Validate for CreateInvestmentEventRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.length("symbol", &self.symbol, 1, MAX_SYMBOL_LEN);
+        v.date("event_date", self.event_date);
+        if let Some(notes) = &self.notes {
+            v.length("notes", notes, 0, MAX_NOTES_LEN);
+        }
+    }
+}
+
+impl Validate for UpdateInvestmentEventRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(event_date) = self.event_date {
+            v.date("event_date", event_date);
+        }
+        if let Some(Some(notes)) = &self.notes {
+            v.length("notes", notes, 0, MAX_NOTES_LEN);
+        }
+    }
+}
+
+impl Validate for SecurityPriceRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.length("symbol", &self.symbol, 1, MAX_SYMBOL_LEN);
+        v.date("price_date", self.price_date.and_time(NaiveTime::MIN).and_utc());
+        v.positive_amount("price", self.price);
+        v.check(
+            self.price.normalize().scale() <= QUANTITY_SCALE,
+            "price",
+            "invalid_scale",
+            format!("价格最多 {} 位小数", QUANTITY_SCALE),
+        );
+        v.check(
+            self.price < Decimal::from(10u64.pow(MAX_QUANTITY_DIGITS)),
+            "price",
+            "too_large",
+            "价格过大",
+        );
+        v.currency("currency", &self.currency.trim().to_ascii_uppercase());
+    }
+}
+
+// 转入金额和汇率必须为正，手续费可以为 0
+fn transfer_amounts(v: &mut Validator, to_amount: Option<Decimal>, exchange_rate: Option<Decimal>, fee: Option<Decimal>) {
+    if let Some(to_amount) = to_amount {
+        v.positive_amount("to_amount", to_amount);
+    }
+    if let Some(exchange_rate) = exchange_rate {
+        v.check(exchange_rate > Decimal::ZERO, "exchange_rate", "not_positive", "汇率必须大于 0");
+        v.check(
+            exchange_rate <= Decimal::from(MAX_EXCHANGE_RATE),
+            "exchange_rate",
+            "too_large",
+            "汇率过大",
+        );
+    }
+    if let Some(fee) = fee {
+        v.check(fee >= Decimal::ZERO, "fee", "negative", "手续费不能为负");
+    }
+}
+
+impl Validate for CreateTransferRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            self.from_account_id != self.to_account_id,
+            "to_account_id",
+            "same_account",
+            "转出和转入账户不能相同",
+        );
+        v.positive_amount("amount", self.amount);
+        transfer_amounts(v, self.to_amount, self.exchange_rate, self.fee);
+        v.length("description", &self.description, 1, MAX_DESCRIPTION_LEN);
+        if let Some(notes) = &self.notes {
+            v.length("notes", notes, 0, MAX_NOTES_LEN);
+        }
+        if let Some(tags) = &self.tags {
+            v.tags("tags", tags);
+        }
+        if let Some(transfer_date) = self.transfer_date {
+            v.date("transfer_date", transfer_date);
+        }
+    }
+}
+
+impl Validate for UpdateTransferRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(amount) = self.amount {
+            v.positive_amount("amount", amount);
+        }
+        transfer_amounts(v, self.to_amount, self.exchange_rate, self.fee);
+        if let Some(description) = &self.description {
+            v.length("description", description, 1, MAX_DESCRIPTION_LEN);
+        }
+        if let Some(notes) = &self.notes {
+            v.length("notes", notes, 0, MAX_NOTES_LEN);
+        }
+        if let Some(tags) = &self.tags {
+            v.tags("tags", tags);
+        }
+        if let Some(transfer_date) = self.transfer_date {
+            v.date("transfer_date", transfer_date);
+        }
+    }
+}
+
+impl Validate for UpdateTransactionRequest {
+    fn validate(&self, v: &mut Validator) {
+        if let Some(amount) = self.amount {
+            v.positive_amount("amount", amount);
+        }
+        if let Some(description) = &self.description {
+            v.length("description", description, 1, MAX_DESCRIPTION_LEN);
+        }
+        if let Some(notes) = &self.notes {
+            v.length("notes", notes, 0, MAX_NOTES_LEN);
+        }
+        if let Some(tags) = &self.tags {
+            v.tags("tags", tags);
+        }
+        if let Some(transaction_date) = self.transaction_date {
+            v.date("transaction_date", transaction_date);
+        }
+    }
+}
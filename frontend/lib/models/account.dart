@@ -21,6 +21,10 @@ enum AccountType {
   investment,
   @JsonValue('Crypto')
   crypto,
+  @JsonValue('Loan')
+  loan,
+  @JsonValue('Receivable')
+  receivable,
 }
 
 @JsonSerializable()
@@ -69,6 +73,10 @@ class Account {
         return '投资账户';
       case AccountType.crypto:
         return '加密货币';
+      case AccountType.loan:
+        return '贷款';
+      case AccountType.receivable:
+        return '借出';
     }
   }
 
@@ -36,6 +36,8 @@ const _$AccountTypeEnumMap = {
   AccountType.creditCard: 'CreditCard',
   AccountType.investment: 'Investment',
   AccountType.crypto: 'Crypto',
+  AccountType.loan: 'Loan',
+  AccountType.receivable: 'Receivable',
 };
 
 CreateAccountRequest _$CreateAccountRequestFromJson(